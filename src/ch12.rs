@@ -5,6 +5,7 @@ use crate::domain::material::{Material, Substance};
 use crate::domain::matrix::Matrix;
 use crate::domain::object::{Cube, Plane, Sphere};
 use crate::domain::pattern::Pattern;
+use crate::domain::settings::RenderSettings;
 use crate::domain::world::World;
 use crate::domain::{Point, Vector};
 use std::f64::consts::PI;
@@ -28,13 +29,14 @@ pub fn run() -> Result<(), Error> {
     // render to canvas
     let canvas = world.render(
         &camera,
+        &RenderSettings::default(),
         Arc::new(move |itr: usize, total_size: usize| {
             if ((itr as f64 / total_size as f64) * 100.0) % 10.0 == 0.0 {
                 print!("#");
                 let _ = stdout().flush();
             }
         }),
-    );
+    )?;
 
     println!("{}", "");
     println!("Rendering to file...");
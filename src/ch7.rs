@@ -4,6 +4,7 @@ use crate::domain::light::Light;
 use crate::domain::material::Material;
 use crate::domain::matrix::Matrix;
 use crate::domain::object::{Object, Sphere};
+use crate::domain::settings::RenderSettings;
 use crate::domain::world::World;
 use crate::domain::{Point, Vector};
 use std::f64::consts::PI;
@@ -116,13 +117,14 @@ pub fn run() -> Result<(), Error> {
     // canvas
     let canvas = world.render(
         &camera,
+        &RenderSettings::default(),
         Arc::new(move |itr: usize, total_size: usize| {
             if ((itr as f64 / total_size as f64) * 100.0) % 10.0 == 0.0 {
                 print!("#");
                 let _ = stdout().flush();
             }
         }),
-    );
+    )?;
 
     //canvas.invert_y();
     println!("{}", "");
@@ -0,0 +1,388 @@
+use crate::domain::camera::Camera;
+use crate::domain::color::Color;
+use crate::domain::matrix::Matrix;
+use crate::domain::object::Object;
+use crate::domain::world::World;
+use crate::domain::{Id, Point, Vector};
+
+// How values change between one keyframe and the next.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Easing {
+    Linear,
+    // starts slowly and speeds up
+    EaseIn,
+    // starts quickly and slows down
+    EaseOut,
+    // slow at both ends
+    EaseInOut,
+}
+
+impl Easing {
+    // maps the share of time passed between two keyframes to the share of the change made
+    pub fn apply(&self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+// Values that can be blended between keyframes.
+pub trait Interpolate {
+    // t = 0 gives this value and t = 1 the other
+    fn interpolate(&self, other: &Self, t: f64) -> Self;
+}
+
+impl Interpolate for f64 {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Interpolate for Point {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        self + &(&(other - self) * t)
+    }
+}
+
+impl Interpolate for Vector {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        self + &(&(other - self) * t)
+    }
+}
+
+impl Interpolate for Color {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        self + &(&(other - self) * t as f32)
+    }
+}
+
+// Placement of an object split into parts that blend well: a full turn can be keyframed as a
+// rotation from 0 to 2 pi, which interpolating matrices can't do.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Pose {
+    pub translation: Vector,
+    // rotations around the x, y and z axes in radians, applied in that order
+    pub rotation: Vector,
+    pub scaling: Vector,
+}
+
+impl Pose {
+    // constructor
+    pub fn new(translation: Vector, rotation: Vector, scaling: Vector) -> Pose {
+        Pose {
+            translation,
+            rotation,
+            scaling,
+        }
+    }
+
+    // scales, then rotates, then translates
+    pub fn transformation(&self) -> Matrix {
+        let t = &self.translation;
+        let r = &self.rotation;
+        let s = &self.scaling;
+        let rotation = &(&Matrix::new_rotation_z(r.z()) * &Matrix::new_rotation_y(r.y()))
+            * &Matrix::new_rotation_x(r.x());
+        &(&Matrix::new_translation(t.x(), t.y(), t.z()) * &rotation)
+            * &Matrix::new_scaling(s.x(), s.y(), s.z())
+    }
+}
+
+impl Default for Pose {
+    fn default() -> Self {
+        Pose::new(
+            Vector::new(0.0, 0.0, 0.0),
+            Vector::new(0.0, 0.0, 0.0),
+            Vector::new(1.0, 1.0, 1.0),
+        )
+    }
+}
+
+impl Interpolate for Pose {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Pose::new(
+            self.translation.interpolate(&other.translation, t),
+            self.rotation.interpolate(&other.rotation, t),
+            self.scaling.interpolate(&other.scaling, t),
+        )
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Keyframe<T> {
+    pub frame: f64,
+    pub value: T,
+    // how the value changes on the way from the previous keyframe
+    pub easing: Easing,
+}
+
+// Keyframed value over time. Before the first and after the last keyframe the value holds.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Track<T> {
+    keys: Vec<Keyframe<T>>,
+}
+
+pub struct TrackBuilder<T> {
+    keys: Vec<Keyframe<T>>,
+}
+
+impl<T: Interpolate + Clone> Track<T> {
+    // builder
+    pub fn new() -> TrackBuilder<T> {
+        TrackBuilder { keys: Vec::new() }
+    }
+
+    pub fn keys(&self) -> &[Keyframe<T>] {
+        &self.keys
+    }
+
+    // value at the given (possibly fractional) frame, none for a track without keyframes
+    pub fn value_at(&self, frame: f64) -> Option<T> {
+        let first = self.keys.first()?;
+        if frame <= first.frame {
+            return Option::Some(first.value.clone());
+        }
+
+        // the last keyframe at or before the frame, blended into the one after it
+        let next = self.keys.iter().position(|k| k.frame > frame);
+        match next {
+            None => self.keys.last().map(|k| k.value.clone()),
+            Some(index) => {
+                let from = &self.keys[index - 1];
+                let to = &self.keys[index];
+                let t = (frame - from.frame) / (to.frame - from.frame);
+                Option::Some(from.value.interpolate(&to.value, to.easing.apply(t)))
+            }
+        }
+    }
+}
+
+impl<T: Interpolate + Clone> TrackBuilder<T> {
+    // finalizes built instance, keyframes sorted by frame (NaN frames are dropped)
+    pub fn build(&self) -> Track<T> {
+        let mut keys = self.keys.clone();
+        keys.retain(|k| !k.frame.is_nan());
+        keys.sort_by(|a, b| a.frame.total_cmp(&b.frame));
+        Track { keys }
+    }
+
+    // keyframe reached linearly from the previous one
+    pub fn key(&mut self, frame: f64, value: T) -> &mut TrackBuilder<T> {
+        self.eased_key(frame, value, Easing::Linear)
+    }
+
+    pub fn eased_key(&mut self, frame: f64, value: T, easing: Easing) -> &mut TrackBuilder<T> {
+        self.keys.push(Keyframe {
+            frame,
+            value,
+            easing,
+        });
+        self
+    }
+}
+
+// Material values that can be keyframed.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum MaterialValue {
+    Ambient,
+    Diffuse,
+    Specular,
+    Shininess,
+    Reflective,
+    Transparency,
+    Roughness,
+    RefractiveIndex,
+    EmissionStrength,
+}
+
+// A keyframed property of the scene.
+#[derive(PartialEq, Debug, Clone)]
+pub enum Channel {
+    // camera placement, looking from one point at another
+    CameraView {
+        from: Track<Point>,
+        to: Track<Point>,
+        up: Vector,
+    },
+    FieldOfView(Track<f64>),
+    // placement of the object with the given id, together with the motion (in object space) the
+    // object itself makes while the shutter is open, if it has an end transformation of its own
+    Transformation(Id, Track<Pose>, Option<Matrix>),
+    LightPosition(Track<Point>),
+    LightIntensity(Track<Color>),
+    // the material's plain color; a pattern on the material takes precedence and keeps its own
+    // colors
+    MaterialColor(Id, Track<Color>),
+    Material(Id, MaterialValue, Track<f64>),
+}
+
+// Set of keyframed properties posing a scene for every frame of a sequence. Channels for
+// objects or a light that aren't in the world are ignored.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Animation {
+    pub channels: Vec<Channel>,
+}
+
+pub struct AnimationBuilder {
+    channels: Vec<Channel>,
+}
+
+impl Animation {
+    // builder
+    pub fn new() -> AnimationBuilder {
+        AnimationBuilder {
+            channels: Vec::new(),
+        }
+    }
+
+    // Poses the world and camera as they are at the given frame. With the camera's shutter open
+    // for a while, animated objects also get their pose one frame later as end transformation,
+    // so they blur along their motion.
+    pub fn apply(&self, world: &mut World, camera: &mut Camera, frame: f64) {
+        let blurred = camera.shutter_close > camera.shutter_open;
+        for channel in self.channels.iter() {
+            match channel {
+                Channel::CameraView { from, to, up } => {
+                    if let (Some(from), Some(to)) = (from.value_at(frame), to.value_at(frame)) {
+                        camera.transform = Matrix::new_view_transformation(&from, &to, up);
+                    }
+                }
+                Channel::FieldOfView(track) => {
+                    if let Some(field_of_view) = track.value_at(frame) {
+                        camera.set_field_of_view(field_of_view);
+                    }
+                }
+                Channel::Transformation(id, track, motion) => {
+                    let (object, pose) = match (find(world, id), track.value_at(frame)) {
+                        (Some(object), Some(pose)) => (object, pose),
+                        _ => continue,
+                    };
+                    let start = pose.transformation();
+                    let end = if blurred {
+                        track.value_at(frame + 1.0).map(|p| p.transformation())
+                    } else {
+                        Option::None
+                    };
+
+                    // the object's own motion carries on from wherever the animation puts it
+                    let shape = object.shape_mut();
+                    shape.end_transformation = match motion {
+                        Some(motion) => {
+                            Option::Some(&end.unwrap_or_else(|| start.clone()) * motion)
+                        }
+                        None => end,
+                    };
+                    shape.transformation = start;
+                }
+                Channel::LightPosition(track) => {
+                    if let (Some(light), Some(position)) =
+                        (world.light_source.as_mut(), track.value_at(frame))
+                    {
+                        light.position = position;
+                    }
+                }
+                Channel::LightIntensity(track) => {
+                    if let (Some(light), Some(intensity)) =
+                        (world.light_source.as_mut(), track.value_at(frame))
+                    {
+                        light.intensity = intensity;
+                    }
+                }
+                Channel::MaterialColor(id, track) => {
+                    if let (Some(object), Some(color)) = (find(world, id), track.value_at(frame)) {
+                        object.shape_mut().material.color = color;
+                    }
+                }
+                Channel::Material(id, value, track) => {
+                    if let (Some(object), Some(v)) = (find(world, id), track.value_at(frame)) {
+                        let material = &mut object.shape_mut().material;
+                        match value {
+                            MaterialValue::Ambient => material.ambient = v,
+                            MaterialValue::Diffuse => material.diffuse = v,
+                            MaterialValue::Specular => material.specular = v,
+                            MaterialValue::Shininess => material.shininess = v,
+                            MaterialValue::Reflective => material.reflective = v,
+                            MaterialValue::Transparency => material.transparency = v,
+                            MaterialValue::Roughness => material.roughness = v,
+                            MaterialValue::RefractiveIndex => {
+                                material.refractive_index_override = Option::Some(v)
+                            }
+                            MaterialValue::EmissionStrength => material.emission_strength = v,
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl AnimationBuilder {
+    // finalizes built instance
+    pub fn build(&self) -> Animation {
+        Animation {
+            channels: self.channels.clone(),
+        }
+    }
+
+    pub fn channel(&mut self, channel: Channel) -> &mut AnimationBuilder {
+        self.channels.push(channel);
+        self
+    }
+
+    pub fn camera_view(
+        &mut self,
+        from: Track<Point>,
+        to: Track<Point>,
+        up: Vector,
+    ) -> &mut AnimationBuilder {
+        self.channel(Channel::CameraView { from, to, up })
+    }
+
+    pub fn field_of_view(&mut self, track: Track<f64>) -> &mut AnimationBuilder {
+        self.channel(Channel::FieldOfView(track))
+    }
+
+    // the object's own end transformation (if any) is kept as a motion relative to its pose
+    pub fn transformation(&mut self, object: &Object, track: Track<Pose>) -> &mut AnimationBuilder {
+        let shape = object.shape();
+        let motion = match (&shape.end_transformation, shape.transformation.inverse()) {
+            (Some(end), Some(inverse)) => Option::Some(&inverse * end),
+            _ => Option::None,
+        };
+        self.channel(Channel::Transformation(shape.id, track, motion))
+    }
+
+    pub fn light_position(&mut self, track: Track<Point>) -> &mut AnimationBuilder {
+        self.channel(Channel::LightPosition(track))
+    }
+
+    pub fn light_intensity(&mut self, track: Track<Color>) -> &mut AnimationBuilder {
+        self.channel(Channel::LightIntensity(track))
+    }
+
+    // animates the plain color only, patterned materials keep showing their pattern
+    pub fn material_color(
+        &mut self,
+        object: &Object,
+        track: Track<Color>,
+    ) -> &mut AnimationBuilder {
+        self.channel(Channel::MaterialColor(object.shape().id, track))
+    }
+
+    pub fn material(
+        &mut self,
+        object: &Object,
+        value: MaterialValue,
+        track: Track<f64>,
+    ) -> &mut AnimationBuilder {
+        self.channel(Channel::Material(object.shape().id, value, track))
+    }
+}
+
+fn find<'a>(world: &'a mut World, id: &Id) -> Option<&'a mut Object> {
+    world.objects.iter_mut().find(|o| o.shape().id == *id)
+}
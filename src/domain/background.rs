@@ -0,0 +1,122 @@
+use crate::domain::canvas::Canvas;
+use crate::domain::color::Color;
+use crate::domain::pattern::{Addressing, Filtering, ImageTexture, Texture};
+use crate::domain::ramp::ColorRamp;
+use crate::domain::uv::{cube_face, cube_face_uv, equirectangular_map, CubeFace};
+use crate::domain::{Point, Vector};
+use std::sync::Arc;
+
+// What rays see when they leave the scene without hitting anything.
+#[derive(PartialEq, Debug, Clone)]
+pub enum Background {
+    Solid(Color),
+    // ramp sampled by the direction's height, from straight down (0) to straight up (1)
+    Sky(ColorRamp),
+    CubeMap(CubeMap),
+    Equirectangular(ImageTexture),
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        Background::Solid(Color::BLACK)
+    }
+}
+
+impl Background {
+    // sky fading from the horizon color up to the zenith color, with ground below the horizon
+    pub fn new_sky(ground: Color, horizon: Color, zenith: Color) -> Background {
+        Background::Sky(ColorRamp::new(vec![
+            (0.0, ground),
+            (0.5, horizon),
+            (1.0, zenith),
+        ]))
+    }
+
+    // bilinearly filtered latitude/longitude image - clamped so the poles don't bleed into each
+    // other
+    pub fn new_equirectangular(canvas: Arc<Canvas>) -> Background {
+        Background::Equirectangular(ImageTexture::new(
+            canvas,
+            Filtering::Bilinear,
+            Addressing::Clamp,
+        ))
+    }
+
+    // color seen along the given direction
+    pub fn color_at(&self, direction: &Vector) -> Color {
+        match self {
+            Background::Solid(color) => *color,
+            Background::Sky(ramp) => {
+                let height = direction.y() / direction.magnitude();
+                ramp.color_at((height + 1.0) / 2.0)
+            }
+            Background::CubeMap(cube_map) => cube_map.color_at(direction),
+            Background::Equirectangular(texture) => {
+                let (u, v) = equirectangular_map(direction);
+                texture.color_at(&Point::new(u, v, 0.0))
+            }
+        }
+    }
+}
+
+// Six images forming the inside of a cube around the scene. Faces are laid out as seen from the
+// center, the same way `uv::cube_map` unfolds them.
+#[derive(PartialEq, Debug, Clone)]
+pub struct CubeMap {
+    pub left: ImageTexture,
+    pub right: ImageTexture,
+    pub front: ImageTexture,
+    pub back: ImageTexture,
+    pub up: ImageTexture,
+    pub down: ImageTexture,
+}
+
+impl CubeMap {
+    // constructor - faces are bilinearly filtered and clamped at their edges
+    pub fn new(
+        left: Arc<Canvas>,
+        right: Arc<Canvas>,
+        front: Arc<Canvas>,
+        back: Arc<Canvas>,
+        up: Arc<Canvas>,
+        down: Arc<Canvas>,
+    ) -> CubeMap {
+        let face = |canvas| ImageTexture::new(canvas, Filtering::Bilinear, Addressing::Clamp);
+        CubeMap {
+            left: face(left),
+            right: face(right),
+            front: face(front),
+            back: face(back),
+            up: face(up),
+            down: face(down),
+        }
+    }
+
+    pub fn face(&self, face: CubeFace) -> &ImageTexture {
+        match face {
+            CubeFace::Left => &self.left,
+            CubeFace::Right => &self.right,
+            CubeFace::Front => &self.front,
+            CubeFace::Back => &self.back,
+            CubeFace::Up => &self.up,
+            CubeFace::Down => &self.down,
+        }
+    }
+
+    pub fn color_at(&self, direction: &Vector) -> Color {
+        // project the direction onto the unit cube
+        let coord = direction
+            .x()
+            .abs()
+            .max(direction.y().abs().max(direction.z().abs()));
+        let point = Point::new(
+            direction.x() / coord,
+            direction.y() / coord,
+            direction.z() / coord,
+        );
+
+        let face = cube_face(&point);
+        let (u, v) = cube_face_uv(face, &point);
+        self.face(face).color_at(&Point::new(u, v, 0.0))
+    }
+}
@@ -0,0 +1,169 @@
+use crate::domain::noise::Noise;
+use crate::domain::object::Object;
+use crate::domain::pattern::Pattern;
+use crate::domain::random::orthonormal_basis;
+use crate::domain::uv::UvMapping;
+use crate::domain::{Point, Vector};
+
+// Step used for the finite differences below.
+const DELTA: f64 = 0.0001;
+
+// Scalar field whose slopes tilt the surface normal.
+#[derive(PartialEq, Debug, Clone)]
+pub enum HeightField {
+    // fbm noise evaluated in object space, so bumps stick to the object when it moves
+    Noise { noise: Noise, frequency: f64 },
+    // luminance of any pattern
+    Pattern(Pattern),
+}
+
+impl HeightField {
+    // height at the given world point, with the object in its pose at the given time
+    pub fn height_at(&self, object: &Object, world_point: &Point, time: f64) -> f64 {
+        match self {
+            HeightField::Noise { noise, frequency } => {
+                let p = &object.inverse_transformation_at(time) * world_point;
+                noise.fbm(&Point::new(
+                    p.x() * frequency,
+                    p.y() * frequency,
+                    p.z() * frequency,
+                ))
+            }
+            HeightField::Pattern(pattern) => {
+                pattern.color_at_time(object, world_point, time).luminance() as f64
+            }
+        }
+    }
+}
+
+// Per-material change to the geometric normal, applied before shading.
+#[derive(PartialEq, Debug, Clone)]
+pub enum NormalPerturbation {
+    // normals follow the slopes of a height field, scaled by strength
+    Bump { height: HeightField, strength: f64 },
+    // tangent-space normal map: red/green/blue encode the tangent, bitangent and normal
+    // components, with (0.5, 0.5, 1.0) leaving the normal untouched; strength scales the tilt
+    NormalMap { pattern: Pattern, strength: f64 },
+}
+
+impl NormalPerturbation {
+    pub fn new_noise_bump(noise: Noise, frequency: f64, strength: f64) -> NormalPerturbation {
+        NormalPerturbation::Bump {
+            height: HeightField::Noise { noise, frequency },
+            strength,
+        }
+    }
+
+    pub fn new_pattern_bump(pattern: Pattern, strength: f64) -> NormalPerturbation {
+        NormalPerturbation::Bump {
+            height: HeightField::Pattern(pattern),
+            strength,
+        }
+    }
+
+    // the pattern is usually an image texture; its uv mapping (native if unset) defines the
+    // tangent frame
+    pub fn new_normal_map(pattern: Pattern, strength: f64) -> NormalPerturbation {
+        NormalPerturbation::NormalMap { pattern, strength }
+    }
+
+    // perturbs the (normalized, outward) world normal at the given world point, with the object
+    // in its pose at the given time
+    pub fn perturb(
+        &self,
+        object: &Object,
+        world_point: &Point,
+        normal: &Vector,
+        time: f64,
+    ) -> Vector {
+        match self {
+            NormalPerturbation::Bump { height, strength } => {
+                let height_at = |p: Point| height.height_at(object, &p, time);
+                let gradient = Vector::new(
+                    central_difference(height_at, world_point, 0),
+                    central_difference(height_at, world_point, 1),
+                    central_difference(height_at, world_point, 2),
+                );
+
+                // only the slope along the surface tilts the normal
+                let surface_gradient = &gradient - &(normal * gradient.dot_product(normal));
+                (normal - &(&surface_gradient * *strength)).normalize()
+            }
+            NormalPerturbation::NormalMap { pattern, strength } => {
+                let mapping = pattern.mapping().unwrap_or(UvMapping::Native);
+                let (tangent, bitangent) =
+                    match tangent_frame(object, world_point, normal, mapping, time) {
+                        Some(frame) => frame,
+                        None => return *normal,
+                    };
+
+                let color = pattern.color_at_time(object, world_point, time);
+                let x = (2.0 * color.red as f64 - 1.0) * strength;
+                let y = (2.0 * color.green as f64 - 1.0) * strength;
+                let z = 2.0 * color.blue as f64 - 1.0;
+
+                (&(&(&tangent * x) + &(&bitangent * y)) + &(normal * z)).normalize()
+            }
+        }
+    }
+}
+
+fn offset(point: &Point, axis: usize, amount: f64) -> Point {
+    match axis {
+        0 => point.add_x(amount),
+        1 => point.add_y(amount),
+        _ => point.add_z(amount),
+    }
+}
+
+fn central_difference<F: Fn(Point) -> f64>(f: F, point: &Point, axis: usize) -> f64 {
+    (f(offset(point, axis, DELTA)) - f(offset(point, axis, -DELTA))) / (2.0 * DELTA)
+}
+
+// difference between two texture coordinates, ignoring jumps across the [0, 1) seam
+fn uv_difference(a: f64, b: f64) -> f64 {
+    (a - b + 0.5).rem_euclid(1.0) - 0.5
+}
+
+// unit tangent (along u) and bitangent (along v) at the point, found by differentiating the uv
+// mapping along two arbitrary directions in the surface plane
+fn tangent_frame(
+    object: &Object,
+    world_point: &Point,
+    normal: &Vector,
+    mapping: UvMapping,
+    time: f64,
+) -> Option<(Vector, Vector)> {
+    let (t1, t2) = orthonormal_basis(normal);
+
+    let inverse = object.inverse_transformation_at(time);
+    let uv = |direction: &Vector, amount: f64| {
+        let p = world_point + &(direction * amount);
+        mapping.map(object, &(&inverse * &p))
+    };
+    let derivative = |direction: &Vector| {
+        let (u0, v0) = uv(direction, -DELTA);
+        let (u1, v1) = uv(direction, DELTA);
+        (
+            uv_difference(u1, u0) / (2.0 * DELTA),
+            uv_difference(v1, v0) / (2.0 * DELTA),
+        )
+    };
+    let (du1, dv1) = derivative(&t1);
+    let (du2, dv2) = derivative(&t2);
+
+    let determinant = du1 * dv2 - du2 * dv1;
+    if determinant.abs() < crate::domain::EPSILON {
+        return Option::None;
+    }
+
+    let dp_du = &(&t1 * (dv2 / determinant)) - &(&t2 * (dv1 / determinant));
+    let dp_dv = &(&t2 * (du1 / determinant)) - &(&t1 * (du2 / determinant));
+
+    let tangent = (&dp_du - &(normal * dp_du.dot_product(normal))).normalize();
+    let mut bitangent = normal.cross_product(&tangent);
+    if bitangent.dot_product(&dp_dv) < 0.0 {
+        bitangent = -bitangent;
+    }
+    Option::Some((tangent, bitangent))
+}
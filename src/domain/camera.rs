@@ -2,12 +2,17 @@ use crate::domain::matrix::Matrix;
 use crate::domain::ray::Ray;
 use crate::domain::Point;
 
+#[derive(Debug, Clone)]
 pub struct Camera {
     pub hsize: usize,
     pub vsize: usize,
     pub field_of_view: f64,
     pub transform: Matrix,
     pub pixel_size: f64,
+    // times within the frame (0 to 1) the shutter opens and closes at; objects moving while it's
+    // open are blurred
+    pub shutter_open: f64,
+    pub shutter_close: f64,
     half_width: f64,
     half_height: f64,
 }
@@ -15,37 +20,49 @@ pub struct Camera {
 impl Camera {
     // constructor
     pub fn new(hsize: usize, vsize: usize, field_of_view: f64) -> Camera {
+        let mut camera = Camera {
+            hsize,
+            vsize,
+            field_of_view,
+            transform: crate::domain::matrix::IDENTITY.clone(),
+            pixel_size: 0.0,
+            shutter_open: 0.0,
+            shutter_close: 0.0,
+            half_width: 0.0,
+            half_height: 0.0,
+        };
+        camera.set_field_of_view(field_of_view);
+        camera
+    }
+
+    // changes the field of view, resizing the pixels to match
+    pub fn set_field_of_view(&mut self, field_of_view: f64) {
         let half_view = f64::tan(field_of_view / 2.0);
-        let aspect = hsize as f64 / vsize as f64;
+        let aspect = self.hsize as f64 / self.vsize as f64;
 
-        let half_width;
-        let half_height;
         if aspect >= 1.0 {
-            half_width = half_view;
-            half_height = half_view / aspect;
+            self.half_width = half_view;
+            self.half_height = half_view / aspect;
         } else {
-            half_width = half_view * aspect;
-            half_height = half_view;
+            self.half_width = half_view * aspect;
+            self.half_height = half_view;
         }
 
-        let pixel_size = (half_width * 2.0) / hsize as f64;
-
-        Camera {
-            hsize,
-            vsize,
-            field_of_view,
-            transform: crate::domain::matrix::IDENTITY.clone(),
-            pixel_size,
-            half_width,
-            half_height,
-        }
+        self.field_of_view = field_of_view;
+        self.pixel_size = (self.half_width * 2.0) / self.hsize as f64;
     }
 
     // Constructs a new ray that starts at camera and passes through indicated x,y pixel on the canvas
     pub fn ray_for_pixel(&self, px: usize, py: usize) -> Ray {
-        // offset from edge of canvas to pixel's center
-        let xoffset = (px as f64 + 0.5) * self.pixel_size;
-        let yoffset = (py as f64 + 0.5) * self.pixel_size;
+        self.ray_for_sample(px, py, 0.5, 0.5)
+    }
+
+    // ray through the given position within a pixel, with (0, 0) its top left corner and
+    // (1, 1) its bottom right one
+    pub fn ray_for_sample(&self, px: usize, py: usize, dx: f64, dy: f64) -> Ray {
+        // offset from edge of canvas to the sampled position
+        let xoffset = (px as f64 + dx) * self.pixel_size;
+        let yoffset = (py as f64 + dy) * self.pixel_size;
 
         // untransformed coordinates of pixel in world space
         let world_x = self.half_width - xoffset;
@@ -58,6 +75,11 @@ impl Camera {
         let origin = &transf_inv * &Point::ORIGIN;
         let direction = (&pixel - &origin).normalize();
 
-        Ray::new(origin, direction)
+        Ray::new(origin, direction).with_time(self.shutter_open)
+    }
+
+    // time the given share (0 to 1) of the exposure has passed at
+    pub fn shutter_time(&self, share: f64) -> f64 {
+        self.shutter_open + (self.shutter_close - self.shutter_open) * share
     }
 }
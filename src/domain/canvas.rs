@@ -62,9 +62,9 @@ impl Canvas {
         // }
     }
 
-    // returns pixel at given coordinates
+    // returns pixel at column x and row y, the same order `render` takes
     pub fn pixel_at(&self, x: usize, y: usize) -> Color {
-        self[x][y]
+        self[y][x]
     }
 }
 
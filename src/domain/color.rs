@@ -24,6 +24,11 @@ impl Color {
     pub fn new(red: f32, green: f32, blue: f32) -> Color {
         Color { red, green, blue }
     }
+
+    // perceived brightness (Rec. 709 weights)
+    pub fn luminance(&self) -> f32 {
+        0.2126 * self.red + 0.7152 * self.green + 0.0722 * self.blue
+    }
 }
 
 impl Default for Color {
@@ -0,0 +1,167 @@
+use crate::domain::canvas::Canvas;
+use crate::domain::color::Color;
+use crate::domain::random::Rng;
+use crate::domain::uv::equirectangular_map;
+use crate::domain::Vector;
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+// Light arriving from every direction, read from an equirectangular (latitude/longitude) image.
+// Directions are importance-sampled by pixel luminance, so bright spots such as a studio's
+// softboxes receive most of the samples.
+#[derive(Debug, Clone)]
+pub struct EnvironmentLight {
+    pub canvas: Arc<Canvas>,
+    pub intensity: f32,
+    pub samples: usize,
+    // cumulative distribution over rows, then over the pixels of every row
+    marginal_cdf: Vec<f64>,
+    conditional_cdfs: Vec<Vec<f64>>,
+    total_weight: f64,
+}
+
+pub struct EnvironmentLightBuilder {
+    canvas: Arc<Canvas>,
+    intensity: Option<f32>,
+    samples: Option<usize>,
+}
+
+// maps are shared, so equality is by identity rather than by comparing every pixel
+impl PartialEq for EnvironmentLight {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.canvas, &other.canvas)
+            && self.intensity == other.intensity
+            && self.samples == other.samples
+    }
+}
+
+impl EnvironmentLight {
+    pub const DEFAULT_INTENSITY: f32 = 1.0;
+    pub const DEFAULT_SAMPLES: usize = 64;
+
+    // builder - the map needs at least one pixel to look directions up in
+    pub fn new(canvas: Arc<Canvas>) -> EnvironmentLightBuilder {
+        if canvas.width == 0 || canvas.height == 0 {
+            panic!("Environment light requires a non-empty map.");
+        }
+
+        EnvironmentLightBuilder {
+            canvas,
+            intensity: Option::None,
+            samples: Option::None,
+        }
+    }
+
+    // scaled radiance arriving along the given direction
+    pub fn radiance(&self, direction: &Vector) -> Color {
+        let (column, row) = self.pixel_for(direction);
+        &self.canvas.pixel_at(column, row) * self.intensity
+    }
+
+    // probability density (per unit solid angle) of sampling the given direction
+    pub fn pdf(&self, direction: &Vector) -> f64 {
+        if self.total_weight == 0.0 {
+            return 0.0;
+        }
+
+        // the sin(theta) weighting of the rows cancels out the pixel's solid angle shrinking
+        // towards the poles
+        let (column, row) = self.pixel_for(direction);
+        let pixels = (self.canvas.width * self.canvas.height) as f64;
+        luminance(&self.canvas.pixel_at(column, row)) * pixels / (self.total_weight * 2.0 * PI * PI)
+    }
+
+    // picks a direction proportionally to its (luminance weighted) contribution, returning it
+    // together with its pdf
+    pub fn sample(&self, rng: &mut Rng) -> Option<(Vector, f64)> {
+        if self.total_weight == 0.0 {
+            return Option::None;
+        }
+
+        let row = pick(&self.marginal_cdf, rng.next_f64());
+        let column = pick(&self.conditional_cdfs[row], rng.next_f64());
+
+        // jitter within the chosen pixel; row 0 is the top of the image
+        let u = (column as f64 + rng.next_f64()) / self.canvas.width as f64;
+        let v = 1.0 - (row as f64 + rng.next_f64()) / self.canvas.height as f64;
+        let direction = direction_for(u, v);
+        let pdf = self.pdf(&direction);
+
+        if pdf > 0.0 {
+            Option::Some((direction, pdf))
+        } else {
+            Option::None
+        }
+    }
+
+    fn pixel_for(&self, direction: &Vector) -> (usize, usize) {
+        let (u, v) = equirectangular_map(direction);
+        let column = ((u * self.canvas.width as f64) as usize).min(self.canvas.width - 1);
+        let row = (((1.0 - v) * self.canvas.height as f64) as usize).min(self.canvas.height - 1);
+        (column, row)
+    }
+}
+
+impl EnvironmentLightBuilder {
+    // finalizes built instance, precomputing the sampling distribution
+    pub fn build(&self) -> EnvironmentLight {
+        let canvas = self.canvas.clone();
+        let mut marginal_cdf = Vec::with_capacity(canvas.height);
+        let mut conditional_cdfs = Vec::with_capacity(canvas.height);
+        let mut total_weight = 0.0;
+
+        for row in 0..canvas.height {
+            let sin_theta = (PI * (row as f64 + 0.5) / canvas.height as f64).sin();
+            let mut row_weight = 0.0;
+            let mut cdf = Vec::with_capacity(canvas.width);
+            for column in 0..canvas.width {
+                row_weight += luminance(&canvas.pixel_at(column, row)) * sin_theta;
+                cdf.push(row_weight);
+            }
+
+            total_weight += row_weight;
+            marginal_cdf.push(total_weight);
+            conditional_cdfs.push(cdf);
+        }
+
+        EnvironmentLight {
+            canvas,
+            intensity: self
+                .intensity
+                .unwrap_or(EnvironmentLight::DEFAULT_INTENSITY),
+            samples: self.samples.unwrap_or(EnvironmentLight::DEFAULT_SAMPLES),
+            marginal_cdf,
+            conditional_cdfs,
+            total_weight,
+        }
+    }
+
+    pub fn intensity(&mut self, intensity: f32) -> &mut EnvironmentLightBuilder {
+        self.intensity = Option::Some(intensity);
+        self
+    }
+
+    pub fn samples(&mut self, samples: usize) -> &mut EnvironmentLightBuilder {
+        self.samples = Option::Some(samples);
+        self
+    }
+}
+
+// sampling weight of a color - negative values never get sampled
+pub fn luminance(color: &Color) -> f64 {
+    color.luminance().max(0.0) as f64
+}
+
+// inverse of `uv::equirectangular_map`
+pub fn direction_for(u: f64, v: f64) -> Vector {
+    let theta = (u - 0.5) * 2.0 * PI;
+    let phi = (1.0 - v) * PI;
+    Vector::new(phi.sin() * theta.sin(), phi.cos(), phi.sin() * theta.cos())
+}
+
+// index of the first cumulative weight above the scaled random value
+fn pick(cdf: &[f64], random: f64) -> usize {
+    let target = random * cdf.last().unwrap();
+    let index = cdf.partition_point(|&weight| weight <= target);
+    index.min(cdf.len() - 1)
+}
@@ -0,0 +1,344 @@
+use crate::domain::camera::Camera;
+use crate::domain::color::Color;
+use crate::domain::intersection::Computations;
+use crate::domain::material::{ShadingModel, CHANNEL_WAVELENGTHS};
+use crate::domain::microfacet::{
+    base_reflectance, ggx_distribution, sample_ggx_half_vector, schlick_fresnel, smith_geometry,
+};
+use crate::domain::occlusion::AmbientOcclusion;
+use crate::domain::random::{sample_cone, sample_cosine_hemisphere, split_mix, Rng};
+use crate::domain::ray::Ray;
+use crate::domain::settings::RenderSettings;
+use crate::domain::world::World;
+use crate::domain::Vector;
+use std::f64::consts::PI;
+
+// Russian roulette only kicks in after this many bounces, so short paths are never cut
+const ROULETTE_BOUNCES: usize = 3;
+
+// highest survival probability, so even bright paths eventually terminate
+const MAX_SURVIVAL: f32 = 0.95;
+
+// Determines how the light reaching the camera is estimated.
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub enum Integrator {
+    // direct lighting plus recursive mirror reflection and refraction
+    #[default]
+    Whitted,
+    // Monte Carlo path tracing: every pixel averages random light paths (as many as the render
+    // settings' samples), which picks up color bleeding and other indirect illumination
+    PathTracing {
+        max_bounces: usize,
+    },
+    // ambient occlusion only, for quick clay previews of the geometry
+    AmbientOcclusion(AmbientOcclusion),
+}
+
+impl Integrator {
+    // samples per pixel path tracing renders with unless the settings ask for another count
+    pub const DEFAULT_PATH_SAMPLES: usize = 64;
+    pub const DEFAULT_PATH_BOUNCES: usize = 8;
+
+    pub fn new_path_tracing() -> Integrator {
+        Integrator::PathTracing {
+            max_bounces: Integrator::DEFAULT_PATH_BOUNCES,
+        }
+    }
+
+    // samples per pixel when the settings don't set them
+    pub fn default_samples(&self) -> usize {
+        match self {
+            Integrator::PathTracing { .. } => Integrator::DEFAULT_PATH_SAMPLES,
+            _ => RenderSettings::DEFAULT_SAMPLES,
+        }
+    }
+}
+
+impl World {
+    // averages the given number of paths through random positions within the pixel
+    pub fn path_traced_pixel(
+        &self,
+        camera: &Camera,
+        x: usize,
+        y: usize,
+        samples: usize,
+        max_bounces: usize,
+    ) -> Color {
+        if samples == 0 {
+            return Color::BLACK;
+        }
+
+        // seeded by the pixel so renders are repeatable
+        let mut seed = ((y as u64) << 32) | x as u64;
+        let mut rng = Rng::new(split_mix(&mut seed));
+        let mut total = Color::BLACK;
+        for _ in 0..samples {
+            let ray = camera
+                .ray_for_sample(x, y, rng.next_f64(), rng.next_f64())
+                .with_time(camera.shutter_time(rng.next_f64()));
+            total = &total + &self.trace_path(&ray, max_bounces, &mut rng);
+        }
+        &total * (1.0 / samples as f32)
+    }
+
+    // Follows a single light path backwards from the given ray. At every hit one lobe (diffuse,
+    // glossy, reflection or refraction) is picked with probability proportional to its weight.
+    // Diffuse bounces are cosine-weighted and glossy ones (microfacet materials) follow the GGX
+    // distribution, both also sample the light source directly (next-event estimation; subsurface
+    // materials gather the light scattered beneath their surface instead). Media along every
+    // segment add the light they scatter and dim the rest. The ambient term is ignored, indirect
+    // light takes its place.
+    pub fn trace_path(&self, ray: &Ray, max_bounces: usize, rng: &mut Rng) -> Color {
+        let mut ray = ray.clone();
+        let mut throughput = Color::WHITE;
+        let mut radiance = Color::BLACK;
+
+        for bounce in 0..max_bounces {
+            let mut ints = if bounce == 0 {
+                self.intersect_from_camera(&ray)
+            } else {
+                self.intersect(&ray)
+            };
+            let original_ints = ints.clone();
+            let hit = ints.hit();
+
+            // media up to whatever the ray reaches scatter light of their own and dim the rest
+            let distance = hit.as_ref().map_or(f64::INFINITY, |hit| hit.distance);
+            let (transmittance, scattered) = self.media_along(&ray, distance);
+            radiance = &radiance + &(&throughput * &scattered);
+            throughput = &throughput * transmittance as f32;
+
+            let hit = match hit {
+                Some(hit) => hit,
+                None => {
+                    let sky = match self.environment_light.as_ref() {
+                        Some(environment) if bounce > 0 => environment.radiance(&ray.direction),
+                        _ => self.background.color_at(&ray.direction),
+                    };
+                    radiance = &radiance + &(&throughput * &sky);
+                    break;
+                }
+            };
+            let comps =
+                Computations::prepare_computations(&hit, &ray, Option::Some(&original_ints));
+            let material = &comps.object.shape().material;
+
+            // light travelling through the object is absorbed on its way here
+            if comps.inside {
+                throughput = &throughput * &material.transmittance(comps.distance);
+            }
+            radiance = &radiance + &(&throughput * &material.emitted());
+
+            let albedo = material.diffuse_color_at(comps.object, &comps.over_point, comps.time);
+            // microfacet materials reflect glossily as much as fresnel has them reflect towards
+            // the viewer
+            let (f0, roughness, glossy) = match material.shading_model {
+                ShadingModel::Microfacet { metallic } => {
+                    let base_color =
+                        material.color_at_time(comps.object, &comps.over_point, comps.time);
+                    let f0 = base_reflectance(&base_color, metallic);
+                    let n_dot_v = comps.eye_v.dot_product(&comps.normal_v).max(0.0);
+                    let weight = schlick_fresnel(&f0, n_dot_v).luminance().max(0.0) as f64;
+                    (f0, material.roughness, weight)
+                }
+                ShadingModel::Phong => (Color::BLACK, 0.0, 0.0),
+            };
+            let (reflective, transparency) =
+                if material.reflective > 0.0 && material.transparency > 0.0 {
+                    let reflectance = comps.schlick();
+                    (
+                        material.reflective * reflectance,
+                        material.transparency * (1.0 - reflectance),
+                    )
+                } else {
+                    (material.reflective, material.transparency)
+                };
+            let diffuse = albedo.luminance().max(0.0) as f64;
+            let total = diffuse + glossy + reflective + transparency;
+            if total <= 0.0 {
+                break;
+            }
+
+            // weights above one are kept (lobes add up like in the Whitted tracer), below one
+            // the remaining probability absorbs the path
+            let scale = total.max(1.0);
+            let choice = rng.next_f64() * scale;
+            if choice < diffuse {
+                throughput = &throughput * (scale / diffuse) as f32;
+                // light scattered beneath the surface (already tinted by the albedo) stands in for
+                // the direct light
+                let direct = if material.subsurface.is_some() {
+                    self.subsurface_color(&comps, RenderSettings::DEFAULT_BIAS)
+                } else {
+                    &albedo * &self.direct_light(&comps)
+                };
+                radiance = &radiance + &(&throughput * &direct);
+                throughput = &throughput * &albedo;
+                ray = Ray::new(
+                    comps.over_point,
+                    sample_cosine_hemisphere(rng, &comps.normal_v),
+                )
+                .with_time(comps.time);
+            } else if choice < diffuse + glossy {
+                throughput = &throughput * (scale / glossy) as f32;
+                radiance = &radiance + &(&throughput * &self.glossy_light(&comps, &f0, roughness));
+                ray = match self.glossy_path(&comps, &f0, roughness, &mut throughput, rng) {
+                    Some(ray) => ray,
+                    // reflected below the surface
+                    None => break,
+                };
+            } else if choice < diffuse + glossy + reflective {
+                throughput = &throughput * scale as f32;
+                let direction = spread(rng, &comps, &comps.reflect_v, 1.0);
+                ray = Ray::new(comps.over_point, direction).with_time(comps.time);
+            } else if choice < total {
+                throughput = &throughput * scale as f32;
+                ray = self.refracted_path(&comps, &mut throughput, rng);
+            } else {
+                // absorbed
+                break;
+            }
+
+            if !continue_path(&mut throughput, bounce, rng) {
+                break;
+            }
+        }
+        radiance
+    }
+
+    // light source contribution for a white lambertian surface, filtered by whatever lies
+    // between; the light delivers intensity times pi, cancelling the 1 / pi of the lambertian brdf
+    fn direct_light(&self, comps: &Computations) -> Color {
+        let light = match self.light_source.as_ref() {
+            Some(light) => light,
+            None => return Color::BLACK,
+        };
+        let cos_theta = (&light.position - &comps.over_point)
+            .normalize()
+            .dot_product(&comps.normal_v);
+        if cos_theta <= 0.0 {
+            return Color::BLACK;
+        }
+        &(&light.intensity * &self.received_light(comps)) * cos_theta as f32
+    }
+
+    // light source contribution reflected by the glossy (cook-torrance specular) lobe, with the
+    // light delivering intensity times pi as above
+    fn glossy_light(&self, comps: &Computations, f0: &Color, roughness: f64) -> Color {
+        let light = match self.light_source.as_ref() {
+            Some(light) => light,
+            None => return Color::BLACK,
+        };
+        let light_v = (&light.position - &comps.over_point).normalize();
+        let n_dot_l = light_v.dot_product(&comps.normal_v);
+        let n_dot_v = comps.eye_v.dot_product(&comps.normal_v);
+        if n_dot_l <= 0.0 || n_dot_v <= 0.0 {
+            return Color::BLACK;
+        }
+
+        let half_v = (&light_v + &comps.eye_v).normalize();
+        let n_dot_h = comps.normal_v.dot_product(&half_v).max(0.0);
+        let v_dot_h = comps.eye_v.dot_product(&half_v).max(0.0);
+        let fresnel = schlick_fresnel(f0, v_dot_h);
+        let factor = ggx_distribution(n_dot_h, roughness)
+            * smith_geometry(n_dot_v, n_dot_l, roughness)
+            / (4.0 * n_dot_v)
+            * PI;
+        &(&(&fresnel * &light.intensity) * &self.received_light(comps)) * factor as f32
+    }
+
+    // continues the path along the view direction mirrored about a GGX-sampled half vector; the
+    // distribution cancels out of the weight, leaving fresnel and shadowing-masking
+    fn glossy_path(
+        &self,
+        comps: &Computations,
+        f0: &Color,
+        roughness: f64,
+        throughput: &mut Color,
+        rng: &mut Rng,
+    ) -> Option<Ray> {
+        let half_v = sample_ggx_half_vector(rng, &comps.normal_v, roughness);
+        let v_dot_h = comps.eye_v.dot_product(&half_v);
+        let direction = &(&half_v * (2.0 * v_dot_h)) - &comps.eye_v;
+        let n_dot_l = direction.dot_product(&comps.normal_v);
+        let n_dot_v = comps.eye_v.dot_product(&comps.normal_v);
+        let n_dot_h = comps.normal_v.dot_product(&half_v);
+        if v_dot_h <= 0.0 || n_dot_l <= 0.0 || n_dot_v <= 0.0 || n_dot_h <= 0.0 {
+            return Option::None;
+        }
+
+        let weight = smith_geometry(n_dot_v, n_dot_l, roughness) * v_dot_h / (n_dot_v * n_dot_h);
+        *throughput = &(&*throughput * &schlick_fresnel(f0, v_dot_h)) * weight as f32;
+        Option::Some(Ray::new(comps.over_point, direction).with_time(comps.time))
+    }
+
+    // continues the path through the surface; dispersive materials pick one channel at random
+    // and carry only that channel onwards, paths already split keep to their channel
+    fn refracted_path(&self, comps: &Computations, throughput: &mut Color, rng: &mut Rng) -> Ray {
+        let material = &comps.object.shape().material;
+        let (n1, n2) = match material.dispersion {
+            Some(dispersion) => {
+                let channels = [throughput.red, throughput.green, throughput.blue];
+                let lit: Vec<usize> = (0..3).filter(|c| channels[*c] != 0.0).collect();
+                let channel = if lit.len() == 1 {
+                    lit[0]
+                } else {
+                    let channel = ((rng.next_f64() * 3.0) as usize).min(2);
+                    let mut mask = [0.0; 3];
+                    mask[channel] = 3.0;
+                    *throughput = &*throughput * &Color::new(mask[0], mask[1], mask[2]);
+                    channel
+                };
+                let index = dispersion.index_at(CHANNEL_WAVELENGTHS[channel]);
+                if comps.inside {
+                    (index, comps.n2)
+                } else {
+                    (comps.n1, index)
+                }
+            }
+            None => (comps.n1, comps.n2),
+        };
+
+        let ray = match comps.refracted_direction(n1, n2) {
+            Some(direction) => Ray::new(comps.under_point, spread(rng, comps, &direction, -1.0)),
+            // total internal reflection
+            None => Ray::new(comps.over_point, spread(rng, comps, &comps.reflect_v, 1.0)),
+        };
+        ray.with_time(comps.time)
+    }
+}
+
+// Russian roulette: after a few bounces dim paths are terminated at random, and survivors are
+// boosted to keep the estimate unbiased
+fn continue_path(throughput: &mut Color, bounce: usize, rng: &mut Rng) -> bool {
+    if bounce < ROULETTE_BOUNCES {
+        return true;
+    }
+    let survival = throughput
+        .red
+        .max(throughput.green)
+        .max(throughput.blue)
+        .min(MAX_SURVIVAL);
+    if rng.next_f64() >= survival as f64 {
+        return false;
+    }
+    *throughput = &*throughput * (1.0 / survival);
+    true
+}
+
+// rough materials scatter around the ideal direction, keeping to the given side of the surface
+pub fn spread(rng: &mut Rng, comps: &Computations, direction: &Vector, side: f64) -> Vector {
+    let material = &comps.object.shape().material;
+    if material.roughness <= 0.0 {
+        return *direction;
+    }
+
+    let half_angle = material.roughness.min(1.0) * PI / 2.0;
+    let sample = sample_cone(rng, &direction.normalize(), half_angle);
+    let along_normal = sample.dot_product(&comps.normal_v);
+    if along_normal * side < 0.0 {
+        &sample - &(&comps.normal_v * (2.0 * along_normal))
+    } else {
+        sample
+    }
+}
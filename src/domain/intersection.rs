@@ -101,6 +101,14 @@ impl<'a> Intersections<'a> {
         self.intersections.append(&mut b);
     }
 
+    // keeps only the intersections matching the predicate
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&Intersection<'a>) -> bool,
+    {
+        self.intersections.retain(f);
+    }
+
     // returns number of intersections
     pub fn len(&self) -> usize {
         self.intersections.len()
@@ -111,7 +119,7 @@ impl<'a> Intersections<'a> {
         self.len() == 0
     }
 
-    fn inner_hit(&mut self, validate: bool) -> Option<Intersection> {
+    fn inner_hit(&mut self, validate: bool) -> Option<Intersection<'a>> {
         while let Some(intersection) = self.intersections.pop() {
             if validate {
                 let valid = !intersection.distance.is_infinite() && !intersection.distance.is_nan();
@@ -127,12 +135,12 @@ impl<'a> Intersections<'a> {
     }
 
     // returns first item (regardless of sign (negative/positive)
-    pub fn hit_unchecked(&mut self) -> Option<Intersection> {
+    pub fn hit_unchecked(&mut self) -> Option<Intersection<'a>> {
         self.inner_hit(false)
     }
 
     // pops minimal item from heap
-    pub fn hit(&mut self) -> Option<Intersection> {
+    pub fn hit(&mut self) -> Option<Intersection<'a>> {
         self.inner_hit(true)
     }
 }
@@ -149,47 +157,38 @@ pub struct Computations<'a> {
     pub n1: f64,
     pub n2: f64,
     pub under_point: Point,
+    // time the ray was cast at, rays spawned at the hit are cast at the same time
+    pub time: f64,
 }
 
 impl<'a> Computations<'a> {
-    // builder
-    pub fn new(
-        distance: f64,
-        object: &'a Object,
-        point: Point,
-        eye_v: Vector,
-        normal_v: Vector,
-        inside: bool,
-        over_point: Point,
-        reflect_v: Vector,
-        n1: f64,
-        n2: f64,
-        under_point: Point,
-    ) -> Computations {
-        Computations {
-            distance,
-            object,
-            point,
-            eye_v,
-            normal_v,
-            inside,
-            over_point,
-            reflect_v,
-            n1,
-            n2,
-            under_point,
-        }
-    }
-
     // Utility method for pre-computing reusable, frequently-used computations
     pub fn prepare_computations(
         hit_intersection: &'a Intersection,
         ray: &'a Ray,
         all_intersections: Option<&'a Intersections>,
+    ) -> Computations<'a> {
+        Computations::prepare_computations_with_bias(
+            hit_intersection,
+            ray,
+            all_intersections,
+            crate::domain::EPSILON,
+        )
+    }
+
+    // as above, with over and under points the given distance off the surface
+    pub fn prepare_computations_with_bias(
+        hit_intersection: &'a Intersection,
+        ray: &'a Ray,
+        all_intersections: Option<&'a Intersections>,
+        bias: f64,
     ) -> Computations<'a> {
         let point = ray.position(hit_intersection.distance);
         let eye_v = -ray.direction;
-        let mut normal_v = hit_intersection.object.normal_at(&point);
+        let mut normal_v = hit_intersection.object.normal_at_time(&point, ray.time);
+        if let Some(perturbation) = &hit_intersection.object.shape().material.normal_perturbation {
+            normal_v = perturbation.perturb(hit_intersection.object, &point, &normal_v, ray.time);
+        }
 
         let inside;
         if normal_v.dot_product(&eye_v) < 0.0 {
@@ -199,7 +198,7 @@ impl<'a> Computations<'a> {
             inside = false;
         }
 
-        let epsilon_scaled_normal = &normal_v * crate::domain::EPSILON;
+        let epsilon_scaled_normal = &normal_v * bias;
         let over_point = &point + &epsilon_scaled_normal;
         let under_point = &point - &epsilon_scaled_normal;
 
@@ -209,9 +208,9 @@ impl<'a> Computations<'a> {
         let (n1, n2) =
             Computations::precompute_refractive_indexes(hit_intersection, all_intersections);
 
-        Computations::new(
-            hit_intersection.distance,
-            hit_intersection.object,
+        Computations {
+            distance: hit_intersection.distance,
+            object: hit_intersection.object,
             point,
             eye_v,
             normal_v,
@@ -221,7 +220,8 @@ impl<'a> Computations<'a> {
             n1,
             n2,
             under_point,
-        )
+            time: ray.time,
+        }
     }
 
     fn precompute_refractive_indexes(
@@ -304,6 +304,19 @@ impl<'a> Computations<'a> {
         (n1, n2)
     }
 
+    // direction of the ray refracted between the given indices, none on total internal reflection
+    pub fn refracted_direction(&self, n1: f64, n2: f64) -> Option<Vector> {
+        let n_ratio = n1 / n2;
+        let cos_i = self.eye_v.dot_product(&self.normal_v);
+        let sin2_t = n_ratio.powi(2) * (1.0 - cos_i.powi(2));
+        if sin2_t > 1.0 {
+            return Option::None;
+        }
+
+        let cos_t = (1.0 - sin2_t).sqrt();
+        Option::Some(&(&self.normal_v * (n_ratio * cos_i - cos_t)) - &(&self.eye_v * n_ratio))
+    }
+
     pub fn schlick(&self) -> f64 {
         let mut cos = self.eye_v.dot_product(&self.normal_v);
         if self.n1 > self.n2 {
@@ -0,0 +1,166 @@
+use crate::domain::Point;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+// Balanced kd-tree over items positioned in space, answering k-nearest-neighbour queries. The
+// tree is implicit: every range of the item list is split at its median along the axis of
+// largest extent, and that item becomes the node for the range.
+#[derive(Debug, Clone)]
+pub struct KdTree<T> {
+    items: Vec<(Point, T)>,
+    axes: Vec<usize>,
+}
+
+// candidate during a search, ordered by distance so the heap keeps the farthest on top
+struct Neighbour {
+    distance2: f64,
+    index: usize,
+}
+
+impl PartialEq for Neighbour {
+    fn eq(&self, other: &Self) -> bool {
+        self.distance2 == other.distance2
+    }
+}
+
+impl Eq for Neighbour {}
+
+impl PartialOrd for Neighbour {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Neighbour {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance2
+            .partial_cmp(&other.distance2)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+impl<T> KdTree<T> {
+    // constructor, balances the tree over the given items
+    pub fn new(mut items: Vec<(Point, T)>) -> KdTree<T> {
+        let mut axes = vec![0; items.len()];
+        let len = items.len();
+        balance(&mut items, &mut axes, 0, len);
+        KdTree { items, axes }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    // up to k items closest to the point and no further away than max_distance, nearest first,
+    // each with its squared distance
+    pub fn nearest(&self, point: &Point, k: usize, max_distance: f64) -> Vec<(&T, f64)> {
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        if k > 0 {
+            self.search(
+                point,
+                k,
+                max_distance * max_distance,
+                0,
+                self.len(),
+                &mut heap,
+            );
+        }
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|n| (&self.items[n.index].1, n.distance2))
+            .collect()
+    }
+
+    fn search(
+        &self,
+        point: &Point,
+        k: usize,
+        max_distance2: f64,
+        lo: usize,
+        hi: usize,
+        heap: &mut BinaryHeap<Neighbour>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+        let mid = (lo + hi) / 2;
+        let (position, _) = &self.items[mid];
+
+        let offset = point - position;
+        let distance2 = offset.dot_product(&offset);
+        if distance2 <= bound(heap, k, max_distance2) {
+            heap.push(Neighbour {
+                distance2,
+                index: mid,
+            });
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+
+        // nearer side first, the far side only while it can still hold closer items
+        let axis = self.axes[mid];
+        let split = coordinate(point, axis) - coordinate(position, axis);
+        let (near, far) = if split < 0.0 {
+            ((lo, mid), (mid + 1, hi))
+        } else {
+            ((mid + 1, hi), (lo, mid))
+        };
+        self.search(point, k, max_distance2, near.0, near.1, heap);
+        if split * split <= bound(heap, k, max_distance2) {
+            self.search(point, k, max_distance2, far.0, far.1, heap);
+        }
+    }
+}
+
+// squared distance an item must beat to be kept
+fn bound(heap: &BinaryHeap<Neighbour>, k: usize, max_distance2: f64) -> f64 {
+    match heap.peek() {
+        Some(farthest) if heap.len() >= k => farthest.distance2.min(max_distance2),
+        _ => max_distance2,
+    }
+}
+
+fn balance<T>(items: &mut [(Point, T)], axes: &mut [usize], lo: usize, hi: usize) {
+    if hi <= lo + 1 {
+        return;
+    }
+
+    // split along the widest axis
+    let extent = |axis: usize| {
+        let values = items[lo..hi].iter().map(|(p, _)| coordinate(p, axis));
+        let min = values.clone().fold(f64::INFINITY, f64::min);
+        let max = values.fold(f64::NEG_INFINITY, f64::max);
+        max - min
+    };
+    let axis = (0..3)
+        .max_by(|a, b| {
+            extent(*a)
+                .partial_cmp(&extent(*b))
+                .unwrap_or(Ordering::Equal)
+        })
+        .unwrap_or(0);
+
+    let mid = (lo + hi) / 2;
+    items[lo..hi].select_nth_unstable_by(mid - lo, |(a, _), (b, _)| {
+        coordinate(a, axis)
+            .partial_cmp(&coordinate(b, axis))
+            .unwrap_or(Ordering::Equal)
+    });
+    axes[mid] = axis;
+    balance(items, axes, lo, mid);
+    balance(items, axes, mid + 1, hi);
+}
+
+fn coordinate(point: &Point, axis: usize) -> f64 {
+    match axis {
+        0 => point.x(),
+        1 => point.y(),
+        _ => point.z(),
+    }
+}
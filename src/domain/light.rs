@@ -1,7 +1,11 @@
 use crate::domain::color::Color;
-use crate::domain::material::Material;
+use crate::domain::material::{Material, ShadingModel};
+use crate::domain::microfacet::{
+    base_reflectance, ggx_distribution, schlick_fresnel, smith_geometry,
+};
 use crate::domain::object::Object;
 use crate::domain::{Point, Vector};
+use std::f64::consts::PI;
 
 #[derive(PartialEq, Debug, Copy, Clone)]
 pub struct Light {
@@ -18,6 +22,12 @@ impl Light {
         }
     }
 
+    // flat ambient term included in the lighting of every point, lit or not, given the surface
+    // color there
+    pub fn ambient(material: &Material, color: &Color, light: &Light) -> Color {
+        &(color * &light.intensity) * material.ambient as f32
+    }
+
     // TODO Should move this over to Sphere. Perhaps turn this into a traight that Sphere and others implement.
     pub fn lighting(
         material: &Material,
@@ -28,14 +38,48 @@ impl Light {
         normal_v: &Vector,
         in_shadow: bool,
     ) -> Color {
-        let color = match &material.pattern {
-            Some(pattern) => pattern.color_at(object, point),
-            None => material.color,
+        let filter = if in_shadow {
+            Color::BLACK
+        } else {
+            Color::WHITE
         };
+        Light::filtered_lighting(material, object, light, point, eye_v, normal_v, &filter)
+    }
+
+    // lighting where the light reaches the point dimmed and tinted by the given filter (white when
+    // unobstructed, black when fully in shadow); the ambient term is never filtered
+    pub fn filtered_lighting(
+        material: &Material,
+        object: &Object,
+        light: &Light,
+        point: &Point,
+        eye_v: &Vector,
+        normal_v: &Vector,
+        filter: &Color,
+    ) -> Color {
+        let color = material.color_at(object, point);
+        Light::colored_lighting(material, &color, light, point, eye_v, normal_v, filter)
+    }
+
+    // filtered lighting of a surface whose color (pattern included) was already looked up
+    pub fn colored_lighting(
+        material: &Material,
+        color: &Color,
+        light: &Light,
+        point: &Point,
+        eye_v: &Vector,
+        normal_v: &Vector,
+        filter: &Color,
+    ) -> Color {
+        if let ShadingModel::Microfacet { .. } = material.shading_model {
+            return Light::microfacet_lighting(
+                material, color, light, point, eye_v, normal_v, filter,
+            );
+        }
 
-        let effective_color = &color * &light.intensity;
+        let effective_color = color * &light.intensity;
         let light_v = (&light.position - point).normalize();
-        let ambient = &effective_color * material.ambient as f32;
+        let ambient = Light::ambient(material, color, light);
         let light_dot_normal = light_v.dot_product(normal_v);
 
         let diffuse: Color;
@@ -44,8 +88,12 @@ impl Light {
             diffuse = Color::BLACK;
             specular = Color::BLACK;
         } else {
-            // calculate diffuse
-            diffuse = &effective_color * (material.diffuse as f32 * light_dot_normal as f32);
+            // calculate diffuse, unless it's scattered below the surface instead
+            diffuse = if material.subsurface.is_some() {
+                Color::BLACK
+            } else {
+                &effective_color * (material.diffuse as f32 * light_dot_normal as f32)
+            };
 
             // calculate specular
             let reflect_v = (-light_v).reflect(normal_v);
@@ -60,10 +108,58 @@ impl Light {
         }
 
         // return color calculation
-        if in_shadow {
+        if *filter == Color::BLACK {
             ambient
         } else {
-            &(&ambient + &diffuse) + &specular
+            &ambient + &(&(&diffuse + &specular) * filter)
+        }
+    }
+
+    // cook-torrance specular plus lambertian diffuse; lights deliver intensity * pi so that a
+    // white light head-on to a white, rough dielectric comes out close to Phong's full diffuse
+    fn microfacet_lighting(
+        material: &Material,
+        base_color: &Color,
+        light: &Light,
+        point: &Point,
+        eye_v: &Vector,
+        normal_v: &Vector,
+        filter: &Color,
+    ) -> Color {
+        let metallic = match material.shading_model {
+            ShadingModel::Microfacet { metallic } => metallic,
+            ShadingModel::Phong => 0.0,
+        };
+        let roughness = material.roughness;
+        let ambient = Light::ambient(material, base_color, light);
+
+        let light_v = (&light.position - point).normalize();
+        let n_dot_l = light_v.dot_product(normal_v);
+        let n_dot_v = eye_v.dot_product(normal_v);
+        if *filter == Color::BLACK || n_dot_l <= 0.0 || n_dot_v <= 0.0 {
+            return ambient;
         }
+
+        let half_v = (&light_v + eye_v).normalize();
+        let n_dot_h = normal_v.dot_product(&half_v).max(0.0);
+        let v_dot_h = eye_v.dot_product(&half_v).max(0.0);
+
+        let fresnel = schlick_fresnel(&base_reflectance(base_color, metallic), v_dot_h);
+        let specular_factor = ggx_distribution(n_dot_h, roughness)
+            * smith_geometry(n_dot_v, n_dot_l, roughness)
+            / (4.0 * n_dot_v * n_dot_l);
+        let specular = &fresnel * (specular_factor * PI) as f32;
+
+        // whatever isn't reflected is scattered diffusely, unless the surface is a metal or
+        // scatters it below the surface instead
+        let transmitted = &Color::WHITE - &fresnel;
+        let diffuse = if material.subsurface.is_some() {
+            Color::BLACK
+        } else {
+            &(&transmitted * base_color) * (1.0 - metallic) as f32
+        };
+
+        let reflected = &(&(&diffuse + &specular) * &light.intensity) * filter;
+        &ambient + &(&reflected * n_dot_l as f32)
     }
 }
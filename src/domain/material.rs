@@ -1,5 +1,10 @@
+use crate::domain::bump::NormalPerturbation;
 use crate::domain::color::Color;
+use crate::domain::medium::Medium;
+use crate::domain::object::Object;
 use crate::domain::pattern::Pattern;
+use crate::domain::subsurface::Subsurface;
+use crate::domain::Point;
 
 #[derive(PartialEq, Debug, Clone)]
 pub struct Material {
@@ -13,6 +18,19 @@ pub struct Material {
     pub transparency: f64,
     pub substance: Substance,
     pub refractive_index_override: Option<f64>,
+    pub normal_perturbation: Option<NormalPerturbation>,
+    pub shading_model: ShadingModel,
+    // spreads reflections and refractions, and the highlights of the microfacet model
+    pub roughness: f64,
+    pub glossy_samples: usize,
+    pub absorption_color: Color,
+    pub absorption_density: f64,
+    pub dispersion: Option<Dispersion>,
+    pub emission: Color,
+    pub emission_strength: f64,
+    // fills the object's inside; it only shows through a transparent surface
+    pub medium: Option<Medium>,
+    pub subsurface: Option<Subsurface>,
 }
 
 impl Default for Material {
@@ -28,6 +46,17 @@ impl Default for Material {
             transparency: Material::DEFAULT_TRANSPARENCY,
             substance: Material::DEFAULT_SUBSTANCE,
             refractive_index_override: Option::None,
+            normal_perturbation: Option::None,
+            shading_model: Material::DEFAULT_SHADING_MODEL,
+            roughness: Material::DEFAULT_ROUGHNESS,
+            glossy_samples: Material::DEFAULT_GLOSSY_SAMPLES,
+            absorption_color: Color::WHITE,
+            absorption_density: Material::DEFAULT_ABSORPTION_DENSITY,
+            dispersion: Option::None,
+            emission: Color::BLACK,
+            emission_strength: Material::DEFAULT_EMISSION_STRENGTH,
+            medium: Option::None,
+            subsurface: Option::None,
         }
     }
 }
@@ -43,6 +72,17 @@ pub struct MaterialBuilder {
     transparency: Option<f64>,
     substance: Option<Substance>,
     refractive_index_override: Option<f64>,
+    normal_perturbation: Option<NormalPerturbation>,
+    shading_model: Option<ShadingModel>,
+    roughness: Option<f64>,
+    glossy_samples: Option<usize>,
+    absorption_color: Option<Color>,
+    absorption_density: Option<f64>,
+    dispersion: Option<Dispersion>,
+    emission: Option<Color>,
+    emission_strength: Option<f64>,
+    medium: Option<Medium>,
+    subsurface: Option<Subsurface>,
 }
 
 impl Material {
@@ -53,6 +93,11 @@ impl Material {
     pub const DEFAULT_REFLECTIVE: f64 = 0.0;
     pub const DEFAULT_TRANSPARENCY: f64 = 0.0;
     pub const DEFAULT_SUBSTANCE: Substance = Substance::VACUUM;
+    pub const DEFAULT_SHADING_MODEL: ShadingModel = ShadingModel::Phong;
+    pub const DEFAULT_ROUGHNESS: f64 = 0.0;
+    pub const DEFAULT_GLOSSY_SAMPLES: usize = 16;
+    pub const DEFAULT_ABSORPTION_DENSITY: f64 = 0.0;
+    pub const DEFAULT_EMISSION_STRENGTH: f64 = 1.0;
 
     // builder
     pub fn new() -> MaterialBuilder {
@@ -67,9 +112,72 @@ impl Material {
             transparency: Option::None,
             substance: Option::None,
             refractive_index_override: Option::None,
+            normal_perturbation: Option::None,
+            shading_model: Option::None,
+            roughness: Option::None,
+            glossy_samples: Option::None,
+            absorption_color: Option::None,
+            absorption_density: Option::None,
+            dispersion: Option::None,
+            emission: Option::None,
+            emission_strength: Option::None,
+            medium: Option::None,
+            subsurface: Option::None,
         }
     }
 
+    // surface color at the given world point, taking the pattern into account
+    pub fn color_at(&self, object: &Object, point: &Point) -> Color {
+        self.color_at_time(object, point, 0.0)
+    }
+
+    // surface color at the given world point, with the object in its pose at the given time
+    pub fn color_at_time(&self, object: &Object, point: &Point, time: f64) -> Color {
+        match &self.pattern {
+            Some(pattern) => pattern.color_at_time(object, point, time),
+            None => self.color,
+        }
+    }
+
+    // share of the surface color scattered diffusely (metals have none)
+    pub fn diffuse_color_at(&self, object: &Object, point: &Point, time: f64) -> Color {
+        let color = self.color_at_time(object, point, time);
+        match self.shading_model {
+            ShadingModel::Phong => &color * self.diffuse as f32,
+            ShadingModel::Microfacet { metallic, .. } => &color * (1.0 - metallic as f32),
+        }
+    }
+
+    // light given off by the surface itself, regardless of any light source
+    pub fn emitted(&self) -> Color {
+        &self.emission * self.emission_strength as f32
+    }
+
+    // share of light surviving a trip of given length through the material (Beer-Lambert law):
+    // the absorption color is what remains of white light after one unit at density 1
+    pub fn transmittance(&self, distance: f64) -> Color {
+        if self.absorption_density == 0.0 {
+            return Color::WHITE;
+        }
+
+        let depth = (self.absorption_density * distance) as f32;
+        if depth <= 0.0 {
+            return Color::WHITE;
+        }
+        let channel = |c: f32| {
+            if c >= 1.0 {
+                1.0
+            } else {
+                (c.max(0.0).ln() * depth).exp()
+            }
+        };
+        Color::new(
+            channel(self.absorption_color.red),
+            channel(self.absorption_color.green),
+            channel(self.absorption_color.blue),
+        )
+    }
+
     pub fn refractive_index(&self) -> f64 {
         self.refractive_index_override
             .unwrap_or(self.substance.refractive_index())
@@ -87,9 +195,33 @@ impl MaterialBuilder {
             shininess: self.shininess.unwrap_or(Material::DEFAULT_SHININESS),
             pattern: self.pattern.clone(),
             reflective: self.reflective.unwrap_or(Material::DEFAULT_REFLECTIVE),
-            transparency: self.transparency.unwrap_or(Material::DEFAULT_TRANSPARENCY),
+            // rays only reach a medium through its object's surface, so a bound medium makes
+            // the surface clear unless told otherwise
+            transparency: self.transparency.unwrap_or(match self.medium {
+                Some(_) => 1.0,
+                None => Material::DEFAULT_TRANSPARENCY,
+            }),
             substance: self.substance.unwrap_or(Material::DEFAULT_SUBSTANCE),
             refractive_index_override: self.refractive_index_override,
+            normal_perturbation: self.normal_perturbation.clone(),
+            shading_model: self
+                .shading_model
+                .unwrap_or(Material::DEFAULT_SHADING_MODEL),
+            roughness: self.roughness.unwrap_or(Material::DEFAULT_ROUGHNESS),
+            glossy_samples: self
+                .glossy_samples
+                .unwrap_or(Material::DEFAULT_GLOSSY_SAMPLES),
+            absorption_color: self.absorption_color.unwrap_or(Color::WHITE),
+            absorption_density: self
+                .absorption_density
+                .unwrap_or(Material::DEFAULT_ABSORPTION_DENSITY),
+            dispersion: self.dispersion,
+            emission: self.emission.unwrap_or(Color::BLACK),
+            emission_strength: self
+                .emission_strength
+                .unwrap_or(Material::DEFAULT_EMISSION_STRENGTH),
+            medium: self.medium.clone(),
+            subsurface: self.subsurface,
         }
     }
 
@@ -142,6 +274,64 @@ impl MaterialBuilder {
         self.refractive_index_override = Option::Some(refractive_index);
         self
     }
+
+    pub fn normal_perturbation(
+        &mut self,
+        normal_perturbation: NormalPerturbation,
+    ) -> &mut MaterialBuilder {
+        self.normal_perturbation = Option::Some(normal_perturbation);
+        self
+    }
+
+    pub fn shading_model(&mut self, shading_model: ShadingModel) -> &mut MaterialBuilder {
+        self.shading_model = Option::Some(shading_model);
+        self
+    }
+
+    pub fn roughness(&mut self, roughness: f64) -> &mut MaterialBuilder {
+        self.roughness = Option::Some(roughness);
+        self
+    }
+
+    pub fn glossy_samples(&mut self, glossy_samples: usize) -> &mut MaterialBuilder {
+        self.glossy_samples = Option::Some(glossy_samples);
+        self
+    }
+
+    pub fn absorption_color(&mut self, absorption_color: Color) -> &mut MaterialBuilder {
+        self.absorption_color = Option::Some(absorption_color);
+        self
+    }
+
+    pub fn absorption_density(&mut self, absorption_density: f64) -> &mut MaterialBuilder {
+        self.absorption_density = Option::Some(absorption_density);
+        self
+    }
+
+    pub fn dispersion(&mut self, dispersion: Dispersion) -> &mut MaterialBuilder {
+        self.dispersion = Option::Some(dispersion);
+        self
+    }
+
+    pub fn emission(&mut self, emission: Color) -> &mut MaterialBuilder {
+        self.emission = Option::Some(emission);
+        self
+    }
+
+    pub fn emission_strength(&mut self, emission_strength: f64) -> &mut MaterialBuilder {
+        self.emission_strength = Option::Some(emission_strength);
+        self
+    }
+
+    pub fn medium(&mut self, medium: Medium) -> &mut MaterialBuilder {
+        self.medium = Option::Some(medium);
+        self
+    }
+
+    pub fn subsurface(&mut self, subsurface: Subsurface) -> &mut MaterialBuilder {
+        self.subsurface = Option::Some(subsurface);
+        self
+    }
 }
 
 #[derive(PartialEq, Debug, Clone, Copy)]
@@ -154,6 +344,16 @@ pub enum Substance {
 }
 
 impl Substance {
+    // preset wavelength-dependent indices, where known
+    pub fn dispersion(&self) -> Option<Dispersion> {
+        match self {
+            Substance::WATER => Option::Some(Dispersion::new_water()),
+            Substance::GLASS => Option::Some(Dispersion::new_glass()),
+            Substance::DIAMOND => Option::Some(Dispersion::new_diamond()),
+            _ => Option::None,
+        }
+    }
+
     pub fn refractive_index(&self) -> f64 {
         match self {
             Substance::VACUUM => 1.0,
@@ -164,3 +364,63 @@ impl Substance {
         }
     }
 }
+
+// How surfaces respond to lights. Phong uses ambient/diffuse/specular/shininess, while the
+// physically based microfacet model (GGX distribution, Smith geometry and Schlick fresnel) uses
+// the material color as base color together with metallic and the material's roughness (both in
+// [0, 1]).
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum ShadingModel {
+    Phong,
+    Microfacet { metallic: f64 },
+}
+
+// Wavelengths (in micrometers) traced for the red, green and blue channels: the Fraunhofer C and
+// F lines and the mercury e line.
+pub const CHANNEL_WAVELENGTHS: [f64; 3] = [0.6563, 0.5461, 0.4861];
+
+// Wavelength-dependent refractive index. Wavelengths are in micrometers.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Dispersion {
+    // n = a + b / wavelength^2
+    Cauchy { a: f64, b: f64 },
+    // n^2 = 1 + sum of b_i * wavelength^2 / (wavelength^2 - c_i)
+    Sellmeier { b: [f64; 3], c: [f64; 3] },
+}
+
+impl Dispersion {
+    pub fn new_cauchy(a: f64, b: f64) -> Dispersion {
+        Dispersion::Cauchy { a, b }
+    }
+
+    pub fn new_sellmeier(b: [f64; 3], c: [f64; 3]) -> Dispersion {
+        Dispersion::Sellmeier { b, c }
+    }
+
+    // borosilicate crown glass (BK7)
+    pub fn new_glass() -> Dispersion {
+        Dispersion::new_sellmeier(
+            [1.039_612_12, 0.231_792_344, 1.010_469_45],
+            [0.006_000_698_67, 0.020_017_914_4, 103.560_653],
+        )
+    }
+
+    pub fn new_diamond() -> Dispersion {
+        Dispersion::new_sellmeier([0.3306, 4.3356, 0.0], [0.030_625, 0.011_236, 0.0])
+    }
+
+    pub fn new_water() -> Dispersion {
+        Dispersion::new_cauchy(1.3199, 0.004_55)
+    }
+
+    pub fn index_at(&self, wavelength: f64) -> f64 {
+        let w2 = wavelength * wavelength;
+        match self {
+            Dispersion::Cauchy { a, b } => a + b / w2,
+            Dispersion::Sellmeier { b, c } => {
+                let sum: f64 = b.iter().zip(c.iter()).map(|(b, c)| b * w2 / (w2 - c)).sum();
+                (1.0 + sum).sqrt()
+            }
+        }
+    }
+}
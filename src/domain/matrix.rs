@@ -1,6 +1,7 @@
 use crate::domain::{Point, Vector};
 use lazy_static::lazy_static;
 use num::Integer;
+use std::array::from_fn;
 use std::ops::{Index, IndexMut, Mul};
 
 #[derive(Clone, Debug)]
@@ -205,6 +206,38 @@ impl Matrix {
         }
     }
 
+    // Blends into the other matrix, t = 0 giving this matrix and t = 1 the other. Both are split
+    // into translation, rotation and scaling: translations and scalings blend linearly, rotations
+    // along the shortest arc (quaternion slerp), so turning objects keep their shape on the way.
+    pub fn interpolate(&self, other: &Matrix, t: f64) -> Matrix {
+        let (from, to) = match (decompose(self), decompose(other)) {
+            (Some(from), Some(to)) => (from, to),
+            // singular matrices have no rotation to speak of, blend them entry by entry
+            _ => {
+                let contents = self
+                    .contents
+                    .iter()
+                    .zip(other.contents.iter())
+                    .map(|(a, b)| a + (b - a) * t)
+                    .collect();
+                return Matrix::new(self.width, self.height, contents);
+            }
+        };
+
+        let lerp = |a: f64, b: f64| a + (b - a) * t;
+        let rotation = quaternion_to_rotation(&slerp(&from.rotation, &to.rotation, t));
+        let scaling: Matrix3 =
+            from_fn(|row| from_fn(|col| lerp(from.scaling[row][col], to.scaling[row][col])));
+        let rs = multiply3(&rotation, &scaling);
+        let tr: [f64; 3] = from_fn(|i| lerp(from.translation[i], to.translation[i]));
+
+        let contents = vec![rs[0][0], rs[0][1], rs[0][2], tr[0],
+                            rs[1][0], rs[1][1], rs[1][2], tr[1],
+                            rs[2][0], rs[2][1], rs[2][2], tr[2],
+                            0.0,      0.0,      0.0,      1.0];
+        Matrix::new(4, 4, contents)
+    }
+
     pub fn is_invertible(&self) -> bool {
         self.cache
             .as_ref()
@@ -328,3 +361,157 @@ impl PartialEq for Matrix {
                 .all(|(a, b): (&f64, &f64)| crate::domain::epsilon_eq(*a, *b))
     }
 }
+
+type Matrix3 = [[f64; 3]; 3];
+
+// unit quaternion (w, x, y, z)
+type Quaternion = [f64; 4];
+
+// affine transformation split as translation * rotation * scaling (where the scaling part also
+// takes any shearing or mirroring)
+struct Decomposition {
+    translation: [f64; 3],
+    rotation: Quaternion,
+    scaling: Matrix3,
+}
+
+// Polar decomposition of the upper 3x3 part: averaging it with its inverse transpose converges
+// to the closest rotation, whatever remains is the scaling. None for singular matrices.
+fn decompose(matrix: &Matrix) -> Option<Decomposition> {
+    if matrix.width != 4 || matrix.height != 4 {
+        return Option::None;
+    }
+    let upper: Matrix3 = from_fn(|row| from_fn(|col| matrix[row][col]));
+
+    let mut rotation = upper;
+    for _ in 0..100 {
+        let inverse = invert3(&rotation)?;
+        let next: Matrix3 =
+            from_fn(|row| from_fn(|col| 0.5 * (rotation[row][col] + inverse[col][row])));
+        let change = (0..9)
+            .map(|i| (next[i / 3][i % 3] - rotation[i / 3][i % 3]).abs())
+            .fold(0.0, f64::max);
+        rotation = next;
+        if change < 1e-12 {
+            break;
+        }
+    }
+    // mirroring is left to the scaling, so the rotation is a proper one
+    if determinant3(&rotation) < 0.0 {
+        rotation = rotation.map(|row| row.map(|v| -v));
+    }
+
+    let scaling = multiply3(&invert3(&rotation)?, &upper);
+    Option::Some(Decomposition {
+        translation: [matrix[0][3], matrix[1][3], matrix[2][3]],
+        rotation: rotation_to_quaternion(&rotation),
+        scaling,
+    })
+}
+
+fn multiply3(a: &Matrix3, b: &Matrix3) -> Matrix3 {
+    from_fn(|row| from_fn(|col| (0..3).map(|i| a[row][i] * b[i][col]).sum()))
+}
+
+fn determinant3(m: &Matrix3) -> f64 {
+    m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+        - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+}
+
+fn invert3(m: &Matrix3) -> Option<Matrix3> {
+    let determinant = determinant3(m);
+    if determinant.abs() < 1e-12 {
+        return Option::None;
+    }
+    // cofactors of the transposed positions, via the cyclic neighbours
+    Option::Some(from_fn(|row| {
+        from_fn(|col| {
+            let (r1, r2) = ((col + 1) % 3, (col + 2) % 3);
+            let (c1, c2) = ((row + 1) % 3, (row + 2) % 3);
+            (m[r1][c1] * m[r2][c2] - m[r1][c2] * m[r2][c1]) / determinant
+        })
+    }))
+}
+
+fn rotation_to_quaternion(m: &Matrix3) -> Quaternion {
+    let trace = m[0][0] + m[1][1] + m[2][2];
+    if trace > 0.0 {
+        let s = (trace + 1.0).sqrt() * 2.0;
+        [
+            0.25 * s,
+            (m[2][1] - m[1][2]) / s,
+            (m[0][2] - m[2][0]) / s,
+            (m[1][0] - m[0][1]) / s,
+        ]
+    } else if m[0][0] > m[1][1] && m[0][0] > m[2][2] {
+        let s = (1.0 + m[0][0] - m[1][1] - m[2][2]).sqrt() * 2.0;
+        [
+            (m[2][1] - m[1][2]) / s,
+            0.25 * s,
+            (m[0][1] + m[1][0]) / s,
+            (m[0][2] + m[2][0]) / s,
+        ]
+    } else if m[1][1] > m[2][2] {
+        let s = (1.0 + m[1][1] - m[0][0] - m[2][2]).sqrt() * 2.0;
+        [
+            (m[0][2] - m[2][0]) / s,
+            (m[0][1] + m[1][0]) / s,
+            0.25 * s,
+            (m[1][2] + m[2][1]) / s,
+        ]
+    } else {
+        let s = (1.0 + m[2][2] - m[0][0] - m[1][1]).sqrt() * 2.0;
+        [
+            (m[1][0] - m[0][1]) / s,
+            (m[0][2] + m[2][0]) / s,
+            (m[1][2] + m[2][1]) / s,
+            0.25 * s,
+        ]
+    }
+}
+
+fn quaternion_to_rotation(q: &Quaternion) -> Matrix3 {
+    let [w, x, y, z] = *q;
+    [
+        [
+            1.0 - 2.0 * (y * y + z * z),
+            2.0 * (x * y - w * z),
+            2.0 * (x * z + w * y),
+        ],
+        [
+            2.0 * (x * y + w * z),
+            1.0 - 2.0 * (x * x + z * z),
+            2.0 * (y * z - w * x),
+        ],
+        [
+            2.0 * (x * z - w * y),
+            2.0 * (y * z + w * x),
+            1.0 - 2.0 * (x * x + y * y),
+        ],
+    ]
+}
+
+// spherical interpolation along the shorter of the two arcs between the rotations
+fn slerp(a: &Quaternion, b: &Quaternion, t: f64) -> Quaternion {
+    let mut dot: f64 = (0..4).map(|i| a[i] * b[i]).sum();
+    let mut b = *b;
+    if dot < 0.0 {
+        b = b.map(|v| -v);
+        dot = -dot;
+    }
+
+    let (wa, wb) = if dot > 0.9995 {
+        // nearly the same rotation, a straight blend is accurate enough
+        (1.0 - t, t)
+    } else {
+        let theta = dot.acos();
+        (
+            ((1.0 - t) * theta).sin() / theta.sin(),
+            (t * theta).sin() / theta.sin(),
+        )
+    };
+    let q: Vec<f64> = (0..4).map(|i| wa * a[i] + wb * b[i]).collect();
+    let length = q.iter().map(|v| v * v).sum::<f64>().sqrt();
+    [q[0] / length, q[1] / length, q[2] / length, q[3] / length]
+}
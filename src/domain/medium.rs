@@ -0,0 +1,245 @@
+use crate::domain::color::Color;
+use crate::domain::intersection::Intersections;
+use crate::domain::noise::Noise;
+use crate::domain::random::Rng;
+use crate::domain::ray::Ray;
+use crate::domain::world::World;
+use crate::domain::Point;
+
+// keeps the marching offsets uncorrelated with the other rays seeded from the same point
+const MEDIUM_SALT: u64 = 0xF0;
+
+// below this share of light left, the rest of a march can't be seen anymore
+const MIN_TRANSMITTANCE: f64 = 0.0001;
+
+// How much light a medium extinguishes per unit of distance.
+#[derive(PartialEq, Debug, Clone)]
+pub enum Density {
+    // homogeneous, the same everywhere
+    Uniform(f64),
+    // heterogeneous, fbm noise (in world space) varying the density between zero and the given one
+    Noise {
+        noise: Noise,
+        density: f64,
+        frequency: f64,
+    },
+}
+
+impl Density {
+    pub fn at(&self, point: &Point) -> f64 {
+        match self {
+            Density::Uniform(density) => *density,
+            Density::Noise {
+                noise,
+                density,
+                frequency,
+            } => {
+                let p = Point::new(
+                    point.x() * frequency,
+                    point.y() * frequency,
+                    point.z() * frequency,
+                );
+                density * (0.5 + 0.5 * noise.fbm(&p)).clamp(0.0, 1.0)
+            }
+        }
+    }
+}
+
+// Participating medium such as fog, smoke or dust: it dims the light travelling through it and
+// scatters light from the light source towards the viewer. Set as the world's fog it fills the
+// whole scene; bound to a closed object through `Material::medium` it fills that object's inside.
+// Rays only get inside by passing through the object's surface, so binding a medium makes the
+// material fully transparent by default; an opaque surface hides the medium.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Medium {
+    pub density: Density,
+    // share of the extinguished light that is scattered rather than absorbed, per channel
+    pub color: Color,
+    // henyey-greenstein asymmetry: 0 scatters evenly, towards 1 mostly forward (god rays)
+    pub anisotropy: f64,
+    // samples along every ray
+    pub steps: usize,
+    // how far the medium reaches along rays that leave the scene (only used for fog)
+    pub max_distance: f64,
+}
+
+pub struct MediumBuilder {
+    density: Density,
+    color: Option<Color>,
+    anisotropy: Option<f64>,
+    steps: Option<usize>,
+    max_distance: Option<f64>,
+}
+
+impl Medium {
+    pub const DEFAULT_ANISOTROPY: f64 = 0.0;
+    pub const DEFAULT_STEPS: usize = 32;
+    pub const DEFAULT_MAX_DISTANCE: f64 = 100.0;
+
+    // builder
+    pub fn new(density: Density) -> MediumBuilder {
+        MediumBuilder {
+            density,
+            color: Option::None,
+            anisotropy: Option::None,
+            steps: Option::None,
+            max_distance: Option::None,
+        }
+    }
+
+    // share of light scattered towards the viewer for the given angle between the viewing and
+    // the lighting direction, scaled so that it averages to one over all directions
+    pub fn phase(&self, cos_theta: f64) -> f64 {
+        let g = self.anisotropy;
+        (1.0 - g * g) / (1.0 + g * g - 2.0 * g * cos_theta).powf(1.5)
+    }
+
+    // share of light surviving the way along the ray between the given distances
+    pub fn transmittance(&self, ray: &Ray, from: f64, to: f64) -> f64 {
+        let length = to - from;
+        if length <= 0.0 {
+            return 1.0;
+        }
+        if let Density::Uniform(density) = self.density {
+            return (-density * length).exp();
+        }
+
+        let steps = self.steps.max(1);
+        let dt = length / steps as f64;
+        let optical_depth: f64 = (0..steps)
+            .map(|i| self.density.at(&ray.position(from + (i as f64 + 0.5) * dt)) * dt)
+            .sum();
+        (-optical_depth).exp()
+    }
+}
+
+impl MediumBuilder {
+    // finalizes built instance
+    pub fn build(&self) -> Medium {
+        Medium {
+            density: self.density.clone(),
+            color: self.color.unwrap_or(Color::WHITE),
+            anisotropy: self.anisotropy.unwrap_or(Medium::DEFAULT_ANISOTROPY),
+            steps: self.steps.unwrap_or(Medium::DEFAULT_STEPS),
+            max_distance: self.max_distance.unwrap_or(Medium::DEFAULT_MAX_DISTANCE),
+        }
+    }
+
+    pub fn color(&mut self, color: Color) -> &mut MediumBuilder {
+        self.color = Option::Some(color);
+        self
+    }
+
+    pub fn anisotropy(&mut self, anisotropy: f64) -> &mut MediumBuilder {
+        self.anisotropy = Option::Some(anisotropy);
+        self
+    }
+
+    pub fn steps(&mut self, steps: usize) -> &mut MediumBuilder {
+        self.steps = Option::Some(steps);
+        self
+    }
+
+    pub fn max_distance(&mut self, max_distance: f64) -> &mut MediumBuilder {
+        self.max_distance = Option::Some(max_distance);
+        self
+    }
+}
+
+impl World {
+    // Color seen along the ray once the media it travels through are taken into account: the
+    // volumes it starts inside of, then the fog. `distance` is where the ray hit something (or
+    // infinity) and `behind` the color found there.
+    pub fn through_media(&self, ray: &Ray, distance: f64, behind: Color) -> Color {
+        let (transmittance, scattered) = self.media_along(ray, distance);
+        &(&behind * transmittance as f32) + &scattered
+    }
+
+    // share of the light from up to the given distance that the media along the ray let through,
+    // and the light they scatter towards the ray's origin themselves
+    pub fn media_along(&self, ray: &Ray, distance: f64) -> (f64, Color) {
+        let mut transmittance = 1.0;
+        let mut scattered = Color::BLACK;
+        let mut add = |(t, s): (f64, Color)| {
+            transmittance *= t;
+            scattered = &(&scattered * t as f32) + &s;
+        };
+
+        for object in self.objects.iter() {
+            let medium = match object.shape().material.medium.as_ref() {
+                Some(medium) => medium,
+                None => continue,
+            };
+
+            // an odd number of crossings behind the origin puts it inside the object
+            let mut crossings = object.intersect(ray);
+            let (inside, exit) = volume_span(&mut crossings);
+            if inside {
+                add(self.march(medium, ray, distance.min(exit)));
+            }
+        }
+
+        if let Some(fog) = self.fog.as_ref() {
+            add(self.march(fog, ray, distance.min(fog.max_distance)));
+        }
+        (transmittance, scattered)
+    }
+
+    // Integrates the medium along the ray from its origin to the given distance in evenly spaced
+    // (and randomly offset) steps, gathering single scattering from the light source. Returns the
+    // transmittance over that length together with the scattered light.
+    fn march(&self, medium: &Medium, ray: &Ray, length: f64) -> (f64, Color) {
+        if length <= 0.0 || medium.steps == 0 {
+            return (1.0, Color::BLACK);
+        }
+
+        let dt = length / medium.steps as f64;
+        let direction = ray.direction.normalize();
+        // seeded by the ray so renders are repeatable
+        let jitter = Rng::from_point(&ray.origin, MEDIUM_SALT).next_f64();
+
+        let mut transmittance = 1.0;
+        let mut scattered = Color::BLACK;
+        for i in 0..medium.steps {
+            let point = ray.position((i as f64 + jitter) * dt);
+            let density = medium.density.at(&point);
+            if density <= 0.0 {
+                continue;
+            }
+            let step_transmittance = (-density * dt).exp();
+
+            if let Some(light) = self.light_source.as_ref() {
+                let to_light = (&light.position - &point).normalize();
+                let phase = medium.phase(direction.dot_product(&to_light));
+                let arriving = &light.intensity * &self.shadow_filter_at(&point, ray.time);
+                let in_scattered = &(&arriving * &medium.color) * phase as f32;
+
+                // light scattered within the step, seen through everything in front of it
+                let share = transmittance * (1.0 - step_transmittance);
+                scattered = &scattered + &(&in_scattered * share as f32);
+            }
+
+            transmittance *= step_transmittance;
+            if transmittance < MIN_TRANSMITTANCE {
+                return (0.0, scattered);
+            }
+        }
+        (transmittance, scattered)
+    }
+}
+
+// whether the ray starts inside the closed object the crossings belong to, and where it
+// first crosses its surface ahead
+fn volume_span(crossings: &mut Intersections) -> (bool, f64) {
+    let mut behind = 0;
+    let mut exit = f64::INFINITY;
+    while let Some(crossing) = crossings.hit_unchecked() {
+        if crossing.distance < 0.0 {
+            behind += 1;
+        } else {
+            exit = crossing.distance;
+            break;
+        }
+    }
+    (behind % 2 == 1, exit)
+}
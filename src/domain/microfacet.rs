@@ -0,0 +1,51 @@
+use crate::domain::color::Color;
+use crate::domain::random::{orthonormal_basis, Rng};
+use crate::domain::Vector;
+use std::f64::consts::PI;
+
+// Reflectance of non-metals at normal incidence.
+const DIELECTRIC_F0: f32 = 0.04;
+
+// Keeps perfectly smooth surfaces from collapsing the distribution into a singularity.
+const MIN_ROUGHNESS: f64 = 0.03;
+
+// GGX (Trowbridge-Reitz) normal distribution, using the common alpha = roughness^2 remapping
+pub fn ggx_distribution(n_dot_h: f64, roughness: f64) -> f64 {
+    let alpha = roughness.max(MIN_ROUGHNESS).powi(2);
+    let alpha2 = alpha * alpha;
+    let denominator = n_dot_h * n_dot_h * (alpha2 - 1.0) + 1.0;
+    alpha2 / (PI * denominator * denominator)
+}
+
+// half vector drawn around the normal proportionally to the GGX distribution times n.h
+pub fn sample_ggx_half_vector(rng: &mut Rng, normal: &Vector, roughness: f64) -> Vector {
+    let alpha = roughness.max(MIN_ROUGHNESS).powi(2);
+    let u = rng.next_f64();
+    let cos_theta = ((1.0 - u) / (1.0 + (alpha * alpha - 1.0) * u)).sqrt();
+    let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+    let phi = 2.0 * PI * rng.next_f64();
+
+    let (tangent, bitangent) = orthonormal_basis(normal);
+    let direction = &(&tangent * (sin_theta * phi.cos())) + &(&bitangent * (sin_theta * phi.sin()));
+    (&direction + &(normal * cos_theta)).normalize()
+}
+
+// Smith shadowing-masking, built from Schlick-GGX terms for the view and light directions
+pub fn smith_geometry(n_dot_v: f64, n_dot_l: f64, roughness: f64) -> f64 {
+    let k = (roughness + 1.0).powi(2) / 8.0;
+    let g1 = |n_dot_x: f64| n_dot_x / (n_dot_x * (1.0 - k) + k);
+    g1(n_dot_v) * g1(n_dot_l)
+}
+
+// Schlick's approximation of fresnel reflectance
+pub fn schlick_fresnel(f0: &Color, cos_theta: f64) -> Color {
+    let factor = (1.0 - cos_theta).max(0.0).powi(5) as f32;
+    &(f0 * (1.0 - factor)) + &(&Color::WHITE * factor)
+}
+
+// reflectance at normal incidence: metals tint their reflections with the base color
+pub fn base_reflectance(base_color: &Color, metallic: f64) -> Color {
+    let dielectric = Color::new(DIELECTRIC_F0, DIELECTRIC_F0, DIELECTRIC_F0);
+    let metallic = metallic as f32;
+    &(&dielectric * (1.0 - metallic)) + &(base_color * metallic)
+}
@@ -1,17 +1,33 @@
 use std::hash::Hash;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
+pub(crate) mod animation;
+pub(crate) mod background;
+pub(crate) mod bump;
 pub(crate) mod camera;
 pub(crate) mod canvas;
 pub(crate) mod color;
+pub(crate) mod environment;
+pub(crate) mod integrator;
 pub(crate) mod intersection;
+pub(crate) mod kdtree;
 pub(crate) mod light;
 pub(crate) mod material;
 pub(crate) mod matrix;
+pub(crate) mod medium;
+pub(crate) mod microfacet;
+pub(crate) mod noise;
 pub(crate) mod object;
+pub(crate) mod occlusion;
 pub(crate) mod operations;
 pub(crate) mod pattern;
+pub(crate) mod photon;
+pub(crate) mod ramp;
+pub(crate) mod random;
 pub(crate) mod ray;
+pub(crate) mod settings;
+pub(crate) mod subsurface;
+pub(crate) mod uv;
 pub(crate) mod world;
 
 use num::{Float, NumCast};
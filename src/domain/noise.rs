@@ -0,0 +1,230 @@
+use crate::domain::random::{split_mix, unit_float};
+use crate::domain::{Point, Vector};
+
+// Deterministic 3D gradient (Perlin) noise with fractal (fBm/turbulence) controls.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Noise {
+    pub seed: u64,
+    pub octaves: usize,
+    pub lacunarity: f64,
+    pub gain: f64,
+    permutation: Vec<usize>,
+}
+
+pub struct NoiseBuilder {
+    seed: Option<u64>,
+    octaves: Option<usize>,
+    lacunarity: Option<f64>,
+    gain: Option<f64>,
+}
+
+impl Default for Noise {
+    fn default() -> Self {
+        Noise::new().build()
+    }
+}
+
+impl Noise {
+    pub const DEFAULT_SEED: u64 = 0;
+    pub const DEFAULT_OCTAVES: usize = 4;
+    pub const DEFAULT_LACUNARITY: f64 = 2.0;
+    pub const DEFAULT_GAIN: f64 = 0.5;
+
+    // builder
+    pub fn new() -> NoiseBuilder {
+        NoiseBuilder {
+            seed: Option::None,
+            octaves: Option::None,
+            lacunarity: Option::None,
+            gain: Option::None,
+        }
+    }
+
+    // single octave of gradient noise, roughly in the [-1, 1] range
+    pub fn noise(&self, point: &Point) -> f64 {
+        let (xf, yf, zf) = (point.x().floor(), point.y().floor(), point.z().floor());
+        let (xi, yi, zi) = (
+            xf.rem_euclid(256.0) as usize,
+            yf.rem_euclid(256.0) as usize,
+            zf.rem_euclid(256.0) as usize,
+        );
+        let (x, y, z) = (point.x() - xf, point.y() - yf, point.z() - zf);
+        let (u, v, w) = (fade(x), fade(y), fade(z));
+
+        let p = &self.permutation;
+        let a = p[xi] + yi;
+        let aa = p[a] + zi;
+        let ab = p[a + 1] + zi;
+        let b = p[xi + 1] + yi;
+        let ba = p[b] + zi;
+        let bb = p[b + 1] + zi;
+
+        lerp(
+            w,
+            lerp(
+                v,
+                lerp(u, grad(p[aa], x, y, z), grad(p[ba], x - 1.0, y, z)),
+                lerp(
+                    u,
+                    grad(p[ab], x, y - 1.0, z),
+                    grad(p[bb], x - 1.0, y - 1.0, z),
+                ),
+            ),
+            lerp(
+                v,
+                lerp(
+                    u,
+                    grad(p[aa + 1], x, y, z - 1.0),
+                    grad(p[ba + 1], x - 1.0, y, z - 1.0),
+                ),
+                lerp(
+                    u,
+                    grad(p[ab + 1], x, y - 1.0, z - 1.0),
+                    grad(p[bb + 1], x - 1.0, y - 1.0, z - 1.0),
+                ),
+            ),
+        )
+    }
+
+    // fractal brownian motion: sum of octaves, each scaled in frequency by lacunarity and in
+    // amplitude by gain
+    pub fn fbm(&self, point: &Point) -> f64 {
+        self.fractal(point, |n| n)
+    }
+
+    // like fbm, but summing absolute values for sharp creases (range is [0, ~1])
+    pub fn turbulence(&self, point: &Point) -> f64 {
+        self.fractal(point, f64::abs)
+    }
+
+    // fbm evaluated at three decorrelated offsets - handy for jittering points
+    pub fn fbm_vector(&self, point: &Point) -> Vector {
+        Vector::new(
+            self.fbm(point),
+            self.fbm(&(point + &Vector::new(31.41, 59.26, 53.58))),
+            self.fbm(&(point + &Vector::new(97.93, 23.84, 62.64))),
+        )
+    }
+
+    // cellular (Worley) noise: distances to the nearest and second-nearest feature points, with
+    // one randomly placed feature point per unit cell
+    pub fn cellular(&self, point: &Point) -> (f64, f64) {
+        let (cx, cy, cz) = (
+            point.x().floor() as i64,
+            point.y().floor() as i64,
+            point.z().floor() as i64,
+        );
+
+        let (mut f1, mut f2) = (f64::INFINITY, f64::INFINITY);
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let (x, y, z) = (cx + dx, cy + dy, cz + dz);
+                    let mut state = self.seed
+                        ^ (x as u64).wrapping_mul(0x8DA6_B343)
+                        ^ (y as u64).wrapping_mul(0xD816_3841)
+                        ^ (z as u64).wrapping_mul(0xCB1A_B31F);
+                    let feature = Point::new(
+                        x as f64 + unit_float(split_mix(&mut state)),
+                        y as f64 + unit_float(split_mix(&mut state)),
+                        z as f64 + unit_float(split_mix(&mut state)),
+                    );
+
+                    let distance = (&feature - point).magnitude();
+                    if distance < f1 {
+                        f2 = f1;
+                        f1 = distance;
+                    } else if distance < f2 {
+                        f2 = distance;
+                    }
+                }
+            }
+        }
+        (f1, f2)
+    }
+
+    fn fractal<F: Fn(f64) -> f64>(&self, point: &Point, f: F) -> f64 {
+        let mut result = 0.0;
+        let mut frequency = 1.0;
+        let mut amplitude = 1.0;
+        for _ in 0..self.octaves {
+            let p = Point::new(
+                point.x() * frequency,
+                point.y() * frequency,
+                point.z() * frequency,
+            );
+            result += amplitude * f(self.noise(&p));
+            frequency *= self.lacunarity;
+            amplitude *= self.gain;
+        }
+        result
+    }
+}
+
+impl NoiseBuilder {
+    // finalizes built instance
+    pub fn build(&self) -> Noise {
+        let seed = self.seed.unwrap_or(Noise::DEFAULT_SEED);
+        Noise {
+            seed,
+            octaves: self.octaves.unwrap_or(Noise::DEFAULT_OCTAVES),
+            lacunarity: self.lacunarity.unwrap_or(Noise::DEFAULT_LACUNARITY),
+            gain: self.gain.unwrap_or(Noise::DEFAULT_GAIN),
+            permutation: permutation(seed),
+        }
+    }
+
+    pub fn seed(&mut self, seed: u64) -> &mut NoiseBuilder {
+        self.seed = Option::Some(seed);
+        self
+    }
+
+    pub fn octaves(&mut self, octaves: usize) -> &mut NoiseBuilder {
+        self.octaves = Option::Some(octaves);
+        self
+    }
+
+    pub fn lacunarity(&mut self, lacunarity: f64) -> &mut NoiseBuilder {
+        self.lacunarity = Option::Some(lacunarity);
+        self
+    }
+
+    pub fn gain(&mut self, gain: f64) -> &mut NoiseBuilder {
+        self.gain = Option::Some(gain);
+        self
+    }
+}
+
+// seeded shuffle of 0..256, doubled up to avoid index wrapping
+fn permutation(seed: u64) -> Vec<usize> {
+    let mut table: Vec<usize> = (0..256).collect();
+    let mut state = seed;
+    for i in (1..table.len()).rev() {
+        let j = (split_mix(&mut state) % (i as u64 + 1)) as usize;
+        table.swap(i, j);
+    }
+    table.extend_from_within(..);
+    table
+}
+
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(t: f64, a: f64, b: f64) -> f64 {
+    a + t * (b - a)
+}
+
+// dot product of the distance vector with one of 12 cube-edge gradients
+fn grad(hash: usize, x: f64, y: f64, z: f64) -> f64 {
+    let h = hash & 15;
+    let u = if h < 8 { x } else { y };
+    let v = if h < 4 {
+        y
+    } else if h == 12 || h == 14 {
+        x
+    } else {
+        z
+    };
+    (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
@@ -3,14 +3,24 @@ use crate::domain::material::Material;
 use crate::domain::matrix::Matrix;
 use crate::domain::ray::Ray;
 use crate::domain::{Id, Point, RayTuple, Vector};
+use std::any::Any;
 use std::fmt::{Debug, Formatter};
 
 #[derive(PartialEq, Debug, Clone)]
 pub struct Shape {
     pub id: Id,
     pub transformation: Matrix,
+    // pose at the end of the frame (time 1) for moving shapes, which move from `transformation`
+    // (time 0) towards it
+    pub end_transformation: Option<Matrix>,
     pub material: Material,
     pub shape_type_name: String,
+    // whether the shape blocks light on its way to other surfaces
+    pub casts_shadow: bool,
+    // whether other shapes' shadows darken this shape
+    pub receives_shadow: bool,
+    // whether camera rays see the shape (it still shows up in reflections and refractions)
+    pub visible_to_camera: bool,
 }
 
 #[derive(PartialEq, Debug, Clone)]
@@ -52,14 +62,72 @@ pub struct Cone {
     pub closed: bool,
 }
 
-#[derive(PartialEq, Clone)]
-pub enum Object {
-    Sphere(Sphere),
-    Null(Null), // throw-away test implementation
-    Plane(Plane),
-    Cube(Cube),
-    Cylinder(Cylinder),
-    Cone(Cone),
+// Object-space behaviour shared by every renderable shape. Implement this (alongside `Clone` and
+// `PartialEq`) to add new kinds of shapes to a `World` next to the built-in ones.
+pub trait Geometry: GeometryObject + Send + Sync {
+    // common shape attributes (id, transformation, material...)
+    fn shape(&self) -> &Shape;
+
+    fn shape_mut(&mut self) -> &mut Shape;
+
+    // Finds intersection distances of a ray already converted into object space.
+    fn local_intersect(&self, ray: &Ray) -> Vec<f64>;
+
+    // Computes the normal at given object-space point.
+    fn local_normal_at(&self, point: &Point) -> Vector;
+
+    // Axis-aligned box enclosing the shape in object space.
+    fn bounds(&self) -> Bounds;
+
+    // Maps given object-space point to (u, v) texture coordinates in the [0, 1) range.
+    fn uv_at(&self, point: &Point) -> (f64, f64) {
+        crate::domain::uv::planar_map(point)
+    }
+}
+
+// Allows boxed geometries to be cloned, compared and downcast (blanket-implemented for any
+// `Geometry + Clone + PartialEq`).
+pub trait GeometryObject {
+    fn clone_box(&self) -> Box<dyn Geometry>;
+    fn eq_box(&self, other: &dyn Geometry) -> bool;
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T> GeometryObject for T
+where
+    T: 'static + Geometry + Clone + PartialEq,
+{
+    fn clone_box(&self) -> Box<dyn Geometry> {
+        Box::new(self.clone())
+    }
+
+    fn eq_box(&self, other: &dyn Geometry) -> bool {
+        match other.as_any().downcast_ref::<T>() {
+            Some(o) => self == o,
+            None => false,
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Bounds {
+    pub minimum: Point,
+    pub maximum: Point,
+}
+
+impl Bounds {
+    // constructor
+    pub fn new(minimum: Point, maximum: Point) -> Bounds {
+        Bounds { minimum, maximum }
+    }
+}
+
+pub struct Object {
+    geometry: Box<dyn Geometry>,
 }
 
 impl Debug for Object {
@@ -74,53 +142,34 @@ impl Debug for Object {
     }
 }
 
-impl From<Sphere> for Object {
-    fn from(v: Sphere) -> Self {
-        Object::Sphere(v)
-    }
-}
-
-impl From<Plane> for Object {
-    fn from(v: Plane) -> Self {
-        Object::Plane(v)
-    }
-}
-
-impl From<Null> for Object {
-    fn from(v: Null) -> Self {
-        Object::Null(v)
-    }
-}
-
-impl From<Cube> for Object {
-    fn from(v: Cube) -> Self {
-        Object::Cube(v)
+impl Clone for Object {
+    fn clone(&self) -> Self {
+        Object {
+            geometry: self.geometry.clone_box(),
+        }
     }
 }
 
-impl From<Cylinder> for Object {
-    fn from(v: Cylinder) -> Self {
-        Object::Cylinder(v)
+impl PartialEq for Object {
+    fn eq(&self, other: &Self) -> bool {
+        self.geometry.eq_box(other.geometry.as_ref())
     }
 }
 
-impl From<Cone> for Object {
-    fn from(v: Cone) -> Self {
-        Object::Cone(v)
+impl<G> From<G> for Object
+where
+    G: 'static + Geometry,
+{
+    fn from(v: G) -> Self {
+        Object {
+            geometry: Box::new(v),
+        }
     }
 }
 
 impl Object {
-    // TODO Define trait that returns these, so that the match is not necessary.
     fn local_intersect(&self, ray: &Ray) -> Intersections {
-        let ints = match self {
-            Object::Sphere(sphere) => sphere.local_intersect(ray),
-            Object::Null(null) => null.local_intersect(ray),
-            Object::Plane(plane) => plane.local_intersect(ray),
-            Object::Cube(cube) => cube.local_intersect(ray),
-            Object::Cylinder(cylinder) => cylinder.local_intersect(ray),
-            Object::Cone(cone) => cone.local_intersect(ray),
-        };
+        let ints = self.geometry.local_intersect(ray);
         let mut result = Intersections::new();
         ints.iter().for_each(|int| {
             result.push(Intersection::new(*int, self));
@@ -128,62 +177,71 @@ impl Object {
         result
     }
 
-    // TODO Define trait that returns these, so that the match is not necessary.
     fn local_normal_at(&self, point: &Point) -> Vector {
-        match self {
-            Object::Sphere(sphere) => sphere.local_normal_at(point),
-            Object::Null(null) => null.local_normal_at(point),
-            Object::Plane(plane) => plane.local_normal_at(point),
-            Object::Cube(cube) => cube.local_normal_at(point),
-            Object::Cylinder(cylinder) => cylinder.local_normal_at(point),
-            Object::Cone(cone) => cone.local_normal_at(point),
-        }
+        self.geometry.local_normal_at(point)
     }
 
-    // TODO Define trait that returns these, so that the match is not necessary.
     pub fn shape(&self) -> &Shape {
-        match self {
-            Object::Sphere(sphere) => &sphere.shape,
-            Object::Null(null) => &null.shape,
-            Object::Plane(plane) => &plane.shape,
-            Object::Cube(cube) => &cube.shape,
-            Object::Cylinder(cylinder) => &cylinder.shape,
-            Object::Cone(cone) => &cone.shape,
-        }
+        self.geometry.shape()
     }
 
-    // TODO Define trait that returns these, so that the match is not necessary.
     pub fn shape_mut(&mut self) -> &mut Shape {
-        match self {
-            Object::Sphere(sphere) => &mut sphere.shape,
-            Object::Null(null) => &mut null.shape,
-            Object::Plane(plane) => &mut plane.shape,
-            Object::Cube(cube) => &mut cube.shape,
-            Object::Cylinder(cylinder) => &mut cylinder.shape,
-            Object::Cone(cone) => &mut cone.shape,
-        }
+        self.geometry.shape_mut()
+    }
+
+    // underlying shape implementation
+    pub fn geometry(&self) -> &dyn Geometry {
+        self.geometry.as_ref()
+    }
+
+    // object-space bounding box
+    pub fn bounds(&self) -> Bounds {
+        self.geometry.bounds()
     }
 
     // Finds intersections of ray against sphere instance
     pub fn intersect(&self, ray: &Ray) -> Intersections {
-        let inv_transform = self.shape().transformation.inverse();
-        if inv_transform.is_none() {
-            panic!("Unexpected non-invertible matrix.");
-        }
-        let localized_ray = ray.transform(&inv_transform.unwrap());
+        let localized_ray = ray.transform(&self.inverse_transformation_at(ray.time));
 
         self.local_intersect(&localized_ray)
     }
 
     // Computes the normal at given point.
     pub fn normal_at(&self, point: &Point) -> Vector {
-        let mut st_inv = self.shape().transformation.inverse().unwrap();
+        self.normal_at_time(point, 0.0)
+    }
+
+    // Computes the normal at given point, with the shape in its pose at the given time.
+    pub fn normal_at_time(&self, point: &Point, time: f64) -> Vector {
+        let mut st_inv = self.inverse_transformation_at(time);
         let local_point = &st_inv * point;
         let local_normal = self.local_normal_at(&local_point);
         let world_normal = &*st_inv.transpose() * &local_normal;
 
         world_normal.normalize()
     }
+
+    // Transformation of the shape at the given time within the frame, interpolated between the
+    // start and end pose.
+    pub fn transformation_at(&self, time: f64) -> Matrix {
+        let shape = self.shape();
+        match &shape.end_transformation {
+            Some(end) => shape.transformation.interpolate(end, time.clamp(0.0, 1.0)),
+            None => shape.transformation.clone(),
+        }
+    }
+
+    // Inverse of the shape's transformation at the given time, static shapes reuse the cached one.
+    pub fn inverse_transformation_at(&self, time: f64) -> Matrix {
+        let inverse = match self.shape().end_transformation {
+            Some(_) => self.transformation_at(time).inverse(),
+            None => self.shape().transformation.inverse(),
+        };
+        match inverse {
+            Some(inverse) => inverse,
+            None => panic!("Unexpected non-invertible matrix."),
+        }
+    }
 }
 
 impl Default for Shape {
@@ -191,8 +249,12 @@ impl Default for Shape {
         Shape {
             id: Id::new(),
             transformation: crate::domain::matrix::IDENTITY.clone(),
+            end_transformation: Option::None,
             material: Material::default(),
             shape_type_name: String::default(),
+            casts_shadow: true,
+            receives_shadow: true,
+            visible_to_camera: true,
         }
     }
 }
@@ -206,16 +268,24 @@ impl Shape {
     pub fn new(shape_type_name: &str) -> ShapeBuilder {
         ShapeBuilder {
             transformation: Option::None,
+            end_transformation: Option::None,
             material: Option::None,
             shape_type_name: shape_type_name.parse().unwrap(),
+            casts_shadow: Option::None,
+            receives_shadow: Option::None,
+            visible_to_camera: Option::None,
         }
     }
 }
 
 pub struct ShapeBuilder {
     transformation: Option<Matrix>,
+    end_transformation: Option<Matrix>,
     material: Option<Material>,
     shape_type_name: String,
+    casts_shadow: Option<bool>,
+    receives_shadow: Option<bool>,
+    visible_to_camera: Option<bool>,
 }
 
 impl ShapeBuilder {
@@ -224,11 +294,31 @@ impl ShapeBuilder {
         self
     }
 
+    pub fn end_transformation(&mut self, end_transformation: Matrix) -> &mut ShapeBuilder {
+        self.end_transformation = Option::Some(end_transformation);
+        self
+    }
+
     pub fn material(&mut self, material: Material) -> &mut ShapeBuilder {
         self.material = Option::Some(material);
         self
     }
 
+    pub fn casts_shadow(&mut self, casts_shadow: bool) -> &mut ShapeBuilder {
+        self.casts_shadow = Option::Some(casts_shadow);
+        self
+    }
+
+    pub fn receives_shadow(&mut self, receives_shadow: bool) -> &mut ShapeBuilder {
+        self.receives_shadow = Option::Some(receives_shadow);
+        self
+    }
+
+    pub fn visible_to_camera(&mut self, visible_to_camera: bool) -> &mut ShapeBuilder {
+        self.visible_to_camera = Option::Some(visible_to_camera);
+        self
+    }
+
     pub fn build(&self) -> Shape {
         Shape {
             id: Id::new(),
@@ -236,16 +326,51 @@ impl ShapeBuilder {
                 .transformation
                 .clone()
                 .unwrap_or(crate::domain::matrix::IDENTITY.clone()),
+            end_transformation: self.end_transformation.clone(),
             material: self.material.clone().unwrap_or(Material::default()),
             shape_type_name: self.shape_type_name.clone(),
+            casts_shadow: self.casts_shadow.unwrap_or(true),
+            receives_shadow: self.receives_shadow.unwrap_or(true),
+            visible_to_camera: self.visible_to_camera.unwrap_or(true),
         }
     }
 }
 
+// Shape settings shared by the builders of all geometries, each set once on their shape builder.
+pub trait GeometryBuilder {
+    fn shape_builder(&mut self) -> &mut ShapeBuilder;
+
+    fn end_transformation(&mut self, end_transformation: Matrix) -> &mut Self {
+        self.shape_builder().end_transformation(end_transformation);
+        self
+    }
+
+    fn casts_shadow(&mut self, casts_shadow: bool) -> &mut Self {
+        self.shape_builder().casts_shadow(casts_shadow);
+        self
+    }
+
+    fn receives_shadow(&mut self, receives_shadow: bool) -> &mut Self {
+        self.shape_builder().receives_shadow(receives_shadow);
+        self
+    }
+
+    fn visible_to_camera(&mut self, visible_to_camera: bool) -> &mut Self {
+        self.shape_builder().visible_to_camera(visible_to_camera);
+        self
+    }
+}
+
 pub struct NullBuilder {
     shape_builder: ShapeBuilder,
 }
 
+impl GeometryBuilder for NullBuilder {
+    fn shape_builder(&mut self) -> &mut ShapeBuilder {
+        &mut self.shape_builder
+    }
+}
+
 impl NullBuilder {
     pub fn transformation(&mut self, transformation: Matrix) -> &mut NullBuilder {
         self.shape_builder.transformation(transformation);
@@ -269,7 +394,18 @@ impl Null {
             shape_builder: Shape::new("Null"),
         }
     }
-    pub(crate) fn local_intersect(&self, _ray: &Ray) -> Vec<f64> {
+}
+
+impl Geometry for Null {
+    fn shape(&self) -> &Shape {
+        &self.shape
+    }
+
+    fn shape_mut(&mut self) -> &mut Shape {
+        &mut self.shape
+    }
+
+    fn local_intersect(&self, _ray: &Ray) -> Vec<f64> {
         // NOTE: Ch9 - test 3 require mutability on an intersect calculation that otherwise
         // never leads to mutable state. This commented-out line below causes the API to lead
         // through to other callers - leading to additional complications with lifetimes and
@@ -280,15 +416,25 @@ impl Null {
         vec![]
     }
 
-    pub(crate) fn local_normal_at(&self, point: &Point) -> Vector {
+    fn local_normal_at(&self, point: &Point) -> Vector {
         Vector::new(point.x(), point.y(), point.z())
     }
+
+    fn bounds(&self) -> Bounds {
+        Bounds::new(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0))
+    }
 }
 
 pub struct PlaneBuilder {
     shape_builder: ShapeBuilder,
 }
 
+impl GeometryBuilder for PlaneBuilder {
+    fn shape_builder(&mut self) -> &mut ShapeBuilder {
+        &mut self.shape_builder
+    }
+}
+
 impl PlaneBuilder {
     pub fn transformation(&mut self, transformation: Matrix) -> &mut PlaneBuilder {
         self.shape_builder.transformation(transformation);
@@ -313,8 +459,18 @@ impl Plane {
             shape_builder: Shape::new("Plane"),
         }
     }
+}
 
-    pub(crate) fn local_intersect(&self, ray: &Ray) -> Vec<f64> {
+impl Geometry for Plane {
+    fn shape(&self) -> &Shape {
+        &self.shape
+    }
+
+    fn shape_mut(&mut self) -> &mut Shape {
+        &mut self.shape
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Vec<f64> {
         let result;
         if ray.direction.y().abs() < crate::domain::EPSILON {
             result = Vec::new()
@@ -325,9 +481,16 @@ impl Plane {
         result
     }
 
-    pub(crate) fn local_normal_at(&self, _point: &Point) -> Vector {
+    fn local_normal_at(&self, _point: &Point) -> Vector {
         Vector::new(0.0, 1.0, 0.0)
     }
+
+    fn bounds(&self) -> Bounds {
+        Bounds::new(
+            Point::new(-f64::INFINITY, 0.0, -f64::INFINITY),
+            Point::new(f64::INFINITY, 0.0, f64::INFINITY),
+        )
+    }
 }
 
 pub struct SphereBuilder {
@@ -335,6 +498,12 @@ pub struct SphereBuilder {
     origin: Option<Point>,
 }
 
+impl GeometryBuilder for SphereBuilder {
+    fn shape_builder(&mut self) -> &mut ShapeBuilder {
+        &mut self.shape_builder
+    }
+}
+
 impl SphereBuilder {
     pub fn transformation(&mut self, transformation: Matrix) -> &mut SphereBuilder {
         self.shape_builder.transformation(transformation);
@@ -377,6 +546,16 @@ impl Sphere {
             shape_builder: Shape::new("Sphere"),
         }
     }
+}
+
+impl Geometry for Sphere {
+    fn shape(&self) -> &Shape {
+        &self.shape
+    }
+
+    fn shape_mut(&mut self) -> &mut Shape {
+        &mut self.shape
+    }
 
     // Finds intersections of ray against sphere instance
     fn local_intersect(&self, localized_ray: &Ray) -> Vec<f64> {
@@ -402,12 +581,27 @@ impl Sphere {
     fn local_normal_at(&self, point: &Point) -> Vector {
         point - &Sphere::ORIGIN
     }
+
+    fn bounds(&self) -> Bounds {
+        let radius = Vector::new(1.0, 1.0, 1.0);
+        Bounds::new(&self.origin - &radius, &self.origin + &radius)
+    }
+
+    fn uv_at(&self, point: &Point) -> (f64, f64) {
+        crate::domain::uv::spherical_map(point)
+    }
 }
 
 pub struct CubeBuilder {
     shape_builder: ShapeBuilder,
 }
 
+impl GeometryBuilder for CubeBuilder {
+    fn shape_builder(&mut self) -> &mut ShapeBuilder {
+        &mut self.shape_builder
+    }
+}
+
 impl CubeBuilder {
     pub fn transformation(&mut self, transformation: Matrix) -> &mut CubeBuilder {
         self.shape_builder.transformation(transformation);
@@ -433,21 +627,6 @@ impl Cube {
         }
     }
 
-    pub(crate) fn local_intersect(&self, ray: &Ray) -> Vec<f64> {
-        let (x_tmin, x_tmax) = Cube::check_axis(ray.origin.x(), ray.direction.x());
-        let (y_tmin, y_tmax) = Cube::check_axis(ray.origin.y(), ray.direction.y());
-        let (z_tmin, z_tmax) = Cube::check_axis(ray.origin.z(), ray.direction.z());
-
-        let tmin = x_tmin.max(y_tmin.max(z_tmin));
-        let tmax = x_tmax.min(y_tmax.min(z_tmax));
-
-        if tmin > tmax {
-            vec![]
-        } else {
-            vec![tmin, tmax]
-        }
-    }
-
     fn check_axis(origin_component: f64, direction_component: f64) -> (f64, f64) {
         let tmin_numerator = -1.0 - origin_component;
         let tmax_numerator = 1.0 - origin_component;
@@ -467,8 +646,33 @@ impl Cube {
             (tmin, tmax)
         }
     }
+}
+
+impl Geometry for Cube {
+    fn shape(&self) -> &Shape {
+        &self.shape
+    }
 
-    pub(crate) fn local_normal_at(&self, point: &Point) -> Vector {
+    fn shape_mut(&mut self) -> &mut Shape {
+        &mut self.shape
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Vec<f64> {
+        let (x_tmin, x_tmax) = Cube::check_axis(ray.origin.x(), ray.direction.x());
+        let (y_tmin, y_tmax) = Cube::check_axis(ray.origin.y(), ray.direction.y());
+        let (z_tmin, z_tmax) = Cube::check_axis(ray.origin.z(), ray.direction.z());
+
+        let tmin = x_tmin.max(y_tmin.max(z_tmin));
+        let tmax = x_tmax.min(y_tmax.min(z_tmax));
+
+        if tmin > tmax {
+            vec![]
+        } else {
+            vec![tmin, tmax]
+        }
+    }
+
+    fn local_normal_at(&self, point: &Point) -> Vector {
         let max_c = point.x().abs().max(point.y().abs().max(point.z().abs()));
 
         if max_c == point.x().abs() {
@@ -479,6 +683,14 @@ impl Cube {
             Vector::new(0.0, 0.0, point.z())
         }
     }
+
+    fn bounds(&self) -> Bounds {
+        Bounds::new(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0))
+    }
+
+    fn uv_at(&self, point: &Point) -> (f64, f64) {
+        crate::domain::uv::cube_map(point)
+    }
 }
 
 pub struct CylinderBuilder {
@@ -488,6 +700,12 @@ pub struct CylinderBuilder {
     closed: Option<bool>,
 }
 
+impl GeometryBuilder for CylinderBuilder {
+    fn shape_builder(&mut self) -> &mut ShapeBuilder {
+        &mut self.shape_builder
+    }
+}
+
 impl CylinderBuilder {
     pub fn transformation(&mut self, transformation: Matrix) -> &mut CylinderBuilder {
         self.shape_builder.transformation(transformation);
@@ -533,7 +751,40 @@ impl Cylinder {
         }
     }
 
-    pub(crate) fn local_intersect(&self, ray: &Ray) -> Vec<f64> {
+    fn check_cap(ray: &Ray, t: f64) -> bool {
+        let x = ray.origin.x() + t * ray.direction.x();
+        let z = ray.origin.z() + t * ray.direction.z();
+
+        (x.powi(2) + z.powi(2)) <= 1.0
+    }
+
+    fn intersect_caps(&self, ray: &Ray, xs: &mut Vec<f64>) {
+        if !self.closed || ray.direction.y().abs() < crate::domain::EPSILON {
+            return;
+        }
+
+        let t = (self.minimum - ray.origin.y()) / ray.direction.y();
+        if Cylinder::check_cap(ray, t) {
+            xs.push(t);
+        }
+
+        let t = (self.maximum - ray.origin.y()) / ray.direction.y();
+        if Cylinder::check_cap(ray, t) {
+            xs.push(t);
+        }
+    }
+}
+
+impl Geometry for Cylinder {
+    fn shape(&self) -> &Shape {
+        &self.shape
+    }
+
+    fn shape_mut(&mut self) -> &mut Shape {
+        &mut self.shape
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Vec<f64> {
         let a = ray.direction.x().powi(2) + ray.direction.z().powi(2);
 
         let mut xs = Vec::new();
@@ -568,30 +819,7 @@ impl Cylinder {
         xs
     }
 
-    fn check_cap(ray: &Ray, t: f64) -> bool {
-        let x = ray.origin.x() + t * ray.direction.x();
-        let z = ray.origin.z() + t * ray.direction.z();
-
-        (x.powi(2) + z.powi(2)) <= 1.0
-    }
-
-    fn intersect_caps(&self, ray: &Ray, xs: &mut Vec<f64>) {
-        if !self.closed || ray.direction.y().abs() < crate::domain::EPSILON {
-            return;
-        }
-
-        let t = (self.minimum - ray.origin.y()) / ray.direction.y();
-        if Cylinder::check_cap(ray, t) {
-            xs.push(t);
-        }
-
-        let t = (self.maximum - ray.origin.y()) / ray.direction.y();
-        if Cylinder::check_cap(ray, t) {
-            xs.push(t);
-        }
-    }
-
-    pub(crate) fn local_normal_at(&self, point: &Point) -> Vector {
+    fn local_normal_at(&self, point: &Point) -> Vector {
         let dist = point.x().powi(2) + point.z().powi(2);
 
         if dist < 1.0 && point.y() >= self.maximum - crate::domain::EPSILON {
@@ -602,6 +830,17 @@ impl Cylinder {
             Vector::new(point.x(), 0.0, point.z())
         }
     }
+
+    fn bounds(&self) -> Bounds {
+        Bounds::new(
+            Point::new(-1.0, self.minimum, -1.0),
+            Point::new(1.0, self.maximum, 1.0),
+        )
+    }
+
+    fn uv_at(&self, point: &Point) -> (f64, f64) {
+        crate::domain::uv::cylindrical_map(point)
+    }
 }
 
 pub struct ConeBuilder {
@@ -611,6 +850,12 @@ pub struct ConeBuilder {
     closed: Option<bool>,
 }
 
+impl GeometryBuilder for ConeBuilder {
+    fn shape_builder(&mut self) -> &mut ShapeBuilder {
+        &mut self.shape_builder
+    }
+}
+
 impl ConeBuilder {
     pub fn transformation(&mut self, transformation: Matrix) -> &mut ConeBuilder {
         self.shape_builder.transformation(transformation);
@@ -656,7 +901,40 @@ impl Cone {
         }
     }
 
-    pub(crate) fn local_intersect(&self, ray: &Ray) -> Vec<f64> {
+    fn check_cap(ray: &Ray, t: f64, radius: f64) -> bool {
+        let x = ray.origin.x() + t * ray.direction.x();
+        let z = ray.origin.z() + t * ray.direction.z();
+
+        (x.powi(2) + z.powi(2)) <= radius
+    }
+
+    fn intersect_caps(&self, ray: &Ray, xs: &mut Vec<f64>) {
+        if !self.closed || ray.direction.y().abs() < crate::domain::EPSILON {
+            return;
+        }
+
+        let t = (self.minimum - ray.origin.y()) / ray.direction.y();
+        if Cone::check_cap(ray, t, self.minimum.abs()) {
+            xs.push(t);
+        }
+
+        let t = (self.maximum - ray.origin.y()) / ray.direction.y();
+        if Cone::check_cap(ray, t, self.maximum.abs()) {
+            xs.push(t);
+        }
+    }
+}
+
+impl Geometry for Cone {
+    fn shape(&self) -> &Shape {
+        &self.shape
+    }
+
+    fn shape_mut(&mut self) -> &mut Shape {
+        &mut self.shape
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Vec<f64> {
         let a = ray.direction.x().powi(2) - ray.direction.y().powi(2) + ray.direction.z().powi(2);
         let b = 2.0 * ray.origin.x() * ray.direction.x() - 2.0 * ray.origin.y() * ray.direction.y()
             + 2.0 * ray.origin.z() * ray.direction.z();
@@ -703,30 +981,7 @@ impl Cone {
         xs
     }
 
-    fn check_cap(ray: &Ray, t: f64, radius: f64) -> bool {
-        let x = ray.origin.x() + t * ray.direction.x();
-        let z = ray.origin.z() + t * ray.direction.z();
-
-        (x.powi(2) + z.powi(2)) <= radius
-    }
-
-    fn intersect_caps(&self, ray: &Ray, xs: &mut Vec<f64>) {
-        if !self.closed || ray.direction.y().abs() < crate::domain::EPSILON {
-            return;
-        }
-
-        let t = (self.minimum - ray.origin.y()) / ray.direction.y();
-        if Cone::check_cap(ray, t, self.minimum.abs()) {
-            xs.push(t);
-        }
-
-        let t = (self.maximum - ray.origin.y()) / ray.direction.y();
-        if Cone::check_cap(ray, t, self.maximum.abs()) {
-            xs.push(t);
-        }
-    }
-
-    pub(crate) fn local_normal_at(&self, point: &Point) -> Vector {
+    fn local_normal_at(&self, point: &Point) -> Vector {
         let dist = point.x().powi(2) + point.z().powi(2);
 
         if dist < 1.0 && point.y() >= self.maximum - crate::domain::EPSILON {
@@ -742,4 +997,16 @@ impl Cone {
             Vector::new(point.x(), y, point.z())
         }
     }
+
+    fn bounds(&self) -> Bounds {
+        let radius = self.minimum.abs().max(self.maximum.abs());
+        Bounds::new(
+            Point::new(-radius, self.minimum, -radius),
+            Point::new(radius, self.maximum, radius),
+        )
+    }
+
+    fn uv_at(&self, point: &Point) -> (f64, f64) {
+        crate::domain::uv::cylindrical_map(point)
+    }
 }
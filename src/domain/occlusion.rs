@@ -0,0 +1,92 @@
+use crate::domain::color::Color;
+use crate::domain::intersection::Computations;
+use crate::domain::light::Light;
+use crate::domain::random::{sample_cosine_hemisphere, Rng};
+use crate::domain::ray::Ray;
+use crate::domain::world::World;
+
+// keeps occlusion rays uncorrelated with the other rays seeded from the same point
+const OCCLUSION_SALT: u64 = 0xA0;
+
+// Darkens the ambient term where nearby geometry blocks the surrounding hemisphere, so crevices
+// and contact points look darker than open surfaces.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct AmbientOcclusion {
+    pub samples: usize,
+    // occluders further away than this are ignored
+    pub distance: f64,
+}
+
+impl AmbientOcclusion {
+    pub const DEFAULT_SAMPLES: usize = 16;
+    pub const DEFAULT_DISTANCE: f64 = 1.0;
+
+    // constructor
+    pub fn new(samples: usize, distance: f64) -> AmbientOcclusion {
+        AmbientOcclusion { samples, distance }
+    }
+}
+
+impl Default for AmbientOcclusion {
+    fn default() -> Self {
+        AmbientOcclusion::new(
+            AmbientOcclusion::DEFAULT_SAMPLES,
+            AmbientOcclusion::DEFAULT_DISTANCE,
+        )
+    }
+}
+
+impl World {
+    // share of cosine-weighted hemisphere rays from the point that travel the settings' distance
+    // without hitting anything (1 is fully open, 0 fully enclosed)
+    pub fn ambient_visibility(&self, comps: &Computations, settings: &AmbientOcclusion) -> f64 {
+        if settings.samples == 0 {
+            return 1.0;
+        }
+
+        // seeded by the shaded point so renders are repeatable
+        let mut rng = Rng::from_point(&comps.over_point, OCCLUSION_SALT);
+        let open = (0..settings.samples)
+            .filter(|_| {
+                let direction = sample_cosine_hemisphere(&mut rng, &comps.normal_v);
+                let ray = Ray::new(comps.over_point, direction).with_time(comps.time);
+                let mut ints = self.intersect(&ray);
+                match ints.hit() {
+                    Some(hit) => hit.distance >= settings.distance,
+                    None => true,
+                }
+            })
+            .count();
+        open as f64 / settings.samples as f64
+    }
+
+    // the part of the light's ambient term that is blocked by nearby geometry
+    pub fn occluded_ambient(&self, comps: &Computations, light: &Light) -> Color {
+        let settings = match self.ambient_occlusion.as_ref() {
+            Some(settings) => settings,
+            None => return Color::BLACK,
+        };
+        let material = &comps.object.shape().material;
+        if material.ambient == 0.0 {
+            return Color::BLACK;
+        }
+
+        let color = material.color_at_time(comps.object, &comps.over_point, comps.time);
+        let ambient = Light::ambient(material, &color, light);
+        &ambient * (1.0 - self.ambient_visibility(comps, settings)) as f32
+    }
+
+    // clay preview: every surface is white, darkened only by its ambient occlusion
+    pub fn occlusion_color_at(&self, ray: &Ray, settings: &AmbientOcclusion) -> Color {
+        let mut ints = self.intersect_from_camera(ray);
+        let original_ints = ints.clone();
+        match ints.hit() {
+            Some(hit) => {
+                let comps =
+                    Computations::prepare_computations(&hit, ray, Option::Some(&original_ints));
+                &Color::WHITE * self.ambient_visibility(&comps, settings) as f32
+            }
+            None => Color::WHITE,
+        }
+    }
+}
@@ -1,184 +1,700 @@
+use crate::domain::canvas::Canvas;
 use crate::domain::color::Color;
 use crate::domain::matrix::Matrix;
+use crate::domain::noise::Noise;
 use crate::domain::object::Object;
+use crate::domain::ramp::ColorRamp;
+use crate::domain::uv::UvMapping;
 use crate::domain::Point;
+use std::any::Any;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
 
-#[derive(PartialEq, Debug, Clone)]
-pub enum Pattern {
-    STRIPED {
-        a: Color,
-        b: Color,
-        transformation: Matrix,
-    },
-    GRADIENT {
-        a: Color,
-        b: Color,
-        transformation: Matrix,
-    },
-    RINGED {
-        a: Color,
-        b: Color,
-        transformation: Matrix,
-    },
-    CHECKERED {
-        a: Color,
-        b: Color,
-        transformation: Matrix,
-    },
-    NULL {
-        transformation: Matrix,
-    },
-}
-
-// pub struct TwoColorBuilder {
-//     a: Color,
-//     b: Color,
-//     transformation: Option<Matrix>,
-// }
-//
-// impl TwoColorBuilder {
-//     pub fn transformation(&mut self, t: Matrix) -> &mut TwoColorBuilder {
-//         self.transformation = Option::Some(t);
-//         self
-//     }
-// }
-//
-// pub struct StripedBuilder(TwoColorBuilder);
-//
-// impl StripedBuilder {
-//     pub fn build(&self) -> Pattern {
-//         Pattern::STRIPED {
-//             a: self.a,
-//             b: self.b,
-//             transformation: self
-//                 .transformation
-//                 .unwrap_or(crate::domain::matrix::IDENTITY.clone()),
-//         }
-//     }
-// }
-//
-// impl Deref for StripedBuilder {
-//     type Target = TwoColorBuilder;
-//     fn deref(&self) -> &Self::Target {
-//         &self.0
-//     }
-// }
-//
-// impl DerefMut for StripedBuilder {
-//     fn deref_mut(&mut self) -> &mut Self::Target {
-//         &mut self.0
-//     }
-// }
-//
-// impl From<&mut TwoColorBuilder> for StripedBuilder {
-//     fn from(b: &mut TwoColorBuilder) -> Self {
-//         StripedBuilder(b)
-//     }
-// }
+// Computes colors in pattern space. Implement this (alongside `Clone`, `PartialEq` and `Debug`) to
+// provide custom patterns; wrap instances in a `Pattern` to give them a transformation.
+pub trait Texture: TextureObject + Debug + Send + Sync {
+    // calculates color at given point (already converted into pattern space)
+    fn color_at(&self, pattern_point: &Point) -> Color;
+}
+
+// Allows boxed textures to be cloned, compared and downcast (blanket-implemented for any
+// `Texture + Clone + PartialEq`).
+pub trait TextureObject {
+    fn clone_box(&self) -> Box<dyn Texture>;
+    fn eq_box(&self, other: &dyn Texture) -> bool;
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T> TextureObject for T
+where
+    T: 'static + Texture + Clone + PartialEq,
+{
+    fn clone_box(&self) -> Box<dyn Texture> {
+        Box::new(self.clone())
+    }
+
+    fn eq_box(&self, other: &dyn Texture) -> bool {
+        match other.as_any().downcast_ref::<T>() {
+            Some(o) => self == o,
+            None => false,
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+pub struct Pattern {
+    texture: Box<dyn Texture>,
+    transformation: Matrix,
+    mapping: Option<UvMapping>,
+}
+
+impl Debug for Pattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Pattern {:?}", self.texture)
+    }
+}
+
+impl Clone for Pattern {
+    fn clone(&self) -> Self {
+        Pattern {
+            texture: self.texture.clone_box(),
+            transformation: self.transformation.clone(),
+            mapping: self.mapping,
+        }
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.transformation == other.transformation
+            && self.mapping == other.mapping
+            && self.texture.eq_box(other.texture.as_ref())
+    }
+}
+
+impl From<Color> for Pattern {
+    fn from(color: Color) -> Self {
+        Pattern::new_solid(color)
+    }
+}
+
+impl From<ColorRamp> for Pattern {
+    fn from(ramp: ColorRamp) -> Self {
+        Pattern::new_linear_gradient(ramp, crate::domain::matrix::IDENTITY.clone())
+    }
+}
 
 impl Pattern {
     // constructor
-    pub fn new_striped(a: Color, b: Color, transformation: Matrix) -> Pattern {
-        Pattern::STRIPED {
-            a,
-            b,
+    pub fn new<T: 'static + Texture>(texture: T, transformation: Matrix) -> Pattern {
+        Pattern {
+            texture: Box::new(texture),
             transformation,
+            mapping: Option::None,
         }
     }
 
-    pub fn new_gradient(a: Color, b: Color, transformation: Matrix) -> Pattern {
-        Pattern::GRADIENT {
-            a,
-            b,
+    // constructor for patterns evaluated in 2D texture space: object points are first mapped to
+    // (u, v, 0) before the pattern transformation is applied
+    pub fn new_uv<T: 'static + Texture>(
+        texture: T,
+        mapping: UvMapping,
+        transformation: Matrix,
+    ) -> Pattern {
+        Pattern {
+            texture: Box::new(texture),
             transformation,
+            mapping: Option::Some(mapping),
         }
     }
 
-    pub fn new_ringed(a: Color, b: Color, transformation: Matrix) -> Pattern {
-        Pattern::RINGED {
-            a,
-            b,
+    // checkers with given number of squares across u and v
+    pub fn new_uv_checkered<A, B>(
+        width: f64,
+        height: f64,
+        a: A,
+        b: B,
+        mapping: UvMapping,
+    ) -> Pattern
+    where
+        A: Into<Pattern>,
+        B: Into<Pattern>,
+    {
+        Pattern::new_uv(
+            UvCheckered::new(width, height, a.into(), b.into()),
+            mapping,
+            crate::domain::matrix::IDENTITY.clone(),
+        )
+    }
+
+    // samples a loaded image over the object's (u, v) coordinates
+    pub fn new_image(
+        canvas: Arc<Canvas>,
+        filtering: Filtering,
+        addressing: Addressing,
+        mapping: UvMapping,
+        transformation: Matrix,
+    ) -> Pattern {
+        Pattern::new_uv(
+            ImageTexture::new(canvas, filtering, addressing),
+            mapping,
             transformation,
-        }
+        )
     }
 
-    pub fn new_checkered(a: Color, b: Color, transformation: Matrix) -> Pattern {
-        Pattern::CHECKERED {
-            a,
-            b,
+    pub fn new_solid(color: Color) -> Pattern {
+        Pattern::new(Solid { color }, crate::domain::matrix::IDENTITY.clone())
+    }
+
+    pub fn new_striped<A, B>(a: A, b: B, transformation: Matrix) -> Pattern
+    where
+        A: Into<Pattern>,
+        B: Into<Pattern>,
+    {
+        Pattern::new(Striped::new(a.into(), b.into()), transformation)
+    }
+
+    pub fn new_gradient<A, B>(a: A, b: B, transformation: Matrix) -> Pattern
+    where
+        A: Into<Pattern>,
+        B: Into<Pattern>,
+    {
+        Pattern::new(Gradient::new(a.into(), b.into()), transformation)
+    }
+
+    pub fn new_ringed<A, B>(a: A, b: B, transformation: Matrix) -> Pattern
+    where
+        A: Into<Pattern>,
+        B: Into<Pattern>,
+    {
+        Pattern::new(Ringed::new(a.into(), b.into()), transformation)
+    }
+
+    pub fn new_checkered<A, B>(a: A, b: B, transformation: Matrix) -> Pattern
+    where
+        A: Into<Pattern>,
+        B: Into<Pattern>,
+    {
+        Pattern::new(Checkered::new(a.into(), b.into()), transformation)
+    }
+
+    pub fn new_blended<A, B>(a: A, b: B, transformation: Matrix) -> Pattern
+    where
+        A: Into<Pattern>,
+        B: Into<Pattern>,
+    {
+        Pattern::new(Blended::new(a.into(), b.into()), transformation)
+    }
+
+    pub fn new_perturbed<P>(pattern: P, noise: Noise, scale: f64, transformation: Matrix) -> Pattern
+    where
+        P: Into<Pattern>,
+    {
+        Pattern::new(Perturbed::new(pattern.into(), noise, scale), transformation)
+    }
+
+    // ramp gradient along the x axis (no wrapping - the ramp clamps beyond its stops)
+    pub fn new_linear_gradient(ramp: ColorRamp, transformation: Matrix) -> Pattern {
+        Pattern::new(
+            RampGradient::new(ramp, GradientKind::Linear),
             transformation,
-        }
+        )
+    }
+
+    // ramp gradient by distance from the y axis
+    pub fn new_radial_gradient(ramp: ColorRamp, transformation: Matrix) -> Pattern {
+        Pattern::new(
+            RampGradient::new(ramp, GradientKind::Radial),
+            transformation,
+        )
+    }
+
+    // ramp gradient by distance from the pattern origin
+    pub fn new_spherical_gradient(ramp: ColorRamp, transformation: Matrix) -> Pattern {
+        Pattern::new(
+            RampGradient::new(ramp, GradientKind::Spherical),
+            transformation,
+        )
+    }
+
+    pub fn new_wood(
+        ramp: ColorRamp,
+        noise: Noise,
+        rings: f64,
+        distortion: f64,
+        transformation: Matrix,
+    ) -> Pattern {
+        Pattern::new(Wood::new(ramp, noise, rings, distortion), transformation)
+    }
+
+    pub fn new_marble(
+        ramp: ColorRamp,
+        noise: Noise,
+        frequency: f64,
+        distortion: f64,
+        transformation: Matrix,
+    ) -> Pattern {
+        Pattern::new(
+            Marble::new(ramp, noise, frequency, distortion),
+            transformation,
+        )
+    }
+
+    pub fn new_granite(ramp: ColorRamp, noise: Noise, transformation: Matrix) -> Pattern {
+        Pattern::new(Granite::new(ramp, noise), transformation)
     }
 
     pub fn new_null() -> Pattern {
-        Pattern::NULL {
-            transformation: crate::domain::matrix::IDENTITY.clone(),
-        }
+        Pattern::new(Null, crate::domain::matrix::IDENTITY.clone())
     }
 
-    // TODO Turn this into a trait method to obviate pattern matching
     pub fn transformation(&self) -> &Matrix {
-        match self {
-            Pattern::STRIPED { transformation, .. } => transformation,
-            Pattern::GRADIENT { transformation, .. } => transformation,
-            Pattern::RINGED { transformation, .. } => transformation,
-            Pattern::CHECKERED { transformation, .. } => transformation,
-            Pattern::NULL { transformation, .. } => transformation,
-        }
+        &self.transformation
+    }
+
+    pub fn mapping(&self) -> Option<UvMapping> {
+        self.mapping
+    }
+
+    // underlying pattern implementation
+    pub fn texture(&self) -> &dyn Texture {
+        self.texture.as_ref()
     }
 
     // calculates color for the given pattern at the given point
     pub fn color_at(&self, object: &Object, world_point: &Point) -> Color {
-        // convert pattern to object and pattern orientations
-        let object_point = &object.shape().transformation.inverse().unwrap() * world_point;
-        let pattern_point = &self.transformation().inverse().unwrap() * &object_point;
-
-        match &self {
-            Pattern::STRIPED { a, b, .. } => color_at_striped(a, b, &pattern_point),
-            Pattern::GRADIENT { a, b, .. } => color_at_gradient(a, b, &pattern_point),
-            Pattern::RINGED { a, b, .. } => color_at_ringed(a, b, &pattern_point),
-            Pattern::CHECKERED { a, b, .. } => color_at_checkered(a, b, &pattern_point),
-            Pattern::NULL { .. } => color_at_null(&pattern_point),
+        self.color_at_time(object, world_point, 0.0)
+    }
+
+    // calculates color at the given point, with the object in its pose at the given time
+    pub fn color_at_time(&self, object: &Object, world_point: &Point, time: f64) -> Color {
+        // convert pattern to object orientation
+        let object_point = &object.inverse_transformation_at(time) * world_point;
+        match self.mapping {
+            Some(mapping) => {
+                let (u, v) = mapping.map(object, &object_point);
+                self.local_color_at(&Point::new(u, v, 0.0))
+            }
+            None => self.local_color_at(&object_point),
         }
     }
+
+    // calculates color at a point given in the enclosing space (object space, or the parent
+    // pattern's space for nested patterns)
+    pub fn local_color_at(&self, point: &Point) -> Color {
+        let pattern_point = &self.transformation.inverse().unwrap() * point;
+        self.texture.color_at(&pattern_point)
+    }
+}
+
+// single color everywhere
+#[derive(PartialEq, Debug, Clone)]
+pub struct Solid {
+    pub color: Color,
 }
 
-// striped colors alternating across x axis
-fn color_at_striped<'a, 'b>(a: &'a Color, b: &'a Color, point: &'b Point) -> Color {
-    if point.x().floor() % 2.0 == 0.0 {
-        a.clone()
-    } else {
-        b.clone()
+impl Texture for Solid {
+    fn color_at(&self, _pattern_point: &Point) -> Color {
+        self.color
     }
 }
 
-// gradient colors across x axis
-fn color_at_gradient<'a, 'b>(a: &'a Color, b: &'a Color, point: &'b Point) -> Color {
-    let distance = b - a;
-    let fraction = point.x() as f32 - point.x().floor() as f32;
-    a + &(&distance * fraction)
+// striped patterns alternating across x axis
+#[derive(PartialEq, Debug, Clone)]
+pub struct Striped {
+    pub a: Pattern,
+    pub b: Pattern,
 }
 
-// ringed colors across x axis
-fn color_at_ringed<'a, 'b>(a: &'a Color, b: &'a Color, point: &'b Point) -> Color {
-    let first = (point.x().powi(2) + point.z().powi(2)).sqrt().floor() % 2.0 == 0.0;
-    if first {
-        a.clone()
-    } else {
-        b.clone()
+impl Striped {
+    pub fn new(a: Pattern, b: Pattern) -> Striped {
+        Striped { a, b }
     }
 }
 
-// ringed colors across x axis
-fn color_at_checkered<'a, 'b>(a: &'a Color, b: &'a Color, point: &'b Point) -> Color {
-    let first = (point.x().floor() + point.y().floor() + point.z().floor()) % 2.0 == 0.0;
-    if first {
-        a.clone()
-    } else {
-        b.clone()
+impl Texture for Striped {
+    fn color_at(&self, pattern_point: &Point) -> Color {
+        if pattern_point.x().floor() % 2.0 == 0.0 {
+            self.a.local_color_at(pattern_point)
+        } else {
+            self.b.local_color_at(pattern_point)
+        }
     }
 }
 
-fn color_at_null(point: &Point) -> Color {
-    Color::new(point.x() as f32, point.y() as f32, point.z() as f32)
+// gradient across x axis
+#[derive(PartialEq, Debug, Clone)]
+pub struct Gradient {
+    pub a: Pattern,
+    pub b: Pattern,
+}
+
+impl Gradient {
+    pub fn new(a: Pattern, b: Pattern) -> Gradient {
+        Gradient { a, b }
+    }
+}
+
+impl Texture for Gradient {
+    fn color_at(&self, pattern_point: &Point) -> Color {
+        let a = self.a.local_color_at(pattern_point);
+        let b = self.b.local_color_at(pattern_point);
+        let distance = &b - &a;
+        let fraction = pattern_point.x() as f32 - pattern_point.x().floor() as f32;
+        &a + &(&distance * fraction)
+    }
+}
+
+// concentric rings around y axis
+#[derive(PartialEq, Debug, Clone)]
+pub struct Ringed {
+    pub a: Pattern,
+    pub b: Pattern,
+}
+
+impl Ringed {
+    pub fn new(a: Pattern, b: Pattern) -> Ringed {
+        Ringed { a, b }
+    }
+}
+
+impl Texture for Ringed {
+    fn color_at(&self, pattern_point: &Point) -> Color {
+        let p = pattern_point;
+        let first = (p.x().powi(2) + p.z().powi(2)).sqrt().floor() % 2.0 == 0.0;
+        if first {
+            self.a.local_color_at(pattern_point)
+        } else {
+            self.b.local_color_at(pattern_point)
+        }
+    }
+}
+
+// alternating cubes across all three axes
+#[derive(PartialEq, Debug, Clone)]
+pub struct Checkered {
+    pub a: Pattern,
+    pub b: Pattern,
+}
+
+impl Checkered {
+    pub fn new(a: Pattern, b: Pattern) -> Checkered {
+        Checkered { a, b }
+    }
+}
+
+impl Texture for Checkered {
+    fn color_at(&self, pattern_point: &Point) -> Color {
+        let p = pattern_point;
+        let first = (p.x().floor() + p.y().floor() + p.z().floor()) % 2.0 == 0.0;
+        if first {
+            self.a.local_color_at(pattern_point)
+        } else {
+            self.b.local_color_at(pattern_point)
+        }
+    }
+}
+
+// average of two patterns
+#[derive(PartialEq, Debug, Clone)]
+pub struct Blended {
+    pub a: Pattern,
+    pub b: Pattern,
+}
+
+impl Blended {
+    pub fn new(a: Pattern, b: Pattern) -> Blended {
+        Blended { a, b }
+    }
+}
+
+impl Texture for Blended {
+    fn color_at(&self, pattern_point: &Point) -> Color {
+        let a = self.a.local_color_at(pattern_point);
+        let b = self.b.local_color_at(pattern_point);
+        &(&a + &b) * 0.5
+    }
+}
+
+// jitters the pattern point with fractal noise before delegating to the wrapped pattern
+#[derive(PartialEq, Debug, Clone)]
+pub struct Perturbed {
+    pub pattern: Pattern,
+    pub noise: Noise,
+    pub scale: f64,
+}
+
+impl Perturbed {
+    pub fn new(pattern: Pattern, noise: Noise, scale: f64) -> Perturbed {
+        Perturbed {
+            pattern,
+            noise,
+            scale,
+        }
+    }
+}
+
+impl Texture for Perturbed {
+    fn color_at(&self, pattern_point: &Point) -> Color {
+        let jitter = &self.noise.fbm_vector(pattern_point) * self.scale;
+        self.pattern.local_color_at(&(pattern_point + &jitter))
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum GradientKind {
+    Linear,
+    Radial,
+    Spherical,
+}
+
+// multi-stop gradient looked up by a distance measure in pattern space
+#[derive(PartialEq, Debug, Clone)]
+pub struct RampGradient {
+    pub ramp: ColorRamp,
+    pub kind: GradientKind,
+}
+
+impl RampGradient {
+    pub fn new(ramp: ColorRamp, kind: GradientKind) -> RampGradient {
+        RampGradient { ramp, kind }
+    }
+}
+
+impl Texture for RampGradient {
+    fn color_at(&self, pattern_point: &Point) -> Color {
+        let p = pattern_point;
+        let t = match self.kind {
+            GradientKind::Linear => p.x(),
+            GradientKind::Radial => (p.x().powi(2) + p.z().powi(2)).sqrt(),
+            GradientKind::Spherical => (p.x().powi(2) + p.y().powi(2) + p.z().powi(2)).sqrt(),
+        };
+        self.ramp.color_at(t)
+    }
+}
+
+// growth rings around the y axis with noise-perturbed radius
+#[derive(PartialEq, Debug, Clone)]
+pub struct Wood {
+    pub ramp: ColorRamp,
+    pub noise: Noise,
+    pub rings: f64,
+    pub distortion: f64,
+}
+
+impl Wood {
+    pub fn new(ramp: ColorRamp, noise: Noise, rings: f64, distortion: f64) -> Wood {
+        Wood {
+            ramp,
+            noise,
+            rings,
+            distortion,
+        }
+    }
+}
+
+impl Texture for Wood {
+    fn color_at(&self, pattern_point: &Point) -> Color {
+        let p = pattern_point;
+        let radius = (p.x().powi(2) + p.z().powi(2)).sqrt();
+        let radius = radius + self.noise.fbm(p) * self.distortion;
+        let t = (radius * self.rings).rem_euclid(1.0);
+        self.ramp.color_at(t)
+    }
+}
+
+// sine bands along x, warped by turbulence into veins
+#[derive(PartialEq, Debug, Clone)]
+pub struct Marble {
+    pub ramp: ColorRamp,
+    pub noise: Noise,
+    pub frequency: f64,
+    pub distortion: f64,
+}
+
+impl Marble {
+    pub fn new(ramp: ColorRamp, noise: Noise, frequency: f64, distortion: f64) -> Marble {
+        Marble {
+            ramp,
+            noise,
+            frequency,
+            distortion,
+        }
+    }
+}
+
+impl Texture for Marble {
+    fn color_at(&self, pattern_point: &Point) -> Color {
+        let p = pattern_point;
+        let phase = p.x() * self.frequency + self.noise.turbulence(p) * self.distortion;
+        let t = 0.5 + 0.5 * phase.sin();
+        self.ramp.color_at(t)
+    }
+}
+
+// crystalline grains from cellular noise, speckled with turbulence
+#[derive(PartialEq, Debug, Clone)]
+pub struct Granite {
+    pub ramp: ColorRamp,
+    pub noise: Noise,
+}
+
+impl Granite {
+    pub fn new(ramp: ColorRamp, noise: Noise) -> Granite {
+        Granite { ramp, noise }
+    }
+}
+
+impl Texture for Granite {
+    fn color_at(&self, pattern_point: &Point) -> Color {
+        let (f1, f2) = self.noise.cellular(pattern_point);
+        let speckle = self.noise.turbulence(&Point::new(
+            pattern_point.x() * 4.0,
+            pattern_point.y() * 4.0,
+            pattern_point.z() * 4.0,
+        ));
+        let t = ((f2 - f1) + 0.3 * speckle).min(1.0);
+        self.ramp.color_at(t)
+    }
+}
+
+// checkers in (u, v) space - expects pattern points of the form (u, v, 0)
+#[derive(PartialEq, Debug, Clone)]
+pub struct UvCheckered {
+    pub width: f64,
+    pub height: f64,
+    pub a: Pattern,
+    pub b: Pattern,
+}
+
+impl UvCheckered {
+    pub fn new(width: f64, height: f64, a: Pattern, b: Pattern) -> UvCheckered {
+        UvCheckered {
+            width,
+            height,
+            a,
+            b,
+        }
+    }
+}
+
+impl Texture for UvCheckered {
+    fn color_at(&self, pattern_point: &Point) -> Color {
+        let u2 = (pattern_point.x() * self.width).floor();
+        let v2 = (pattern_point.y() * self.height).floor();
+        if (u2 + v2).rem_euclid(2.0) == 0.0 {
+            self.a.local_color_at(pattern_point)
+        } else {
+            self.b.local_color_at(pattern_point)
+        }
+    }
+}
+
+// How texels are combined when sampling an image.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Filtering {
+    Nearest,
+    Bilinear,
+}
+
+// How texel coordinates outside the image are brought back into it.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Addressing {
+    Wrap,
+    Clamp,
+    Mirror,
+}
+
+impl Addressing {
+    // resolves an integer texel coordinate into the [0, size) range
+    pub fn resolve(&self, index: i64, size: usize) -> usize {
+        let size = size as i64;
+        let resolved = match self {
+            Addressing::Wrap => index.rem_euclid(size),
+            Addressing::Clamp => index.clamp(0, size - 1),
+            Addressing::Mirror => {
+                let m = index.rem_euclid(2 * size);
+                if m >= size {
+                    2 * size - 1 - m
+                } else {
+                    m
+                }
+            }
+        };
+        resolved as usize
+    }
+}
+
+// image sampled in (u, v) space - expects pattern points of the form (u, v, 0), with v = 0 at the
+// bottom row of the image
+#[derive(Debug, Clone)]
+pub struct ImageTexture {
+    pub canvas: Arc<Canvas>,
+    pub filtering: Filtering,
+    pub addressing: Addressing,
+}
+
+impl ImageTexture {
+    pub fn new(canvas: Arc<Canvas>, filtering: Filtering, addressing: Addressing) -> ImageTexture {
+        ImageTexture {
+            canvas,
+            filtering,
+            addressing,
+        }
+    }
+
+    fn texel(&self, x: i64, y: i64) -> Color {
+        let column = self.addressing.resolve(x, self.canvas.width);
+        let row = self.addressing.resolve(y, self.canvas.height);
+        self.canvas.pixel_at(column, row)
+    }
+}
+
+// images are shared, so equality is by identity rather than by comparing every pixel
+impl PartialEq for ImageTexture {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.canvas, &other.canvas)
+            && self.filtering == other.filtering
+            && self.addressing == other.addressing
+    }
+}
+
+impl Texture for ImageTexture {
+    fn color_at(&self, pattern_point: &Point) -> Color {
+        let x = pattern_point.x() * self.canvas.width as f64;
+        let y = (1.0 - pattern_point.y()) * self.canvas.height as f64;
+
+        match self.filtering {
+            Filtering::Nearest => self.texel(x.floor() as i64, y.floor() as i64),
+            Filtering::Bilinear => {
+                // texel centers sit at half-integer coordinates
+                let (x, y) = (x - 0.5, y - 0.5);
+                let (x0, y0) = (x.floor(), y.floor());
+                let (fx, fy) = ((x - x0) as f32, (y - y0) as f32);
+                let (x0, y0) = (x0 as i64, y0 as i64);
+
+                let top = &(&self.texel(x0, y0) * (1.0 - fx)) + &(&self.texel(x0 + 1, y0) * fx);
+                let bottom =
+                    &(&self.texel(x0, y0 + 1) * (1.0 - fx)) + &(&self.texel(x0 + 1, y0 + 1) * fx);
+                &(&top * (1.0 - fy)) + &(&bottom * fy)
+            }
+        }
+    }
+}
+
+// test pattern returning the pattern-space point as a color
+#[derive(PartialEq, Debug, Clone)]
+pub struct Null;
+
+impl Texture for Null {
+    fn color_at(&self, pattern_point: &Point) -> Color {
+        Color::new(
+            pattern_point.x() as f32,
+            pattern_point.y() as f32,
+            pattern_point.z() as f32,
+        )
+    }
 }
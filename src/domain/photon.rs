@@ -0,0 +1,312 @@
+use crate::domain::color::Color;
+use crate::domain::integrator::spread;
+use crate::domain::intersection::Computations;
+use crate::domain::kdtree::KdTree;
+use crate::domain::matrix::Matrix;
+use crate::domain::object::Object;
+use crate::domain::random::{sample_cone, split_mix, Rng};
+use crate::domain::ray::Ray;
+use crate::domain::world::World;
+use crate::domain::{Point, Vector};
+use rayon::prelude::*;
+use std::f64::consts::PI;
+
+// Light that reached a diffuse surface after at least one specular bounce.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Photon {
+    // direction the photon was travelling in when it landed
+    pub direction: Vector,
+    pub power: Color,
+}
+
+// Settings of the photon pass.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct PhotonMapping {
+    // photons emitted towards the reflective and transparent objects, shared between them
+    pub photons: usize,
+    // photons gathered for every radiance estimate
+    pub neighbours: usize,
+    // photons further away than this are never gathered
+    pub radius: f64,
+    pub max_bounces: usize,
+}
+
+impl PhotonMapping {
+    pub const DEFAULT_PHOTONS: usize = 100_000;
+    pub const DEFAULT_NEIGHBOURS: usize = 50;
+    pub const DEFAULT_RADIUS: f64 = 0.5;
+    pub const DEFAULT_MAX_BOUNCES: usize = 8;
+}
+
+impl Default for PhotonMapping {
+    fn default() -> Self {
+        PhotonMapping {
+            photons: PhotonMapping::DEFAULT_PHOTONS,
+            neighbours: PhotonMapping::DEFAULT_NEIGHBOURS,
+            radius: PhotonMapping::DEFAULT_RADIUS,
+            max_bounces: PhotonMapping::DEFAULT_MAX_BOUNCES,
+        }
+    }
+}
+
+// Caustic photons stored around the scene, queried while shading.
+#[derive(Debug, Clone)]
+pub struct PhotonMap {
+    tree: KdTree<Photon>,
+    neighbours: usize,
+    radius: f64,
+}
+
+impl PhotonMap {
+    // constructor
+    pub fn new(photons: Vec<(Point, Photon)>, settings: &PhotonMapping) -> PhotonMap {
+        PhotonMap {
+            tree: KdTree::new(photons),
+            neighbours: settings.neighbours,
+            radius: settings.radius,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    // density estimate of the light arriving at the front side of the surface: the power of the
+    // nearest photons spread over the disc that holds them
+    pub fn irradiance(&self, point: &Point, normal: &Vector) -> Color {
+        let nearest = self.tree.nearest(point, self.neighbours, self.radius);
+        let radius2 = match nearest.last() {
+            // a full set of photons covers only as much area as it needs
+            Some((_, distance2)) if nearest.len() == self.neighbours => *distance2,
+            _ => self.radius * self.radius,
+        };
+        if radius2 <= 0.0 {
+            return Color::BLACK;
+        }
+
+        let power = nearest
+            .iter()
+            .filter(|(photon, _)| photon.direction.dot_product(normal) < 0.0)
+            .fold(Color::BLACK, |total, (photon, _)| &total + &photon.power);
+        &power * (1.0 / (PI * radius2)) as f32
+    }
+}
+
+// a cone of directions from the light enclosing one of the objects photons are aimed at
+struct Target {
+    axis: Vector,
+    cos_half_angle: f64,
+}
+
+impl World {
+    // First pass of photon mapping: shoots photons from the light through the reflective and
+    // transparent objects, and stores where they land on diffuse surfaces. Photons are only
+    // aimed at those objects (whatever else they hit can't focus light), and infinite shapes
+    // are skipped.
+    pub fn emit_photons(&mut self, settings: &PhotonMapping) {
+        let photons = self.trace_photons(settings);
+        self.photon_map = Option::Some(PhotonMap::new(photons, settings));
+    }
+
+    fn trace_photons(&self, settings: &PhotonMapping) -> Vec<(Point, Photon)> {
+        let light = match self.light_source.as_ref() {
+            Some(light) => light,
+            None => return Vec::new(),
+        };
+        let targets: Vec<Target> = self
+            .objects
+            .iter()
+            .filter(|o| {
+                let material = &o.shape().material;
+                material.reflective > 0.0 || material.transparency > 0.0
+            })
+            .filter_map(|o| target(o, &light.position))
+            .collect();
+        if targets.is_empty() {
+            return Vec::new();
+        }
+        let per_target = settings.photons / targets.len();
+
+        (0..targets.len() * per_target)
+            .into_par_iter()
+            .flat_map(|i| {
+                // seeded by the photon's number so the map is repeatable
+                let mut seed = i as u64;
+                let mut rng = Rng::new(split_mix(&mut seed));
+                let index = i / per_target;
+                let cone = &targets[index];
+                let half_angle = cone.cos_half_angle.acos();
+                let direction = sample_cone(&mut rng, &cone.axis, half_angle);
+
+                // directions shared by several cones belong to the first of them
+                let claimed = targets[..index]
+                    .iter()
+                    .any(|t| direction.dot_product(&t.axis) >= t.cos_half_angle);
+                if claimed {
+                    return Vec::new();
+                }
+
+                // every photon carries its share of the light sent into the cone
+                let solid_angle = 2.0 * PI * (1.0 - cone.cos_half_angle);
+                let power = &light.intensity * (solid_angle / per_target as f64) as f32;
+                self.trace_photon(
+                    Ray::new(light.position, direction),
+                    power,
+                    settings,
+                    &mut rng,
+                )
+            })
+            .collect()
+    }
+
+    // follows a photon through specular bounces, storing it on every diffuse surface it meets
+    // after the first of them
+    fn trace_photon(
+        &self,
+        mut ray: Ray,
+        mut power: Color,
+        settings: &PhotonMapping,
+        rng: &mut Rng,
+    ) -> Vec<(Point, Photon)> {
+        let mut stored = Vec::new();
+        let mut travelled = 0.0;
+        for bounce in 0..settings.max_bounces {
+            let mut ints = self.intersect(&ray);
+            let original_ints = ints.clone();
+            let hit = match ints.hit() {
+                Some(hit) => hit,
+                None => break,
+            };
+            let comps =
+                Computations::prepare_computations(&hit, &ray, Option::Some(&original_ints));
+            let material = &comps.object.shape().material;
+            travelled += hit.distance * ray.direction.magnitude();
+
+            if comps.inside {
+                power = &power * &material.transmittance(comps.distance);
+            }
+
+            // lights don't fall off with distance in the Phong model, so the photon makes up for
+            // the spreading of its beam along the way
+            let albedo = material.diffuse_color_at(comps.object, &comps.over_point, comps.time);
+            if bounce > 0 && albedo != Color::BLACK {
+                stored.push((
+                    comps.over_point,
+                    Photon {
+                        direction: ray.direction.normalize(),
+                        power: &power * (travelled * travelled) as f32,
+                    },
+                ));
+            }
+
+            let (reflective, transparency) =
+                if material.reflective > 0.0 && material.transparency > 0.0 {
+                    let reflectance = comps.schlick();
+                    (
+                        material.reflective * reflectance,
+                        material.transparency * (1.0 - reflectance),
+                    )
+                } else {
+                    (material.reflective, material.transparency)
+                };
+            let total = reflective + transparency;
+            if total <= 0.0 {
+                break;
+            }
+
+            // russian roulette between the specular lobes, anything left over is absorbed
+            let scale = total.max(1.0);
+            let choice = rng.next_f64() * scale;
+            power = &power * scale as f32;
+            ray = if choice < reflective {
+                Ray::new(comps.over_point, spread(rng, &comps, &comps.reflect_v, 1.0))
+            } else if choice < total {
+                match comps.refracted_direction(comps.n1, comps.n2) {
+                    Some(direction) => {
+                        Ray::new(comps.under_point, spread(rng, &comps, &direction, -1.0))
+                    }
+                    // total internal reflection
+                    None => Ray::new(comps.over_point, spread(rng, &comps, &comps.reflect_v, 1.0)),
+                }
+            } else {
+                break;
+            };
+        }
+        stored
+    }
+
+    // light focused onto the surface by reflective and transparent objects
+    pub fn caustic_color(&self, comps: &Computations) -> Color {
+        let map = match self.photon_map.as_ref() {
+            Some(map) if !map.is_empty() => map,
+            _ => return Color::BLACK,
+        };
+        let albedo = comps.object.shape().material.diffuse_color_at(
+            comps.object,
+            &comps.over_point,
+            comps.time,
+        );
+        if albedo == Color::BLACK {
+            return Color::BLACK;
+        }
+        &albedo * &map.irradiance(&comps.over_point, &comps.normal_v)
+    }
+}
+
+// cone from the light around the world-space bounding sphere of the object, covering both ends
+// of its motion when it is blurred
+fn target(object: &Object, light: &Point) -> Option<Target> {
+    let bounds = object.bounds();
+    let shape = object.shape();
+    let poses: Vec<&Matrix> = std::iter::once(&shape.transformation)
+        .chain(shape.end_transformation.as_ref())
+        .collect();
+    let corners: Vec<Point> = poses
+        .iter()
+        .flat_map(|transformation| {
+            (0..8).map(move |i| {
+                let pick = |bit: usize, min: f64, max: f64| if i & bit == 0 { min } else { max };
+                let corner = Point::new(
+                    pick(1, bounds.minimum.x(), bounds.maximum.x()),
+                    pick(2, bounds.minimum.y(), bounds.maximum.y()),
+                    pick(4, bounds.minimum.z(), bounds.maximum.z()),
+                );
+                *transformation * &corner
+            })
+        })
+        .collect();
+    if corners
+        .iter()
+        .any(|c| !(c.x().is_finite() && c.y().is_finite() && c.z().is_finite()))
+    {
+        return Option::None;
+    }
+
+    let sum = corners.iter().fold(Vector::new(0.0, 0.0, 0.0), |sum, c| {
+        &sum + &Vector::new(c.x(), c.y(), c.z())
+    });
+    let count = corners.len() as f64;
+    let center = Point::new(sum.x() / count, sum.y() / count, sum.z() / count);
+    let radius = corners
+        .iter()
+        .map(|c| (c - &center).magnitude())
+        .fold(0.0, f64::max);
+
+    let to_center = &center - light;
+    let distance = to_center.magnitude();
+    if distance <= radius {
+        // light inside the bounds: shoot everywhere
+        return Option::Some(Target {
+            axis: Vector::new(0.0, 1.0, 0.0),
+            cos_half_angle: -1.0,
+        });
+    }
+    Option::Some(Target {
+        axis: &to_center * (1.0 / distance),
+        cos_half_angle: (1.0 - (radius / distance).powi(2)).sqrt(),
+    })
+}
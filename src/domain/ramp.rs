@@ -0,0 +1,112 @@
+use crate::domain::color::Color;
+
+// How colors blend between two neighbouring stops.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Interpolation {
+    Linear,
+    Smoothstep,
+    Constant,
+}
+
+impl Interpolation {
+    // remaps the linear fraction between two stops
+    pub fn apply(&self, fraction: f64) -> f64 {
+        match self {
+            Interpolation::Linear => fraction,
+            Interpolation::Smoothstep => fraction * fraction * (3.0 - 2.0 * fraction),
+            Interpolation::Constant => 0.0,
+        }
+    }
+}
+
+// Maps a scalar in the [0, 1] range onto colors interpolated between ordered stops.
+#[derive(PartialEq, Debug, Clone)]
+pub struct ColorRamp {
+    stops: Vec<(f64, Color)>,
+    pub interpolation: Interpolation,
+}
+
+impl ColorRamp {
+    // constructor (linear interpolation) - stops are sorted by position; positions outside [0, 1]
+    // are allowed, NaN ones are dropped
+    pub fn new(stops: Vec<(f64, Color)>) -> ColorRamp {
+        ColorRamp::new_interpolated(stops, Interpolation::Linear)
+    }
+
+    pub fn new_interpolated(stops: Vec<(f64, Color)>, interpolation: Interpolation) -> ColorRamp {
+        let mut stops: Vec<(f64, Color)> = stops.into_iter().filter(|s| !s.0.is_nan()).collect();
+        if stops.is_empty() {
+            panic!("Color ramp requires at least one stop.");
+        }
+
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        ColorRamp {
+            stops,
+            interpolation,
+        }
+    }
+
+    // evenly spaced stops across [0, 1]
+    pub fn new_uniform(colors: Vec<Color>) -> ColorRamp {
+        let last = (colors.len().max(2) - 1) as f64;
+        let stops = colors
+            .into_iter()
+            .enumerate()
+            .map(|(i, c)| (i as f64 / last, c))
+            .collect();
+        ColorRamp::new(stops)
+    }
+
+    // light/dark bands of wood grain
+    pub fn new_wood() -> ColorRamp {
+        ColorRamp::new(vec![
+            (0.0, Color::new(0.78, 0.56, 0.33)),
+            (0.6, Color::new(0.65, 0.43, 0.22)),
+            (0.85, Color::new(0.42, 0.25, 0.11)),
+            (1.0, Color::new(0.78, 0.56, 0.33)),
+        ])
+    }
+
+    // white stone with grey-blue veins
+    pub fn new_marble() -> ColorRamp {
+        ColorRamp::new(vec![
+            (0.0, Color::new(0.35, 0.37, 0.42)),
+            (0.15, Color::new(0.72, 0.73, 0.76)),
+            (0.5, Color::new(0.95, 0.95, 0.93)),
+            (1.0, Color::new(0.98, 0.97, 0.95)),
+        ])
+    }
+
+    // speckled black/grey/pink stone
+    pub fn new_granite() -> ColorRamp {
+        ColorRamp::new(vec![
+            (0.0, Color::new(0.08, 0.08, 0.08)),
+            (0.2, Color::new(0.35, 0.33, 0.32)),
+            (0.55, Color::new(0.72, 0.62, 0.6)),
+            (1.0, Color::new(0.9, 0.88, 0.86)),
+        ])
+    }
+
+    pub fn stops(&self) -> &[(f64, Color)] {
+        &self.stops
+    }
+
+    // calculates color at given position, clamping to the outermost stops (NaN counts as below
+    // the first one)
+    pub fn color_at(&self, t: f64) -> Color {
+        let first = self.stops.first().unwrap();
+        let last = self.stops.last().unwrap();
+        if t.is_nan() || t <= first.0 {
+            return first.1;
+        }
+        if t >= last.0 {
+            return last.1;
+        }
+
+        let upper = self.stops.iter().position(|s| s.0 > t).unwrap();
+        let (t0, c0) = self.stops[upper - 1];
+        let (t1, c1) = self.stops[upper];
+        let fraction = self.interpolation.apply((t - t0) / (t1 - t0)) as f32;
+        &c0 + &(&(&c1 - &c0) * fraction)
+    }
+}
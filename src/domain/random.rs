@@ -0,0 +1,82 @@
+use crate::domain::{Point, Vector};
+use std::f64::consts::PI;
+
+// Small deterministic random number generator (splitmix64). Renders stay reproducible because
+// every generator is seeded from scene data rather than from a shared global state.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    // constructor
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    // seeds from the exact bits of a point, mixed with a caller-chosen salt
+    pub fn from_point(point: &Point, salt: u64) -> Rng {
+        let mut state = salt;
+        for coord in [point.x(), point.y(), point.z()].iter() {
+            state = split_mix(&mut state) ^ coord.to_bits();
+        }
+        Rng::new(split_mix(&mut state))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        split_mix(&mut self.state)
+    }
+
+    // uniform in [0, 1)
+    pub fn next_f64(&mut self) -> f64 {
+        unit_float(self.next_u64())
+    }
+}
+
+// two unit vectors completing an orthonormal basis with the given (unit) axis
+pub fn orthonormal_basis(axis: &Vector) -> (Vector, Vector) {
+    let helper = if axis.x().abs() < 0.9 {
+        Vector::new(1.0, 0.0, 0.0)
+    } else {
+        Vector::new(0.0, 1.0, 0.0)
+    };
+    let tangent = axis.cross_product(&helper).normalize();
+    let bitangent = axis.cross_product(&tangent);
+    (tangent, bitangent)
+}
+
+// uniformly distributed direction within the cone of given half angle around a (unit) axis
+pub fn sample_cone(rng: &mut Rng, axis: &Vector, half_angle: f64) -> Vector {
+    let cos_theta = 1.0 - rng.next_f64() * (1.0 - half_angle.cos());
+    let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+    let phi = 2.0 * PI * rng.next_f64();
+
+    let (tangent, bitangent) = orthonormal_basis(axis);
+    let direction = &(&tangent * (sin_theta * phi.cos())) + &(&bitangent * (sin_theta * phi.sin()));
+    (&direction + &(axis * cos_theta)).normalize()
+}
+
+// cosine-weighted direction on the hemisphere around a (unit) normal; the pdf is cos(theta) / pi
+pub fn sample_cosine_hemisphere(rng: &mut Rng, normal: &Vector) -> Vector {
+    let r = rng.next_f64().sqrt();
+    let phi = 2.0 * PI * rng.next_f64();
+    let z = (1.0 - r * r).max(0.0).sqrt();
+
+    let (tangent, bitangent) = orthonormal_basis(normal);
+    let direction = &(&tangent * (r * phi.cos())) + &(&bitangent * (r * phi.sin()));
+    (&direction + &(normal * z)).normalize()
+}
+
+// splitmix64 step (see: https://prng.di.unimi.it/splitmix64.c)
+pub fn split_mix(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+// maps random bits onto [0, 1)
+pub fn unit_float(bits: u64) -> f64 {
+    (bits >> 11) as f64 / (1_u64 << 53) as f64
+}
@@ -5,12 +5,24 @@ use crate::domain::{Point, Vector};
 pub struct Ray {
     pub origin: Point,
     pub direction: Vector,
+    // moment within the exposure the ray was cast at, moving objects are intersected in the pose
+    // they have at that time
+    pub time: f64,
 }
 
 impl Ray {
     // constructor
     pub fn new(origin: Point, direction: Vector) -> Ray {
-        Ray { origin, direction }
+        Ray {
+            origin,
+            direction,
+            time: 0.0,
+        }
+    }
+
+    // same ray cast at another time
+    pub fn with_time(self, time: f64) -> Ray {
+        Ray { time, ..self }
     }
 
     // calculates points at given position
@@ -23,6 +35,6 @@ impl Ray {
         let new_origin = matrix * &self.origin;
         let new_direction = matrix * &self.direction;
 
-        Ray::new(new_origin, new_direction)
+        Ray::new(new_origin, new_direction).with_time(self.time)
     }
 }
@@ -0,0 +1,186 @@
+use crate::domain::integrator::Integrator;
+use crate::domain::EPSILON;
+
+// Options controlling how `World::render` turns the scene into an image.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct RenderSettings {
+    // bounces a camera ray may take in total, reflections and refractions alike
+    pub max_depth: usize,
+    // bounces that may be reflections, and that may be refractions
+    pub max_reflection_depth: usize,
+    pub max_refraction_depth: usize,
+    // camera rays (or paths) averaged per pixel, each through a random position within the pixel
+    // and at a random time while the shutter is open; a single Whitted or ambient occlusion ray
+    // goes through the pixel's center when the shutter opens. Defaults to one, or to
+    // `Integrator::DEFAULT_PATH_SAMPLES` when path tracing.
+    pub samples: usize,
+    // distance rays spawned at a hit start off the surface, keeping them from hitting it again
+    // (shadow acne); only used by the Whitted integrator
+    pub bias: f64,
+    pub integrator: Integrator,
+    // worker threads, all cores when not set
+    pub threads: Option<usize>,
+}
+
+pub struct RenderSettingsBuilder {
+    max_depth: Option<usize>,
+    max_reflection_depth: Option<usize>,
+    max_refraction_depth: Option<usize>,
+    samples: Option<usize>,
+    bias: Option<f64>,
+    integrator: Option<Integrator>,
+    threads: Option<usize>,
+}
+
+impl RenderSettings {
+    pub const DEFAULT_MAX_DEPTH: usize = 5;
+    pub const DEFAULT_SAMPLES: usize = 1;
+    pub const DEFAULT_BIAS: f64 = EPSILON;
+
+    // builder
+    pub fn new() -> RenderSettingsBuilder {
+        RenderSettingsBuilder {
+            max_depth: Option::None,
+            max_reflection_depth: Option::None,
+            max_refraction_depth: Option::None,
+            samples: Option::None,
+            bias: Option::None,
+            integrator: Option::None,
+            threads: Option::None,
+        }
+    }
+
+    // recursion allowed to camera rays
+    pub fn recursion(&self) -> Recursion {
+        Recursion {
+            depth: self.max_depth,
+            reflections: self.max_reflection_depth,
+            refractions: self.max_refraction_depth,
+            bounce: 0,
+            bias: self.bias,
+        }
+    }
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        RenderSettings::new().build()
+    }
+}
+
+impl RenderSettingsBuilder {
+    // finalizes built instance
+    pub fn build(&self) -> RenderSettings {
+        let integrator = self.integrator.unwrap_or_default();
+        RenderSettings {
+            max_depth: self.max_depth.unwrap_or(RenderSettings::DEFAULT_MAX_DEPTH),
+            max_reflection_depth: self
+                .max_reflection_depth
+                .unwrap_or(RenderSettings::DEFAULT_MAX_DEPTH),
+            max_refraction_depth: self
+                .max_refraction_depth
+                .unwrap_or(RenderSettings::DEFAULT_MAX_DEPTH),
+            samples: self.samples.unwrap_or(integrator.default_samples()),
+            bias: self.bias.unwrap_or(RenderSettings::DEFAULT_BIAS),
+            integrator,
+            threads: self.threads,
+        }
+    }
+
+    pub fn max_depth(&mut self, max_depth: usize) -> &mut RenderSettingsBuilder {
+        self.max_depth = Option::Some(max_depth);
+        self
+    }
+
+    pub fn max_reflection_depth(
+        &mut self,
+        max_reflection_depth: usize,
+    ) -> &mut RenderSettingsBuilder {
+        self.max_reflection_depth = Option::Some(max_reflection_depth);
+        self
+    }
+
+    pub fn max_refraction_depth(
+        &mut self,
+        max_refraction_depth: usize,
+    ) -> &mut RenderSettingsBuilder {
+        self.max_refraction_depth = Option::Some(max_refraction_depth);
+        self
+    }
+
+    pub fn samples(&mut self, samples: usize) -> &mut RenderSettingsBuilder {
+        self.samples = Option::Some(samples);
+        self
+    }
+
+    pub fn bias(&mut self, bias: f64) -> &mut RenderSettingsBuilder {
+        self.bias = Option::Some(bias);
+        self
+    }
+
+    pub fn integrator(&mut self, integrator: Integrator) -> &mut RenderSettingsBuilder {
+        self.integrator = Option::Some(integrator);
+        self
+    }
+
+    pub fn threads(&mut self, threads: usize) -> &mut RenderSettingsBuilder {
+        self.threads = Option::Some(threads);
+        self
+    }
+}
+
+// How much further a traced ray may recurse. A plain number allows that many bounces of either
+// kind, with the default bias.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Recursion {
+    // bounces left in total
+    pub depth: usize,
+    // bounces left that may be reflections, and that may be refractions
+    pub reflections: usize,
+    pub refractions: usize,
+    // bounces taken so far
+    pub bounce: usize,
+    pub bias: f64,
+}
+
+impl Recursion {
+    pub fn can_reflect(&self) -> bool {
+        self.depth > 0 && self.reflections > 0
+    }
+
+    pub fn can_refract(&self) -> bool {
+        self.depth > 0 && self.refractions > 0
+    }
+
+    // recursion left to a reflected ray
+    pub fn reflected(&self) -> Recursion {
+        Recursion {
+            depth: self.depth.saturating_sub(1),
+            reflections: self.reflections.saturating_sub(1),
+            bounce: self.bounce + 1,
+            ..*self
+        }
+    }
+
+    // recursion left to a refracted ray
+    pub fn refracted(&self) -> Recursion {
+        Recursion {
+            depth: self.depth.saturating_sub(1),
+            refractions: self.refractions.saturating_sub(1),
+            bounce: self.bounce + 1,
+            ..*self
+        }
+    }
+}
+
+impl From<usize> for Recursion {
+    fn from(depth: usize) -> Self {
+        Recursion {
+            depth,
+            reflections: depth,
+            refractions: depth,
+            bounce: 0,
+            bias: RenderSettings::DEFAULT_BIAS,
+        }
+    }
+}
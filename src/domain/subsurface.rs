@@ -0,0 +1,155 @@
+use crate::domain::color::Color;
+use crate::domain::intersection::Computations;
+use crate::domain::object::Object;
+use crate::domain::random::{sample_cone, sample_cosine_hemisphere, Rng};
+use crate::domain::ray::Ray;
+use crate::domain::world::World;
+use crate::domain::Point;
+use std::f64::consts::PI;
+
+// keeps the walks uncorrelated with the other rays seeded from the same point
+const SUBSURFACE_SALT: u64 = 0xB0;
+
+// walks still inside after this many scattering events are given up on
+const MAX_STEPS: usize = 64;
+
+// below this the rest of a walk can't add anything visible
+const MIN_THROUGHPUT: f32 = 0.001;
+
+// Light scattered beneath the surface of a closed object before leaving it again somewhere else,
+// as in wax, marble, milk or skin. It replaces the diffuse term of the material: light is
+// gathered where random walks through the object leave it, so it bleeds into shadows and shines
+// through thin parts. The path tracer gathers it in place of the direct light on diffuse bounces.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Subsurface {
+    // share of light surviving every scattering event inside, per channel; channels closer to
+    // white travel further (a reddish color gives skin its red glow)
+    pub color: Color,
+    // mean distance travelled between scattering events
+    pub radius: f64,
+    // walks averaged for every shaded point
+    pub samples: usize,
+}
+
+pub struct SubsurfaceBuilder {
+    radius: f64,
+    color: Option<Color>,
+    samples: Option<usize>,
+}
+
+impl Subsurface {
+    pub const DEFAULT_COLOR: Color = Color {
+        red: 0.95,
+        green: 0.95,
+        blue: 0.95,
+    };
+    pub const DEFAULT_SAMPLES: usize = 16;
+
+    // builder
+    pub fn new(radius: f64) -> SubsurfaceBuilder {
+        SubsurfaceBuilder {
+            radius,
+            color: Option::None,
+            samples: Option::None,
+        }
+    }
+}
+
+impl SubsurfaceBuilder {
+    // finalizes built instance
+    pub fn build(&self) -> Subsurface {
+        Subsurface {
+            color: self.color.unwrap_or(Subsurface::DEFAULT_COLOR),
+            radius: self.radius,
+            samples: self.samples.unwrap_or(Subsurface::DEFAULT_SAMPLES),
+        }
+    }
+
+    pub fn color(&mut self, color: Color) -> &mut SubsurfaceBuilder {
+        self.color = Option::Some(color);
+        self
+    }
+
+    pub fn samples(&mut self, samples: usize) -> &mut SubsurfaceBuilder {
+        self.samples = Option::Some(samples);
+        self
+    }
+}
+
+impl World {
+    // Diffuse light leaving the surface after scattering inside the object. Every walk enters
+    // below the point, takes exponentially distributed steps in random directions until it
+    // crosses the surface again, and collects the light falling onto the surface there. Points
+    // where walks turn or leave are kept the given bias off the surface.
+    pub fn subsurface_color(&self, comps: &Computations, bias: f64) -> Color {
+        let material = &comps.object.shape().material;
+        let (subsurface, light) = match (material.subsurface, self.light_source.as_ref()) {
+            (Some(subsurface), Some(light)) if subsurface.samples > 0 => (subsurface, light),
+            _ => return Color::BLACK,
+        };
+        let albedo = material.diffuse_color_at(comps.object, &comps.over_point, comps.time);
+        if albedo == Color::BLACK {
+            return Color::BLACK;
+        }
+
+        let mut rng = Rng::from_point(&comps.over_point, SUBSURFACE_SALT);
+        let inward = -comps.normal_v;
+        let mut gathered = Color::BLACK;
+        for _ in 0..subsurface.samples {
+            let direction = sample_cosine_hemisphere(&mut rng, &inward);
+            let walk = Ray::new(comps.under_point, direction).with_time(comps.time);
+            let exit = random_walk(comps.object, &subsurface, walk, bias, &mut rng);
+            if let Some((point, throughput)) = exit {
+                let normal = comps.object.normal_at_time(&point, comps.time);
+                let to_light = (&light.position - &point).normalize();
+                let cos_theta = to_light.dot_product(&normal);
+                if cos_theta <= 0.0 {
+                    continue;
+                }
+                let over_point = &point + &(&normal * bias);
+                let received = if comps.object.shape().receives_shadow {
+                    self.shadow_filter_at(&over_point, comps.time)
+                } else {
+                    Color::WHITE
+                };
+                let arriving = &(&light.intensity * &received) * &throughput;
+                gathered = &gathered + &(&arriving * cos_theta as f32);
+            }
+        }
+        &(&gathered * &albedo) * (1.0 / subsurface.samples as f32)
+    }
+}
+
+// point where the walk leaves the object, with the share of light that made it through
+fn random_walk(
+    object: &Object,
+    subsurface: &Subsurface,
+    mut ray: Ray,
+    bias: f64,
+    rng: &mut Rng,
+) -> Option<(Point, Color)> {
+    let mut throughput = Color::WHITE;
+    for _ in 0..MAX_STEPS {
+        let step = -(1.0 - rng.next_f64()).ln() * subsurface.radius;
+        let mut crossings = object.intersect(&ray);
+        let mut surface = f64::INFINITY;
+        while let Some(crossing) = crossings.hit() {
+            if crossing.distance > bias {
+                surface = crossing.distance;
+                break;
+            }
+        }
+        if surface <= step {
+            return Option::Some((ray.position(surface), throughput));
+        }
+
+        throughput = &throughput * &subsurface.color;
+        if throughput.red.max(throughput.green).max(throughput.blue) < MIN_THROUGHPUT {
+            return Option::None;
+        }
+        // scatters evenly in all directions
+        let direction = sample_cone(rng, &ray.direction.normalize(), PI);
+        ray = Ray::new(ray.position(step), direction).with_time(ray.time);
+    }
+    Option::None
+}
@@ -0,0 +1,129 @@
+use crate::domain::object::Object;
+use crate::domain::{Point, Vector};
+use std::f64::consts::PI;
+
+// Projection used to turn object-space points into (u, v) texture coordinates.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum UvMapping {
+    // whatever the object's shape considers natural (see `Geometry::uv_at`)
+    Native,
+    Spherical,
+    Planar,
+    Cylindrical,
+    Cube,
+}
+
+impl UvMapping {
+    // maps the object-space point to (u, v) coordinates in the [0, 1) range
+    pub fn map(&self, object: &Object, object_point: &Point) -> (f64, f64) {
+        match self {
+            UvMapping::Native => object.geometry().uv_at(object_point),
+            UvMapping::Spherical => spherical_map(object_point),
+            UvMapping::Planar => planar_map(object_point),
+            UvMapping::Cylindrical => cylindrical_map(object_point),
+            UvMapping::Cube => cube_map(object_point),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum CubeFace {
+    Left,
+    Right,
+    Front,
+    Back,
+    Up,
+    Down,
+}
+
+impl CubeFace {
+    // (column, row) of the face in a 4x3 horizontal-cross layout, row 0 being the bottom
+    fn cross_cell(&self) -> (f64, f64) {
+        match self {
+            CubeFace::Left => (0.0, 1.0),
+            CubeFace::Front => (1.0, 1.0),
+            CubeFace::Right => (2.0, 1.0),
+            CubeFace::Back => (3.0, 1.0),
+            CubeFace::Up => (1.0, 2.0),
+            CubeFace::Down => (1.0, 0.0),
+        }
+    }
+}
+
+// wraps the unit sphere around the point's direction from the origin
+pub fn spherical_map(point: &Point) -> (f64, f64) {
+    let theta = point.x().atan2(point.z());
+    let radius = (point.x().powi(2) + point.y().powi(2) + point.z().powi(2)).sqrt();
+    let phi = (point.y() / radius).acos();
+    let raw_u = theta / (2.0 * PI);
+
+    (1.0 - (raw_u + 0.5), 1.0 - phi / PI)
+}
+
+// latitude/longitude of a direction as seen from inside the sphere, so -z sits at the image
+// edges and +z in its middle, with u increasing towards +x
+pub fn equirectangular_map(direction: &Vector) -> (f64, f64) {
+    let theta = direction.x().atan2(direction.z());
+    let phi = (direction.y() / direction.magnitude()).acos();
+
+    (0.5 + theta / (2.0 * PI), 1.0 - phi / PI)
+}
+
+// tiles the xz plane every unit
+pub fn planar_map(point: &Point) -> (f64, f64) {
+    (point.x().rem_euclid(1.0), point.z().rem_euclid(1.0))
+}
+
+// wraps around the y axis, repeating every unit of height
+pub fn cylindrical_map(point: &Point) -> (f64, f64) {
+    let theta = point.x().atan2(point.z());
+    let raw_u = theta / (2.0 * PI);
+
+    (1.0 - (raw_u + 0.5), point.y().rem_euclid(1.0))
+}
+
+// face of the unit cube the point lies on (largest coordinate wins)
+pub fn cube_face(point: &Point) -> CubeFace {
+    let abs_x = point.x().abs();
+    let abs_y = point.y().abs();
+    let abs_z = point.z().abs();
+    let coord = abs_x.max(abs_y.max(abs_z));
+
+    if coord == point.x() {
+        CubeFace::Right
+    } else if coord == -point.x() {
+        CubeFace::Left
+    } else if coord == point.y() {
+        CubeFace::Up
+    } else if coord == -point.y() {
+        CubeFace::Down
+    } else if coord == point.z() {
+        CubeFace::Front
+    } else {
+        CubeFace::Back
+    }
+}
+
+// (u, v) within a single face of the unit cube
+pub fn cube_face_uv(face: CubeFace, point: &Point) -> (f64, f64) {
+    let (x, y, z) = (point.x(), point.y(), point.z());
+    let (u, v) = match face {
+        CubeFace::Front => (x + 1.0, y + 1.0),
+        CubeFace::Back => (1.0 - x, y + 1.0),
+        CubeFace::Left => (z + 1.0, y + 1.0),
+        CubeFace::Right => (1.0 - z, y + 1.0),
+        CubeFace::Up => (x + 1.0, 1.0 - z),
+        CubeFace::Down => (x + 1.0, z + 1.0),
+    };
+
+    (u.rem_euclid(2.0) / 2.0, v.rem_euclid(2.0) / 2.0)
+}
+
+// unfolds all six faces of the unit cube into a single 4x3 horizontal cross
+pub fn cube_map(point: &Point) -> (f64, f64) {
+    let face = cube_face(point);
+    let (u, v) = cube_face_uv(face, point);
+    let (column, row) = face.cross_cell();
+
+    ((column + u) / 4.0, (row + v) / 3.0)
+}
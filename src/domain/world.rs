@@ -1,22 +1,66 @@
+use crate::domain::background::Background;
 use crate::domain::camera::Camera;
 use crate::domain::canvas::Canvas;
 use crate::domain::color::Color;
+use crate::domain::environment::EnvironmentLight;
+use crate::domain::integrator::Integrator;
 use crate::domain::intersection::{Computations, Intersections};
 use crate::domain::light::Light;
+use crate::domain::material::CHANNEL_WAVELENGTHS;
+use crate::domain::medium::Medium;
 use crate::domain::object::Object;
+use crate::domain::occlusion::AmbientOcclusion;
+use crate::domain::photon::PhotonMap;
+use crate::domain::random::{sample_cone, split_mix, Rng};
 use crate::domain::ray::Ray;
-use crate::domain::Point;
-use num::traits::Pow;
+use crate::domain::settings::{Recursion, RenderSettings};
+use crate::domain::{Point, Vector};
+use lazy_static::lazy_static;
 use rayon::prelude::*;
+use rayon::ThreadPool;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::io::Error;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
-// Determines maximum iteration depth when tracking ray bounces.
-const MAX_ITERATIONS: usize = 5;
+// Number of bounces over which dispersive refraction is split into per-channel rays; deeper
+// refractions use the material's single index to keep the ray count in check.
+const DISPERSION_BOUNCES: usize = 2;
+
+lazy_static! {
+    // pools for renders limited to a number of threads, built once per thread count
+    static ref THREAD_POOLS: Mutex<HashMap<usize, Arc<ThreadPool>>> = Mutex::new(HashMap::new());
+}
+
+fn thread_pool(threads: usize) -> Result<Arc<ThreadPool>, Error> {
+    let mut pools = THREAD_POOLS.lock().unwrap();
+    if let Some(pool) = pools.get(&threads) {
+        return Ok(Arc::clone(pool));
+    }
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .map_err(Error::other)?;
+    let pool = Arc::new(pool);
+    pools.insert(threads, Arc::clone(&pool));
+    Ok(pool)
+}
 
 pub struct World {
     pub objects: Vec<Object>,
     pub light_source: Option<Light>,
+    pub background: Background,
+    pub environment_light: Option<EnvironmentLight>,
+    pub ambient_occlusion: Option<AmbientOcclusion>,
+    // caustics gathered by `emit_photons`, added to every diffuse surface
+    pub photon_map: Option<PhotonMap>,
+    // participating medium filling the whole scene
+    pub fog: Option<Medium>,
+    // whether shadow rays pass through transparent objects, dimmed and tinted by them; otherwise
+    // any shadow casting object blocks the light
+    pub transparent_shadows: bool,
 }
 
 impl World {
@@ -24,6 +68,12 @@ impl World {
         World {
             objects: Vec::new(),
             light_source: Option::None,
+            background: Background::default(),
+            environment_light: Option::None,
+            ambient_occlusion: Option::None,
+            photon_map: Option::None,
+            fog: Option::None,
+            transparent_shadows: true,
         }
     }
 
@@ -43,23 +93,33 @@ impl World {
     }
 
     // Calculates shade hit for the given computations
-    pub fn shade_hit(&self, comp: &Computations, iteration: usize) -> Color {
-        let in_shadow = self.is_shadowed(&comp.over_point);
-
-        let surface = Light::lighting(
-            &comp.object.shape().material,
-            &comp.object,
-            self.light_source.as_ref().unwrap(),
-            &comp.over_point,
-            &comp.eye_v,
-            &comp.normal_v,
-            in_shadow,
-        );
+    pub fn shade_hit<R: Into<Recursion>>(&self, comp: &Computations, recursion: R) -> Color {
+        let recursion = recursion.into();
+        let direct = match self.light_source.as_ref() {
+            Some(light) => {
+                let material = &comp.object.shape().material;
+                let lit = Light::colored_lighting(
+                    material,
+                    &material.color_at_time(comp.object, &comp.over_point, comp.time),
+                    light,
+                    &comp.over_point,
+                    &comp.eye_v,
+                    &comp.normal_v,
+                    &self.received_light(comp),
+                );
+                &(&lit - &self.occluded_ambient(comp, light))
+                    + &self.subsurface_color(comp, recursion.bias)
+            }
+            None => Color::BLACK,
+        };
+        let material = &comp.object.shape().material;
+        let indirect = &self.environment_color(comp) + &self.caustic_color(comp);
+        // emission is added unconditionally, shadows don't dim a glowing surface
+        let surface = &(&direct + &indirect) + &material.emitted();
 
-        let reflected = self.reflected_color(comp, iteration);
-        let refracted = self.refracted_color(comp, iteration);
+        let reflected = self.reflected_color(comp, recursion);
+        let refracted = self.refracted_color(comp, recursion);
 
-        let material = &comp.object.shape().material;
         if material.reflective > 0.0 && material.transparency > 0.0 {
             let reflectance = comp.schlick();
             &(&surface + &(&reflected * reflectance as f32))
@@ -69,148 +129,481 @@ impl World {
         }
     }
 
-    // calculates color at a given point
-    pub fn color_at(&self, r: &Ray, iteration: usize) -> Color {
-        // find intersections
-        let mut ints = self.intersect(r);
+    // calculates color seen by the camera along the given ray
+    pub fn color_at<R: Into<Recursion>>(&self, r: &Ray, recursion: R) -> Color {
+        self.shade_ray(self.intersect_from_camera(r), r, recursion.into())
+    }
+
+    // calculates color seen along a reflected or refracted ray, which also sees the objects
+    // hidden from the camera
+    fn traced_color_at(&self, r: &Ray, recursion: Recursion) -> Color {
+        self.shade_ray(self.intersect(r), r, recursion)
+    }
+
+    // Returns intersections for given ray with the objects visible to the camera.
+    pub fn intersect_from_camera(&self, ray: &Ray) -> Intersections<'_> {
+        let mut ints = self.intersect(ray);
+        if self.objects.iter().any(|o| !o.shape().visible_to_camera) {
+            ints.retain(|i| i.object.shape().visible_to_camera);
+        }
+        ints
+    }
+
+    fn shade_ray(&self, mut ints: Intersections<'_>, r: &Ray, recursion: Recursion) -> Color {
         let original_ints = ints.clone();
 
-        match ints.hit() {
+        let (color, distance) = match ints.hit() {
             Some(intersection) => {
-                let comps = Computations::prepare_computations(
+                let comps = Computations::prepare_computations_with_bias(
                     &intersection,
                     r,
                     Option::Some(&original_ints),
+                    recursion.bias,
                 );
-                self.shade_hit(&comps, iteration)
+                (self.shade_hit(&comps, recursion), intersection.distance)
             }
-            None => Color::BLACK,
-        }
+            None => (self.background.color_at(&r.direction), f64::INFINITY),
+        };
+        self.through_media(r, distance, color)
     }
 
-    // renders world based on provided camera
+    // renders world based on provided camera and settings, failing when the thread pool asked for
+    // can't be set up
     // _logger fix for multi-threading comes from: https://users.rust-lang.org/t/how-to-send-function-closure-to-another-thread/43549
     pub fn render(
         &self,
         camera: &Camera,
+        settings: &RenderSettings,
         logger: Arc<dyn Fn(usize, usize) -> () + Send + Sync>,
-    ) -> Canvas {
+    ) -> Result<Canvas, Error> {
         let total_size = camera.vsize * camera.hsize;
         //let mut results: Vec<(usize, usize, Color)> = Vec::with_capacity(total_size);
 
         // track iterations for logging
         let itr_counter = AtomicUsize::new(0);
 
-        let iteration_max = MAX_ITERATIONS;
+        let recursion = settings.recursion();
+        let samples = settings.samples;
 
         // compute pixels
-        let mut results = (0..camera.vsize)
-            .into_par_iter()
-            .enumerate()
-            .flat_map(move |(_i, y)| {
-                let mut r: Vec<(usize, usize, Color)> = Vec::with_capacity(camera.hsize);
-                for x in 0..camera.hsize {
-                    // if x != 125 || y != 125 {
-                    //     continue;
-                    // }
-                    // println!("Rendering pixel ({}, {})...", x, y);
-                    let ray = camera.ray_for_pixel(x, y);
-                    // println!("---- Calling from world.render(...) ----");
-                    // let _ = stdout().flush();
-                    let color = self.color_at(&ray, iteration_max);
-                    r.push((x, y, color));
-                }
+        let pixels = || {
+            (0..camera.vsize)
+                .into_par_iter()
+                .enumerate()
+                .flat_map(move |(_i, y)| {
+                    let mut r: Vec<(usize, usize, Color)> = Vec::with_capacity(camera.hsize);
+                    for x in 0..camera.hsize {
+                        // if x != 125 || y != 125 {
+                        //     continue;
+                        // }
+                        // println!("Rendering pixel ({}, {})...", x, y);
+                        // println!("---- Calling from world.render(...) ----");
+                        // let _ = stdout().flush();
+                        let color = match settings.integrator {
+                            Integrator::Whitted => self.sampled_pixel(camera, x, y, samples, |r| {
+                                self.color_at(r, recursion)
+                            }),
+                            Integrator::PathTracing { max_bounces } => {
+                                self.path_traced_pixel(camera, x, y, samples, max_bounces)
+                            }
+                            Integrator::AmbientOcclusion(occlusion) => {
+                                self.sampled_pixel(camera, x, y, samples, |r| {
+                                    self.occlusion_color_at(r, &occlusion)
+                                })
+                            }
+                        };
+                        r.push((x, y, color));
+                    }
 
-                // log increment
-                let size = itr_counter.fetch_add(camera.hsize, Ordering::Relaxed);
-                let log = Arc::clone(&logger);
-                log(size + camera.hsize, total_size);
+                    // log increment
+                    let size = itr_counter.fetch_add(camera.hsize, Ordering::Relaxed);
+                    let log = Arc::clone(&logger);
+                    log(size + camera.hsize, total_size);
 
-                // return value
-                r
-            })
-            .collect::<Vec<(usize, usize, Color)>>();
+                    // return value
+                    r
+                })
+                .collect::<Vec<(usize, usize, Color)>>()
+        };
+        // a dedicated pool when the thread count is limited, rayon's global one otherwise
+        let mut results = match settings.threads {
+            Some(threads) => thread_pool(threads)?.install(pixels),
+            None => pixels(),
+        };
 
         // apply computed values to canvas
         let mut canvas = Canvas::new(camera.hsize, camera.vsize, Color::BLACK);
         results.drain(..).for_each(|(x, y, color)| {
             canvas.render(x, y, color);
         });
-        canvas
+        Ok(canvas)
+    }
+
+    // averages the color seen along the camera's sample rays for the pixel
+    fn sampled_pixel<F>(
+        &self,
+        camera: &Camera,
+        x: usize,
+        y: usize,
+        samples: usize,
+        color_at: F,
+    ) -> Color
+    where
+        F: Fn(&Ray) -> Color,
+    {
+        if samples <= 1 {
+            return color_at(&camera.ray_for_pixel(x, y));
+        }
+
+        // seeded by the pixel so renders are repeatable
+        let mut seed = ((y as u64) << 32) | x as u64;
+        let mut rng = Rng::new(split_mix(&mut seed));
+        let mut total = Color::BLACK;
+        for _ in 0..samples {
+            let ray = camera
+                .ray_for_sample(x, y, rng.next_f64(), rng.next_f64())
+                .with_time(camera.shutter_time(rng.next_f64()));
+            total = &total + &color_at(&ray);
+        }
+        &total * (1.0 / samples as f32)
     }
 
     // determines if point is shadowed
     pub fn is_shadowed(&self, p: &Point) -> bool {
+        self.shadow_filter(p) == Color::BLACK
+    }
+
+    // share of the light source's light reaching the shaded surface, unless it ignores shadows
+    pub fn received_light(&self, comps: &Computations) -> Color {
+        if comps.object.shape().receives_shadow {
+            self.shadow_filter_at(&comps.over_point, comps.time)
+        } else {
+            Color::WHITE
+        }
+    }
+
+    // share of the light source's light that reaches the point: white when unobstructed, black
+    // when blocked, and dimmed and tinted when it has to pass through transparent objects. With a
+    // photon map the light getting through transparent objects arrives as caustics instead, and
+    // without transparent shadows it doesn't get through at all.
+    pub fn shadow_filter(&self, p: &Point) -> Color {
+        self.shadow_filter_at(p, 0.0)
+    }
+
+    // share of the light reaching the point at the given time, moving objects cast their shadow
+    // from where they are at that moment
+    pub fn shadow_filter_at(&self, p: &Point, time: f64) -> Color {
         let v = &self.light_source.unwrap().position - p;
         let distance = v.magnitude();
         let direction = v.normalize();
 
-        let r = Ray::new(p.clone(), direction);
-        let mut intersections = self.intersect(&r);
-        let h = intersections.hit(); // TODO Should this be 'unchecked_hit'?
-        if h.is_some() && h.unwrap().distance < distance {
-            true
+        let ray = Ray::new(*p, direction).with_time(time);
+        let see_through = self.transparent_shadows && self.photon_map.is_none();
+        self.filter_along(&ray, distance, see_through)
+    }
+
+    // Light passing along the ray up to the given distance. Entering a shadow casting object lets
+    // through its color scaled by its transparency, and the way through it is absorbed like any
+    // other transmitted light.
+    pub fn transmission(&self, ray: &Ray, max_distance: f64) -> Color {
+        self.filter_along(ray, max_distance, true)
+    }
+
+    fn filter_along(&self, ray: &Ray, max_distance: f64, see_through: bool) -> Color {
+        let mut filter = if self.objects.iter().all(blocks_plainly) {
+            // nothing to see through or to be absorbed in, the light is either blocked or not
+            if self.is_blocked(ray, max_distance) {
+                return Color::BLACK;
+            }
+            Color::WHITE
         } else {
-            false
+            self.filter_through_objects(ray, max_distance, see_through)
+        };
+        if let Some(fog) = self.fog.as_ref() {
+            let through = fog.transmittance(ray, 0.0, max_distance.min(fog.max_distance));
+            filter = &filter * through as f32;
         }
+        filter
+    }
+
+    // whether a shadow casting object lies on the ray before the given distance
+    fn is_blocked(&self, ray: &Ray, max_distance: f64) -> bool {
+        self.objects
+            .iter()
+            .filter(|o| o.shape().casts_shadow)
+            .any(|o| {
+                let mut crossings = o.intersect(ray);
+                while let Some(hit) = crossings.hit() {
+                    if hit.distance > 0.0 {
+                        return hit.distance < max_distance;
+                    }
+                }
+                false
+            })
+    }
+
+    fn filter_through_objects(&self, ray: &Ray, max_distance: f64, see_through: bool) -> Color {
+        let mut intersections = self.intersect(ray);
+        let mut filter = Color::WHITE;
+
+        // objects the ray is inside of, with the distance at which it entered them
+        let mut containers: Vec<(&Object, f64)> = Vec::new();
+        while let Some(hit) = intersections.hit_unchecked() {
+            if !hit.distance.is_finite() {
+                continue;
+            }
+            if hit.distance >= max_distance {
+                break;
+            }
+            if !hit.object.shape().casts_shadow {
+                continue;
+            }
+            if !see_through && hit.distance > 0.0 {
+                return Color::BLACK;
+            }
+
+            let material = &hit.object.shape().material;
+            let id = hit.object.shape().id;
+            match containers.iter().position(|(o, _)| o.shape().id == id) {
+                Some(index) => {
+                    let (_, entered) = containers.remove(index);
+                    if hit.distance > 0.0 {
+                        filter = &filter * &self.passage(hit.object, ray, entered, hit.distance);
+                    }
+                }
+                None => {
+                    containers.push((hit.object, hit.distance));
+                    if hit.distance > 0.0 {
+                        let color = material.color_at_time(
+                            hit.object,
+                            &ray.position(hit.distance),
+                            ray.time,
+                        );
+                        filter = &filter * &(&color * material.transparency as f32);
+                    }
+                }
+            }
+
+            if filter == Color::BLACK {
+                return Color::BLACK;
+            }
+        }
+
+        // the light may sit inside an object too
+        let end = max_distance.min(f64::MAX);
+        for (object, entered) in containers {
+            filter = &filter * &self.passage(object, ray, entered, end);
+        }
+        filter
+    }
+
+    // light surviving the way through an object between the given distances along the ray
+    fn passage(&self, object: &Object, ray: &Ray, entered: f64, left: f64) -> Color {
+        let material = &object.shape().material;
+        let from = entered.max(0.0);
+        let absorbed = material.transmittance(left - from);
+        match material.medium.as_ref() {
+            Some(medium) => &absorbed * medium.transmittance(ray, from, left) as f32,
+            None => absorbed,
+        }
+    }
+
+    // diffuse light received from the environment map, estimated by importance sampling the map
+    // and tracing a shadow ray for every sample
+    pub fn environment_color(&self, comps: &Computations) -> Color {
+        let environment = match self.environment_light.as_ref() {
+            Some(environment) => environment,
+            None => return Color::BLACK,
+        };
+        let albedo = comps.object.shape().material.diffuse_color_at(
+            comps.object,
+            &comps.over_point,
+            comps.time,
+        );
+        if albedo == Color::BLACK || environment.samples == 0 {
+            return Color::BLACK;
+        }
+
+        // seeded by the shaded point so renders are repeatable
+        let mut rng = Rng::from_point(&comps.over_point, 0);
+        let mut irradiance = Color::BLACK;
+        for _ in 0..environment.samples {
+            let (direction, pdf) = match environment.sample(&mut rng) {
+                Some(sample) => sample,
+                None => continue,
+            };
+            let cos_theta = direction.dot_product(&comps.normal_v);
+            if cos_theta <= 0.0 {
+                continue;
+            }
+
+            let shadow_ray = Ray::new(comps.over_point, direction).with_time(comps.time);
+            let filter = if comps.object.shape().receives_shadow {
+                self.transmission(&shadow_ray, f64::INFINITY)
+            } else {
+                Color::WHITE
+            };
+            if filter == Color::BLACK {
+                continue;
+            }
+            let radiance = &environment.radiance(&direction) * &filter;
+            irradiance = &irradiance + &(&radiance * (cos_theta / pdf) as f32);
+        }
+
+        // lambertian reflection of the averaged irradiance
+        &(&albedo * &irradiance) * (1.0 / (PI * environment.samples as f64)) as f32
     }
 
     // performs reflection calculations
-    pub fn reflected_color(&self, comps: &Computations, iteration: usize) -> Color {
-        if iteration == 0 || comps.object.shape().material.reflective == 0.0 {
+    pub fn reflected_color<R: Into<Recursion>>(&self, comps: &Computations, recursion: R) -> Color {
+        let recursion = recursion.into();
+        if !recursion.can_reflect() || comps.object.shape().material.reflective == 0.0 {
             Color::BLACK
         } else {
-            let reflect_ray = Ray::new(comps.over_point.clone(), comps.reflect_v.clone());
             // println!("---- Calling from world.reflected_color(...) ----");
             // let _ = stdout().flush();
-            let color = self.color_at(&reflect_ray, iteration - 1);
+            let color =
+                self.glossy_color(comps, &comps.over_point, &comps.reflect_v, 1.0, recursion);
             &color * comps.object.shape().material.reflective as f32
         }
     }
 
     // performs refracted color calculation
-    pub fn refracted_color(&self, comps: &Computations, iteration: usize) -> Color {
-        if iteration == 0 {
-            Color::BLACK
-        } else if comps.object.shape().material.transparency == 0.0 {
+    pub fn refracted_color<R: Into<Recursion>>(&self, comps: &Computations, recursion: R) -> Color {
+        let recursion = recursion.into();
+        if !recursion.can_refract() || comps.object.shape().material.transparency == 0.0 {
             Color::BLACK
         } else {
-            let n_ratio = comps.n1 / comps.n2;
-            let cos_i = comps.eye_v.dot_product(&comps.normal_v);
-            let sin2_t: f64 = n_ratio.pow(2) * (1.0 - cos_i.pow(2));
-
-            if sin2_t > 1.0 {
-                return Color::BLACK;
+            let material = &comps.object.shape().material;
+            match material.dispersion {
+                Some(dispersion) if recursion.bounce < DISPERSION_BOUNCES => {
+                    // one ray per channel, each bent by the index for its own wavelength
+                    let channels: Vec<Color> = CHANNEL_WAVELENGTHS
+                        .iter()
+                        .map(|wavelength| {
+                            let index = dispersion.index_at(*wavelength);
+                            let (n1, n2) = if comps.inside {
+                                (index, comps.n2)
+                            } else {
+                                (comps.n1, index)
+                            };
+                            self.refracted_ray_color(comps, n1, n2, recursion)
+                        })
+                        .collect();
+                    Color::new(channels[0].red, channels[1].green, channels[2].blue)
+                }
+                _ => self.refracted_ray_color(comps, comps.n1, comps.n2, recursion),
             }
+        }
+    }
 
-            let cos_t: f64 = (1_f64 - sin2_t).sqrt();
-            let direction =
-                &(&comps.normal_v * (n_ratio * cos_i - cos_t)) - &(&comps.eye_v * n_ratio);
-            let refract_ray = Ray::new(comps.under_point.clone(), direction);
-
-            // println!("---- Calling from world.refracted_color(...) ----");
-            //let _ = stdout().flush();
-            let refracted_color = &self.color_at(&refract_ray, iteration - 1)
-                * comps.object.shape().material.transparency as f32;
-
-            // ---------
-            // println!("== remaining {} ==", iteration);
-            // println!("> n1: {}", &comps.n1);
-            // println!("> n2: {}", &comps.n2);
-            // println!("> eyev: {:?}", &comps.eye_v);
-            // println!("> normalv: {:?}", &comps.normal_v);
-            // println!("> under point: {:?}", &comps.under_point);
-            // println!("> n_ratio: {}", n_ratio);
-            // println!("> cos_i: {}", cos_i);
-            // println!("> sin2_t: {}", sin2_t);
-            // println!("> cos_t: {}", cos_t);
-            // println!("> refracted direction: {:?}", direction);
-            // println!("> refracted_color: {:?}", refracted_color);
-            // let _ = stdout().flush();
-            //
-            // ---------
+    // color seen along a single refracted ray, given the refractive indices on either side
+    fn refracted_ray_color(
+        &self,
+        comps: &Computations,
+        n1: f64,
+        n2: f64,
+        recursion: Recursion,
+    ) -> Color {
+        let direction = match comps.refracted_direction(n1, n2) {
+            Some(direction) => direction,
+            None => return Color::BLACK,
+        };
+
+        // println!("---- Calling from world.refracted_color(...) ----");
+        //let _ = stdout().flush();
+        let material = &comps.object.shape().material;
+        let mut refracted_color =
+            &self.glossy_color(comps, &comps.under_point, &direction, -1.0, recursion)
+                * material.transparency as f32;
+
+        // light entering the object is absorbed on its way to the far side, wherever other
+        // objects nested within it are
+        if !comps.inside && material.absorption_density > 0.0 {
+            let ray = Ray::new(comps.under_point, direction).with_time(comps.time);
+            let distance = match comps.object.intersect(&ray).hit() {
+                Some(exit) => exit.distance,
+                None => f64::INFINITY,
+            };
+            refracted_color = &refracted_color * &material.transmittance(distance);
+        }
+
+        // ---------
+        // println!("== remaining {} ==", iteration);
+        // println!("> n1: {}", &comps.n1);
+        // println!("> n2: {}", &comps.n2);
+        // println!("> eyev: {:?}", &comps.eye_v);
+        // println!("> normalv: {:?}", &comps.normal_v);
+        // println!("> under point: {:?}", &comps.under_point);
+        // println!("> n_ratio: {}", n_ratio);
+        // println!("> cos_i: {}", cos_i);
+        // println!("> sin2_t: {}", sin2_t);
+        // println!("> cos_t: {}", cos_t);
+        // println!("> refracted direction: {:?}", direction);
+        // println!("> refracted_color: {:?}", refracted_color);
+        // let _ = stdout().flush();
+        //
+        // ---------
+
+        refracted_color
+    }
 
-            refracted_color
+    // traces the ideal reflected/refracted ray, or for rough materials averages rays spread in a
+    // cone around it (side tells whether the ray leaves along or against the normal, that is
+    // whether it counts as a reflection or a refraction). Each bounce
+    // gets a quarter of the previous bounce's samples to keep the ray count from exploding.
+    fn glossy_color(
+        &self,
+        comps: &Computations,
+        origin: &Point,
+        direction: &Vector,
+        side: f64,
+        recursion: Recursion,
+    ) -> Color {
+        let next = if side > 0.0 {
+            recursion.reflected()
+        } else {
+            recursion.refracted()
+        };
+        let material = &comps.object.shape().material;
+        if material.roughness <= 0.0 {
+            let ray = Ray::new(*origin, *direction).with_time(comps.time);
+            return self.traced_color_at(&ray, next);
         }
+
+        let samples = material
+            .glossy_samples
+            .checked_shr(2 * recursion.bounce as u32)
+            .unwrap_or(0)
+            .max(1);
+        let half_angle = material.roughness.min(1.0) * PI / 2.0;
+        let axis = direction.normalize();
+
+        // seeded by the origin and side so reflection and refraction decorrelate
+        let mut rng = Rng::from_point(origin, side.to_bits());
+        let mut total = Color::BLACK;
+        for _ in 0..samples {
+            let mut sample = sample_cone(&mut rng, &axis, half_angle);
+
+            // mirror samples that crossed over to the wrong side of the surface
+            let along_normal = sample.dot_product(&comps.normal_v);
+            if along_normal * side < 0.0 {
+                sample = &sample - &(&comps.normal_v * (2.0 * along_normal));
+            }
+            let ray = Ray::new(*origin, sample).with_time(comps.time);
+            total = &total + &self.traced_color_at(&ray, next);
+        }
+        &total * (1.0 / samples as f32)
     }
 }
+
+// whether the object stops all the light reaching it, or lets it pass untouched when it doesn't
+// cast a shadow
+fn blocks_plainly(object: &Object) -> bool {
+    let shape = object.shape();
+    let material = &shape.material;
+    !shape.casts_shadow
+        || (material.transparency == 0.0
+            && material.absorption_density == 0.0
+            && material.medium.is_none())
+}
@@ -0,0 +1,88 @@
+use crate::domain::background::Background;
+use crate::domain::color::Color;
+use crate::domain::material::Material;
+use crate::domain::matrix::Matrix;
+use crate::domain::object::Sphere;
+use crate::domain::ray::Ray;
+use crate::domain::world::World;
+use crate::domain::{Point, Vector};
+
+fn tinted_glass(color: Color, density: f64) -> Material {
+    Material::new()
+        .ambient(0.0)
+        .diffuse(0.0)
+        .specular(0.0)
+        .transparency(1.0)
+        .absorption_color(color)
+        .absorption_density(density)
+        .build()
+}
+
+// a glass ball in front of a white background, without bending rays
+fn glass_world(material: Material) -> World {
+    let mut w = World::new();
+    w.add_object(Sphere::new().material(material).build().into());
+    w.background = Background::Solid(Color::WHITE);
+    w
+}
+
+#[test]
+fn ch11_test30_default_material_absorbs_nothing() {
+    let m = Material::default();
+    assert_eq!(m.absorption_density, 0.0);
+    assert_eq!(m.transmittance(100.0), Color::WHITE);
+}
+
+#[test]
+fn ch11_test31_transmittance_follows_beer_lambert() {
+    let m = tinted_glass(Color::new(0.5, 1.0, 0.25), 1.0);
+    assert_eq!(m.transmittance(0.0), Color::WHITE);
+    assert_eq!(m.transmittance(1.0), Color::new(0.5, 1.0, 0.25));
+    assert_eq!(m.transmittance(2.0), Color::new(0.25, 1.0, 0.0625));
+    assert_eq!(m.transmittance(f64::INFINITY), Color::new(0.0, 1.0, 0.0));
+
+    // fully absorbing channels let everything through over no distance at all
+    let opaque = tinted_glass(Color::new(0.0, 1.0, 0.5), 1.0);
+    assert_eq!(opaque.transmittance(0.0), Color::WHITE);
+    assert_eq!(opaque.transmittance(1.0), Color::new(0.0, 1.0, 0.5));
+
+    // density scales the distance
+    let dense = tinted_glass(Color::new(0.5, 1.0, 0.25), 2.0);
+    assert_eq!(dense.transmittance(1.0), m.transmittance(2.0));
+}
+
+#[test]
+fn ch11_test32_light_through_glass_is_absorbed_by_distance() {
+    let w = glass_world(tinted_glass(Color::new(0.5, 0.9, 0.5), 1.0));
+
+    // through the center: two units of glass
+    let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+    assert_eq!(w.color_at(&r, 5), Color::new(0.25, 0.81, 0.25));
+
+    // a shorter chord absorbs less
+    let r = Ray::new(Point::new(0.0, 0.8, -5.0), Vector::new(0.0, 0.0, 1.0));
+    let color = w.color_at(&r, 5);
+    assert!(color.red > 0.25 && color.red < 1.0);
+    assert!(color.green > color.red);
+}
+
+#[test]
+fn ch11_test33_clear_glass_tints_nothing() {
+    let w = glass_world(tinted_glass(Color::new(0.5, 0.9, 0.5), 0.0));
+    let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+    assert_eq!(w.color_at(&r, 5), Color::WHITE);
+}
+
+#[test]
+fn ch11_test34_absorption_spans_the_whole_object() {
+    // a clear bubble at the center doesn't cut the trip through the tinted ball short
+    let mut w = glass_world(tinted_glass(Color::new(0.5, 0.9, 0.5), 1.0));
+    let bubble = Sphere::new()
+        .material(tinted_glass(Color::WHITE, 0.0))
+        .transformation(Matrix::new_scaling(0.25, 0.25, 0.25))
+        .build();
+    w.add_object(bubble.into());
+
+    let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+    assert_eq!(w.color_at(&r, 5), Color::new(0.25, 0.81, 0.25));
+}
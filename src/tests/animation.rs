@@ -0,0 +1,352 @@
+use crate::domain::animation::{Animation, Easing, Interpolate, MaterialValue, Pose, Track};
+use crate::domain::camera::Camera;
+use crate::domain::color::Color;
+use crate::domain::light::Light;
+use crate::domain::material::Material;
+use crate::domain::matrix::{Matrix, IDENTITY};
+use crate::domain::object::{Object, Sphere};
+use crate::domain::pattern::Pattern;
+use crate::domain::settings::RenderSettings;
+use crate::domain::world::World;
+use crate::domain::{Point, Vector};
+use crate::utils::{read_imagefile, render_frames};
+use std::f64::consts::PI;
+use std::fs;
+use std::sync::Arc;
+
+fn scene() -> (World, Camera) {
+    let mut w = World::new();
+    w.light_source = Option::Some(Light::new(Point::new(-10.0, 10.0, -10.0), Color::WHITE));
+    let ball: Object = Sphere::new().build().into();
+    w.add_object(ball);
+
+    let mut c = Camera::new(5, 5, PI / 3.0);
+    c.transform = Matrix::new_view_transformation(
+        &Point::new(0.0, 0.0, -5.0),
+        &Point::ORIGIN,
+        &Vector::new(0.0, 1.0, 0.0),
+    );
+    (w, c)
+}
+
+fn turn(angle: f64) -> Pose {
+    Pose {
+        rotation: Vector::new(0.0, angle, 0.0),
+        ..Pose::default()
+    }
+}
+
+#[test]
+fn ch7_test37_easing_curves() {
+    for easing in [
+        Easing::Linear,
+        Easing::EaseIn,
+        Easing::EaseOut,
+        Easing::EaseInOut,
+    ]
+    .iter()
+    {
+        assert_eq!(easing.apply(0.0), 0.0);
+        assert_eq!(easing.apply(1.0), 1.0);
+    }
+    assert_eq!(Easing::Linear.apply(0.25), 0.25);
+    assert_eq!(Easing::EaseIn.apply(0.5), 0.25);
+    assert_eq!(Easing::EaseOut.apply(0.5), 0.75);
+    assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+    assert!(Easing::EaseInOut.apply(0.25) < 0.25);
+    // no overshooting outside the segment
+    assert_eq!(Easing::EaseIn.apply(2.0), 1.0);
+}
+
+#[test]
+fn ch7_test38_values_interpolate() {
+    assert_eq!(2.0.interpolate(&4.0, 0.25), 2.5);
+    assert_eq!(
+        Point::new(0.0, 2.0, 0.0).interpolate(&Point::new(4.0, 0.0, 0.0), 0.5),
+        Point::new(2.0, 1.0, 0.0)
+    );
+    assert_eq!(
+        Color::BLACK.interpolate(&Color::new(1.0, 0.5, 0.0), 0.5),
+        Color::new(0.5, 0.25, 0.0)
+    );
+}
+
+#[test]
+fn ch7_test39_tracks_blend_between_keyframes() {
+    let empty: Track<f64> = Track::new().build();
+    assert_eq!(empty.value_at(3.0), Option::None);
+
+    // keyframes may be given in any order
+    let track = Track::new()
+        .key(10.0, 2.0)
+        .eased_key(20.0, 4.0, Easing::EaseIn)
+        .key(1.0, 0.0)
+        .build();
+    assert_eq!(track.keys()[0].frame, 1.0);
+
+    // holds before the first and after the last keyframe
+    assert_eq!(track.value_at(0.0), Option::Some(0.0));
+    assert_eq!(track.value_at(25.0), Option::Some(4.0));
+    // exactly on a keyframe
+    assert_eq!(track.value_at(10.0), Option::Some(2.0));
+    // linear on the way to the second keyframe, eased into the third
+    assert_eq!(track.value_at(5.5), Option::Some(1.0));
+    assert_eq!(track.value_at(15.0), Option::Some(2.5));
+
+    // keyframes without a frame are dropped
+    let track = Track::new().key(f64::NAN, 9.0).key(1.0, 0.0).build();
+    assert_eq!(track.keys().len(), 1);
+    assert_eq!(track.value_at(f64::NAN), Option::Some(0.0));
+}
+
+#[test]
+fn ch7_test40_poses_compose_scaling_rotation_and_translation() {
+    assert_eq!(
+        Pose::default().transformation(),
+        crate::domain::matrix::IDENTITY.clone()
+    );
+
+    let pose = Pose::new(
+        Vector::new(1.0, 2.0, 3.0),
+        Vector::new(0.0, PI / 2.0, 0.0),
+        Vector::new(2.0, 2.0, 2.0),
+    );
+    assert_eq!(
+        &pose.transformation() * &Point::new(1.0, 0.0, 0.0),
+        Point::new(1.0, 2.0, 1.0)
+    );
+
+    // a full turn passes through half of it
+    let half = turn(0.0).interpolate(&turn(2.0 * PI), 0.5);
+    assert_eq!(half.transformation(), Matrix::new_rotation_y(PI));
+}
+
+#[test]
+fn ch7_test41_animations_pose_the_scene() {
+    let (mut w, mut c) = scene();
+    let ball = w.objects[0].clone();
+    let animation = Animation::new()
+        .camera_view(
+            Track::new()
+                .key(0.0, Point::new(0.0, 0.0, -5.0))
+                .key(10.0, Point::new(0.0, 0.0, -15.0))
+                .build(),
+            Track::new().key(0.0, Point::ORIGIN).build(),
+            Vector::new(0.0, 1.0, 0.0),
+        )
+        .field_of_view(Track::new().key(0.0, PI / 3.0).key(10.0, PI / 2.0).build())
+        .transformation(
+            &ball,
+            Track::new()
+                .key(0.0, turn(0.0))
+                .key(10.0, turn(2.0 * PI))
+                .build(),
+        )
+        .light_position(
+            Track::new()
+                .key(0.0, Point::new(-10.0, 10.0, -10.0))
+                .key(10.0, Point::new(10.0, 10.0, -10.0))
+                .build(),
+        )
+        .light_intensity(
+            Track::new()
+                .key(0.0, Color::WHITE)
+                .key(10.0, Color::BLACK)
+                .build(),
+        )
+        .material_color(
+            &ball,
+            Track::new()
+                .key(0.0, Color::WHITE)
+                .key(10.0, Color::new(1.0, 0.0, 0.0))
+                .build(),
+        )
+        .material(
+            &ball,
+            MaterialValue::Reflective,
+            Track::new().key(0.0, 0.0).key(10.0, 1.0).build(),
+        )
+        .material(
+            &ball,
+            MaterialValue::RefractiveIndex,
+            Track::new().key(0.0, 1.0).key(10.0, 2.0).build(),
+        )
+        .build();
+
+    animation.apply(&mut w, &mut c, 5.0);
+    assert_eq!(
+        c.transform,
+        Matrix::new_view_transformation(
+            &Point::new(0.0, 0.0, -10.0),
+            &Point::ORIGIN,
+            &Vector::new(0.0, 1.0, 0.0)
+        )
+    );
+    assert!(crate::domain::epsilon_eq(c.field_of_view, 5.0 * PI / 12.0));
+    assert!(crate::domain::epsilon_eq(
+        c.pixel_size,
+        Camera::new(5, 5, 5.0 * PI / 12.0).pixel_size
+    ));
+
+    let shape = w.objects[0].shape();
+    assert_eq!(shape.transformation, Matrix::new_rotation_y(PI));
+    assert!(shape.end_transformation.is_none());
+    assert_eq!(shape.material.color, Color::new(1.0, 0.5, 0.5));
+    assert_eq!(shape.material.reflective, 0.5);
+    assert_eq!(shape.material.refractive_index(), 1.5);
+
+    let light = w.light_source.unwrap();
+    assert_eq!(light.position, Point::new(0.0, 10.0, -10.0));
+    assert_eq!(light.intensity, Color::new(0.5, 0.5, 0.5));
+}
+
+#[test]
+fn ch7_test42_animations_ignore_missing_objects() {
+    let (mut w, mut c) = scene();
+    let stranger: Object = Sphere::new()
+        .material(Material::new().color(Color::new(0.2, 0.2, 0.2)).build())
+        .build()
+        .into();
+    let animation = Animation::new()
+        .material_color(&stranger, Track::new().key(0.0, Color::BLACK).build())
+        .build();
+    animation.apply(&mut w, &mut c, 0.0);
+    assert_eq!(w.objects[0].shape().material.color, Color::WHITE);
+}
+
+#[test]
+fn ch7_test43_material_color_tracks_leave_patterns_alone() {
+    let (mut w, mut c) = scene();
+    let stripes = Pattern::new_striped(Color::WHITE, Color::BLACK, IDENTITY.clone());
+    w.objects[0].shape_mut().material = Material::new().pattern(stripes).build();
+    let red = Color::new(1.0, 0.0, 0.0);
+    let animation = Animation::new()
+        .material_color(&w.objects[0], Track::new().key(0.0, red).build())
+        .build();
+    animation.apply(&mut w, &mut c, 0.0);
+
+    // the color changes, but the pattern still decides what the surface looks like
+    let ball = &w.objects[0];
+    assert_eq!(ball.shape().material.color, red);
+    assert_eq!(
+        ball.shape()
+            .material
+            .color_at(ball, &Point::new(0.5, 0.0, 0.0)),
+        Color::WHITE
+    );
+    assert_eq!(
+        ball.shape()
+            .material
+            .color_at(ball, &Point::new(-0.5, 0.0, 0.0)),
+        Color::BLACK
+    );
+}
+
+#[test]
+fn ch7_test44_open_shutters_blur_animated_objects_towards_the_next_frame() {
+    let (mut w, mut c) = scene();
+    let ball = w.objects[0].clone();
+    let shift = |x: f64| Pose {
+        translation: Vector::new(x, 0.0, 0.0),
+        ..Pose::default()
+    };
+    let animation = Animation::new()
+        .transformation(
+            &ball,
+            Track::new()
+                .key(0.0, shift(0.0))
+                .key(10.0, shift(10.0))
+                .build(),
+        )
+        .build();
+
+    c.shutter_close = 0.5;
+    animation.apply(&mut w, &mut c, 3.0);
+    let shape = w.objects[0].shape();
+    assert_eq!(shape.transformation, Matrix::new_translation(3.0, 0.0, 0.0));
+    assert_eq!(
+        shape.end_transformation,
+        Option::Some(Matrix::new_translation(4.0, 0.0, 0.0))
+    );
+}
+
+#[test]
+fn ch7_test45_frame_sequences_are_written_to_numbered_files() {
+    let (mut w, mut c) = scene();
+    let animation = Animation::new()
+        .light_intensity(
+            Track::new()
+                .key(1.0, Color::WHITE)
+                .key(3.0, Color::BLACK)
+                .build(),
+        )
+        .build();
+
+    let dir = std::env::temp_dir().join(format!("ray_tracer_frames_{}", std::process::id()));
+    let written = render_frames(
+        &mut w,
+        &mut c,
+        &animation,
+        &RenderSettings::default(),
+        1..=3,
+        &dir,
+        Arc::new(|_, _| {}),
+    )
+    .unwrap();
+    let names: Vec<String> = written
+        .iter()
+        .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(
+        names,
+        vec!["frame_0001.ppm", "frame_0002.ppm", "frame_0003.ppm"]
+    );
+
+    // the ball dims from frame to frame
+    let center = |path| read_imagefile(path).unwrap().pixel_at(2, 2).red;
+    assert!(center(&written[0]) > center(&written[1]));
+    assert!(center(&written[1]) > center(&written[2]));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn ch7_test46_animated_objects_keep_their_own_motion_blur() {
+    let (mut w, mut c) = scene();
+    // the ball drifts one unit along x while the shutter is open
+    w.objects[0].shape_mut().transformation = Matrix::new_translation(0.0, 2.0, 0.0);
+    w.objects[0].shape_mut().end_transformation =
+        Option::Some(Matrix::new_translation(1.0, 2.0, 0.0));
+    let ball = w.objects[0].clone();
+    let lift = |y: f64| Pose {
+        translation: Vector::new(0.0, y, 0.0),
+        ..Pose::default()
+    };
+    let animation = Animation::new()
+        .transformation(
+            &ball,
+            Track::new()
+                .key(0.0, lift(0.0))
+                .key(10.0, lift(10.0))
+                .build(),
+        )
+        .build();
+
+    // still frames keep the drift around the animated pose, every time they're posed
+    for _ in 0..2 {
+        animation.apply(&mut w, &mut c, 3.0);
+        let shape = w.objects[0].shape();
+        assert_eq!(shape.transformation, Matrix::new_translation(0.0, 3.0, 0.0));
+        assert_eq!(
+            shape.end_transformation,
+            Option::Some(Matrix::new_translation(1.0, 3.0, 0.0))
+        );
+    }
+
+    // with the animation blurring too, the drift adds to the move towards the next frame
+    c.shutter_close = 0.5;
+    animation.apply(&mut w, &mut c, 3.0);
+    assert_eq!(
+        w.objects[0].shape().end_transformation,
+        Option::Some(Matrix::new_translation(1.0, 4.0, 0.0))
+    );
+}
@@ -0,0 +1,198 @@
+use crate::domain::background::{Background, CubeMap};
+use crate::domain::canvas::Canvas;
+use crate::domain::color::Color;
+use crate::domain::material::Material;
+use crate::domain::matrix::Matrix;
+use crate::domain::object::{Object, Plane};
+use crate::domain::ray::Ray;
+use crate::domain::uv::equirectangular_map;
+use crate::domain::{Point, Vector};
+use crate::tests::world::build_test_world;
+use std::sync::Arc;
+
+fn solid_canvas(color: Color) -> Arc<Canvas> {
+    Arc::new(Canvas::new(4, 4, color))
+}
+
+fn assert_uv(actual: (f64, f64), expected: (f64, f64)) {
+    assert!(
+        crate::domain::epsilon_eq(actual.0, expected.0)
+            && crate::domain::epsilon_eq(actual.1, expected.1),
+        "{:?} != {:?}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn ch7_test18_default_background_is_black() {
+    let w = build_test_world();
+    assert_eq!(w.background, Background::Solid(Color::BLACK));
+
+    let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 1.0, 0.0));
+    assert_eq!(w.color_at(&r, 5), Color::BLACK);
+}
+
+#[test]
+fn ch7_test19_missed_ray_sees_background() {
+    let mut w = build_test_world();
+    w.background = Background::Solid(Color::new(0.2, 0.4, 0.6));
+    let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 1.0, 0.0));
+    assert_eq!(w.color_at(&r, 5), Color::new(0.2, 0.4, 0.6));
+}
+
+#[test]
+fn ch7_test20_sky_gradient_follows_ray_height() {
+    let ground = Color::new(0.3, 0.2, 0.1);
+    let horizon = Color::WHITE;
+    let zenith = Color::new(0.2, 0.4, 1.0);
+    let sky = Background::new_sky(ground, horizon, zenith);
+
+    assert_eq!(sky.color_at(&Vector::new(0.0, 1.0, 0.0)), zenith);
+    assert_eq!(sky.color_at(&Vector::new(0.0, -2.0, 0.0)), ground);
+    assert_eq!(sky.color_at(&Vector::new(1.0, 0.0, 0.0)), horizon);
+    assert_eq!(
+        sky.color_at(&Vector::new(0.0, 1.0, -3_f64.sqrt())),
+        Color::new(0.6, 0.7, 1.0)
+    );
+}
+
+#[test]
+fn ch7_test21_cube_map_background_picks_face_by_direction() {
+    let colors = [
+        Color::new(1.0, 0.0, 0.0),
+        Color::new(0.0, 1.0, 0.0),
+        Color::new(0.0, 0.0, 1.0),
+        Color::new(1.0, 1.0, 0.0),
+        Color::new(0.0, 1.0, 1.0),
+        Color::new(1.0, 0.0, 1.0),
+    ];
+    let background = Background::CubeMap(CubeMap::new(
+        solid_canvas(colors[0]),
+        solid_canvas(colors[1]),
+        solid_canvas(colors[2]),
+        solid_canvas(colors[3]),
+        solid_canvas(colors[4]),
+        solid_canvas(colors[5]),
+    ));
+
+    let cases = [
+        (Vector::new(-1.0, 0.2, 0.3), colors[0]),
+        (Vector::new(5.0, 0.2, -1.0), colors[1]),
+        (Vector::new(0.1, -0.2, 1.0), colors[2]),
+        (Vector::new(0.0, 0.3, -0.5), colors[3]),
+        (Vector::new(0.2, 3.0, 0.1), colors[4]),
+        (Vector::new(0.0, -1.0, 0.0), colors[5]),
+    ];
+    for (direction, color) in cases.iter() {
+        assert_eq!(background.color_at(direction), *color);
+    }
+}
+
+#[test]
+fn ch7_test22_cube_map_face_orientation() {
+    // left half of the front face is red, right half is blue
+    let mut front = Canvas::new(2, 1, Color::new(1.0, 0.0, 0.0));
+    front[0][1] = Color::new(0.0, 0.0, 1.0);
+    let black = solid_canvas(Color::BLACK);
+    let cube_map = CubeMap::new(
+        black.clone(),
+        black.clone(),
+        Arc::new(front),
+        black.clone(),
+        black.clone(),
+        black,
+    );
+
+    assert_eq!(
+        cube_map.color_at(&Vector::new(-0.9, 0.0, 1.0)),
+        Color::new(1.0, 0.0, 0.0)
+    );
+    assert_eq!(
+        cube_map.color_at(&Vector::new(0.9, 0.0, 1.0)),
+        Color::new(0.0, 0.0, 1.0)
+    );
+}
+
+#[test]
+fn ch7_test23_equirectangular_map_of_directions() {
+    assert_uv(equirectangular_map(&Vector::new(0.0, 0.0, 1.0)), (0.5, 0.5));
+    assert_uv(
+        equirectangular_map(&Vector::new(1.0, 0.0, 0.0)),
+        (0.75, 0.5),
+    );
+    assert_uv(
+        equirectangular_map(&Vector::new(-1.0, 0.0, 0.0)),
+        (0.25, 0.5),
+    );
+    assert_uv(equirectangular_map(&Vector::new(0.0, 2.0, 0.0)), (0.5, 1.0));
+    assert_uv(
+        equirectangular_map(&Vector::new(0.0, -1.0, 0.0)),
+        (0.5, 0.0),
+    );
+}
+
+#[test]
+fn ch7_test24_equirectangular_background() {
+    // top half bright, bottom half dark
+    let mut canvas = Canvas::new(8, 4, Color::new(0.1, 0.1, 0.1));
+    for row in 0..2 {
+        for column in 0..8 {
+            canvas[row][column] = Color::new(0.9, 0.9, 1.0);
+        }
+    }
+    let background = Background::new_equirectangular(Arc::new(canvas));
+
+    assert_eq!(
+        background.color_at(&Vector::new(0.3, 1.0, -0.2)),
+        Color::new(0.9, 0.9, 1.0)
+    );
+    assert_eq!(
+        background.color_at(&Vector::new(-0.3, -1.0, 0.5)),
+        Color::new(0.1, 0.1, 0.1)
+    );
+}
+
+#[test]
+fn ch7_test25_escaping_reflection_sees_background() {
+    let mut w = build_test_world();
+    w.background = Background::Solid(Color::new(0.0, 0.0, 1.0));
+    let floor: Object = Plane::new()
+        .material(
+            Material::new()
+                .reflective(1.0)
+                .diffuse(0.0)
+                .ambient(0.0)
+                .specular(0.0)
+                .build(),
+        )
+        .transformation(Matrix::new_translation(0.0, -1.0, 0.0))
+        .build()
+        .into();
+    w.objects = vec![floor];
+
+    // reflected straight back up into the empty sky
+    let r = Ray::new(Point::new(0.0, 1.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+    assert_eq!(w.color_at(&r, 5), Color::new(0.0, 0.0, 1.0));
+}
+
+#[test]
+fn ch7_test26_escaping_refraction_sees_background() {
+    let mut w = build_test_world();
+    w.background = Background::Solid(Color::new(0.0, 1.0, 0.0));
+    let pane: Object = Plane::new()
+        .material(
+            Material::new()
+                .transparency(1.0)
+                .diffuse(0.0)
+                .ambient(0.0)
+                .specular(0.0)
+                .build(),
+        )
+        .build()
+        .into();
+    w.objects = vec![pane];
+
+    let r = Ray::new(Point::new(0.0, 1.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+    assert_eq!(w.color_at(&r, 5), Color::new(0.0, 1.0, 0.0));
+}
@@ -0,0 +1,160 @@
+use crate::domain::bump::{HeightField, NormalPerturbation};
+use crate::domain::color::Color;
+use crate::domain::intersection::{Computations, Intersection};
+use crate::domain::material::Material;
+use crate::domain::matrix::IDENTITY;
+use crate::domain::noise::Noise;
+use crate::domain::object::{Object, Plane, Sphere};
+use crate::domain::pattern::{Pattern, Solid};
+use crate::domain::ray::Ray;
+use crate::domain::uv::UvMapping;
+use crate::domain::{Point, Vector};
+
+fn assert_vector(actual: &Vector, expected: &Vector) {
+    assert!(
+        (actual.x() - expected.x()).abs() < 0.001
+            && (actual.y() - expected.y()).abs() < 0.001
+            && (actual.z() - expected.z()).abs() < 0.001,
+        "{:?} != {:?}",
+        actual,
+        expected
+    );
+}
+
+fn plane_with(perturbation: NormalPerturbation) -> Object {
+    Plane::new()
+        .material(Material::new().normal_perturbation(perturbation).build())
+        .build()
+        .into()
+}
+
+// normal at (0.5, 0, 0.5) on the plane, seen from above
+fn plane_normal(plane: &Object) -> Vector {
+    let r = Ray::new(Point::new(0.5, 1.0, 0.5), Vector::new(0.0, -1.0, 0.0));
+    let i = Intersection::new(1.0, plane);
+    Computations::prepare_computations(&i, &r, Option::None).normal_v
+}
+
+fn solid_uv(color: Color, mapping: UvMapping) -> Pattern {
+    Pattern::new_uv(Solid { color }, mapping, IDENTITY.clone())
+}
+
+#[test]
+fn ch6_test25_default_material_keeps_geometric_normal() {
+    assert_eq!(Material::default().normal_perturbation, Option::None);
+    let plane: Object = Plane::new().build().into();
+    assert_eq!(plane_normal(&plane), Vector::new(0.0, 1.0, 0.0));
+}
+
+#[test]
+fn ch6_test26_flat_height_field_keeps_normal() {
+    let plane = plane_with(NormalPerturbation::new_pattern_bump(
+        Pattern::new_solid(Color::new(0.3, 0.3, 0.3)),
+        5.0,
+    ));
+    assert_vector(&plane_normal(&plane), &Vector::new(0.0, 1.0, 0.0));
+}
+
+#[test]
+fn ch6_test27_pattern_bump_tilts_normal_down_the_slope() {
+    // luminance rises by one per unit along x
+    let ramp = Pattern::new_gradient(Color::BLACK, Color::WHITE, IDENTITY.clone());
+    let plane = plane_with(NormalPerturbation::new_pattern_bump(ramp.clone(), 1.0));
+    let r = 2_f64.sqrt() / 2.0;
+    assert_vector(&plane_normal(&plane), &Vector::new(-r, r, 0.0));
+
+    // strength scales the tilt
+    let plane = plane_with(NormalPerturbation::new_pattern_bump(ramp, 0.5));
+    assert_vector(
+        &plane_normal(&plane),
+        &Vector::new(-0.5, 1.0, 0.0).normalize(),
+    );
+}
+
+#[test]
+fn ch6_test28_noise_bump_perturbs_normal() {
+    let noise = Noise::new().seed(3).build();
+    let plane = plane_with(NormalPerturbation::new_noise_bump(noise.clone(), 4.0, 0.2));
+    let normal = plane_normal(&plane);
+    assert!((normal.magnitude() - 1.0).abs() < 0.0001);
+    assert!(normal.y() < 1.0 - 0.0001);
+    assert!(normal.y() > 0.0);
+    assert_eq!(normal, plane_normal(&plane));
+
+    let flat = plane_with(NormalPerturbation::Bump {
+        height: HeightField::Noise {
+            noise,
+            frequency: 4.0,
+        },
+        strength: 0.0,
+    });
+    assert_vector(&plane_normal(&flat), &Vector::new(0.0, 1.0, 0.0));
+}
+
+#[test]
+fn ch6_test29_neutral_normal_map_keeps_normal() {
+    let plane = plane_with(NormalPerturbation::new_normal_map(
+        solid_uv(Color::new(0.5, 0.5, 1.0), UvMapping::Planar),
+        1.0,
+    ));
+    assert_vector(&plane_normal(&plane), &Vector::new(0.0, 1.0, 0.0));
+}
+
+#[test]
+fn ch6_test30_normal_map_follows_tangent_frame() {
+    let r = 2_f64.sqrt() / 2.0;
+
+    // planar mapping: u runs along x, v along z
+    let plane = plane_with(NormalPerturbation::new_normal_map(
+        solid_uv(Color::new(0.75, 0.5, 0.75), UvMapping::Planar),
+        1.0,
+    ));
+    assert_vector(&plane_normal(&plane), &Vector::new(r, r, 0.0));
+
+    let plane = plane_with(NormalPerturbation::new_normal_map(
+        solid_uv(Color::new(0.5, 0.75, 0.75), UvMapping::Planar),
+        1.0,
+    ));
+    assert_vector(&plane_normal(&plane), &Vector::new(0.0, r, r));
+
+    // zero strength ignores the map's tilt
+    let plane = plane_with(NormalPerturbation::new_normal_map(
+        solid_uv(Color::new(0.5, 0.75, 0.75), UvMapping::Planar),
+        0.0,
+    ));
+    assert_vector(&plane_normal(&plane), &Vector::new(0.0, 1.0, 0.0));
+}
+
+#[test]
+fn ch6_test31_normal_map_on_sphere() {
+    // on the front of the sphere, u increases towards +x and v towards +y
+    let sphere: Object = Sphere::new()
+        .material(
+            Material::new()
+                .normal_perturbation(NormalPerturbation::new_normal_map(
+                    solid_uv(Color::new(0.75, 0.5, 0.75), UvMapping::Native),
+                    1.0,
+                ))
+                .build(),
+        )
+        .build()
+        .into();
+    let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+    let i = Intersection::new(4.0, &sphere);
+    let comps = Computations::prepare_computations(&i, &r, Option::None);
+    let h = 2_f64.sqrt() / 2.0;
+    assert_vector(&comps.normal_v, &Vector::new(h, 0.0, -h));
+}
+
+#[test]
+fn ch6_test32_perturbed_normal_drives_reflection() {
+    let ramp = Pattern::new_gradient(Color::BLACK, Color::WHITE, IDENTITY.clone());
+    let plane = plane_with(NormalPerturbation::new_pattern_bump(ramp, 1.0));
+    let r = Ray::new(Point::new(0.5, 1.0, 0.5), Vector::new(0.0, -1.0, 0.0));
+    let i = Intersection::new(1.0, &plane);
+    let comps = Computations::prepare_computations(&i, &r, Option::None);
+
+    // bounced off a 45 degree slope
+    assert_vector(&comps.reflect_v, &Vector::new(-1.0, 0.0, 0.0));
+    assert!(!comps.inside);
+}
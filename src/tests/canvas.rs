@@ -67,6 +67,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn ch2_test_pit_2_pixel_at_takes_column_then_row() {
+        let black = Color::new(0.0, 0.0, 0.0);
+        let red = Color::new(1.0, 0.0, 0.0);
+        let mut c = Canvas::new(4, 2, black);
+
+        c.render(3, 1, red);
+        assert_eq!(c[1][3], red);
+        assert_eq!(c.pixel_at(3, 1), red);
+        assert_eq!(c.pixel_at(1, 0), black);
+    }
+
     #[test]
     fn ch2_test6_construct_ppm_header() {
         let c = Canvas::new(5, 3, Color::default());
@@ -1,4 +1,4 @@
-use crate::domain::object::Cube;
+use crate::domain::object::{Cube, Geometry};
 use crate::domain::ray::Ray;
 use crate::domain::{Point, Vector};
 
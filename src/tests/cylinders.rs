@@ -1,4 +1,4 @@
-use crate::domain::object::{Cone, Cylinder};
+use crate::domain::object::{Cone, Cylinder, Geometry};
 use crate::domain::ray::Ray;
 use crate::domain::{Point, Vector};
 
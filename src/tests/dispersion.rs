@@ -0,0 +1,99 @@
+use crate::domain::background::Background;
+use crate::domain::color::Color;
+use crate::domain::material::{Dispersion, Material, Substance, CHANNEL_WAVELENGTHS};
+use crate::domain::object::Sphere;
+use crate::domain::ray::Ray;
+use crate::domain::world::World;
+use crate::domain::{Point, Vector};
+
+fn assert_index(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 0.001,
+        "{} != {}",
+        actual,
+        expected
+    );
+}
+
+fn clear(substance: Substance) -> Material {
+    Material::new()
+        .ambient(0.0)
+        .diffuse(0.0)
+        .specular(0.0)
+        .transparency(1.0)
+        .substance(substance)
+        .build()
+}
+
+// a ball seen off-center against a grey sky, so any color has to come from dispersion
+fn render_ball(material: Material) -> Color {
+    let mut w = World::new();
+    w.add_object(Sphere::new().material(material).build().into());
+    w.background = Background::new_sky(Color::BLACK, Color::new(0.5, 0.5, 0.5), Color::WHITE);
+    let r = Ray::new(Point::new(0.0, 0.3, -5.0), Vector::new(0.0, 0.0, 1.0));
+    w.color_at(&r, 5)
+}
+
+#[test]
+fn ch11_test35_default_material_does_not_disperse() {
+    assert_eq!(Material::default().dispersion, Option::None);
+    assert_eq!(Substance::VACUUM.dispersion(), Option::None);
+    assert_eq!(Substance::AIR.dispersion(), Option::None);
+}
+
+#[test]
+fn ch11_test36_cauchy_index() {
+    let dispersion = Dispersion::new_cauchy(1.5, 0.01);
+    assert_index(dispersion.index_at(0.5), 1.54);
+    assert_index(dispersion.index_at(1.0), 1.51);
+}
+
+#[test]
+fn ch11_test37_preset_indices_match_substances() {
+    // at the sodium d line
+    assert_index(Dispersion::new_glass().index_at(0.5893), 1.5168);
+    assert_index(Dispersion::new_diamond().index_at(0.5893), 2.417);
+    assert_index(Dispersion::new_water().index_at(0.5893), 1.333);
+
+    let substances = [Substance::WATER, Substance::GLASS, Substance::DIAMOND];
+    for substance in substances.iter() {
+        let dispersion = substance.dispersion().unwrap();
+        assert!((dispersion.index_at(0.5893) - substance.refractive_index()).abs() < 0.005);
+        // blue light bends more than red
+        assert!(
+            dispersion.index_at(CHANNEL_WAVELENGTHS[2])
+                > dispersion.index_at(CHANNEL_WAVELENGTHS[0])
+        );
+    }
+}
+
+#[test]
+fn ch11_test38_dispersion_splits_white_light() {
+    let plain = render_ball(clear(Substance::DIAMOND));
+    assert_eq!(plain.red, plain.green);
+    assert_eq!(plain.green, plain.blue);
+
+    let mut dispersive = clear(Substance::DIAMOND);
+    dispersive.dispersion = Substance::DIAMOND.dispersion();
+    let fringed = render_ball(dispersive);
+    // red bends least and sees the brightest part of the sky
+    assert!(fringed.red > fringed.green && fringed.green > fringed.blue);
+    assert!(fringed.red - fringed.blue > 0.001);
+}
+
+#[test]
+fn ch11_test39_each_channel_follows_its_wavelength() {
+    let dispersion = Dispersion::new_glass();
+    let mut dispersive = clear(Substance::GLASS);
+    dispersive.dispersion = Option::Some(dispersion);
+    let fringed = render_ball(dispersive);
+
+    let channel = |wavelength: f64| {
+        let mut material = clear(Substance::GLASS);
+        material.refractive_index_override = Option::Some(dispersion.index_at(wavelength));
+        render_ball(material)
+    };
+    assert!((fringed.red - channel(CHANNEL_WAVELENGTHS[0]).red).abs() < 0.0001);
+    assert!((fringed.green - channel(CHANNEL_WAVELENGTHS[1]).green).abs() < 0.0001);
+    assert!((fringed.blue - channel(CHANNEL_WAVELENGTHS[2]).blue).abs() < 0.0001);
+}
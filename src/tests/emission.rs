@@ -0,0 +1,95 @@
+use crate::domain::color::Color;
+use crate::domain::light::Light;
+use crate::domain::material::Material;
+use crate::domain::matrix::Matrix;
+use crate::domain::object::{Object, Plane, Sphere};
+use crate::domain::ray::Ray;
+use crate::domain::world::World;
+use crate::domain::{Point, Vector};
+
+fn glowing(color: Color, strength: f64) -> Material {
+    Material::new()
+        .color(Color::BLACK)
+        .ambient(0.0)
+        .diffuse(0.0)
+        .specular(0.0)
+        .emission(color)
+        .emission_strength(strength)
+        .build()
+}
+
+#[test]
+fn ch6_test42_default_material_emits_nothing() {
+    let m = Material::default();
+    assert_eq!(m.emission, Color::BLACK);
+    assert_eq!(m.emission_strength, Material::DEFAULT_EMISSION_STRENGTH);
+    assert_eq!(m.emitted(), Color::BLACK);
+}
+
+#[test]
+fn ch6_test43_emission_is_scaled_by_strength() {
+    let m = glowing(Color::new(1.0, 0.5, 0.25), 2.0);
+    assert_eq!(m.emitted(), Color::new(2.0, 1.0, 0.5));
+}
+
+#[test]
+fn ch6_test44_emissive_surface_glows_without_lights() {
+    let mut w = World::new();
+    w.add_object(
+        Sphere::new()
+            .material(glowing(Color::new(1.0, 0.2, 0.6), 1.0))
+            .build()
+            .into(),
+    );
+    let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+    assert_eq!(w.color_at(&r, 5), Color::new(1.0, 0.2, 0.6));
+}
+
+#[test]
+fn ch6_test45_emissive_surface_glows_in_shadow() {
+    let mut w = World::new();
+    w.light_source = Option::Some(Light::new(Point::new(0.0, 0.0, -10.0), Color::WHITE));
+    // a plain sphere between the light and the glowing one
+    w.add_object(
+        Sphere::new()
+            .transformation(Matrix::new_translation(0.0, 0.0, -5.0))
+            .build()
+            .into(),
+    );
+    w.add_object(
+        Sphere::new()
+            .transformation(Matrix::new_translation(0.0, 0.0, 5.0))
+            .material(glowing(Color::new(0.0, 0.8, 0.0), 1.0))
+            .build()
+            .into(),
+    );
+    let r = Ray::new(Point::new(0.0, 0.0, 0.0), Vector::new(0.0, 0.0, 1.0));
+    assert_eq!(w.color_at(&r, 5), Color::new(0.0, 0.8, 0.0));
+}
+
+#[test]
+fn ch6_test46_emissive_surface_shows_in_reflections() {
+    let mut w = World::new();
+    let mirror: Object = Plane::new()
+        .material(
+            Material::new()
+                .color(Color::BLACK)
+                .ambient(0.0)
+                .diffuse(0.0)
+                .specular(0.0)
+                .reflective(1.0)
+                .build(),
+        )
+        .transformation(Matrix::new_translation(0.0, -1.0, 0.0))
+        .build()
+        .into();
+    let panel: Object = Plane::new()
+        .material(glowing(Color::new(0.9, 0.9, 0.3), 1.0))
+        .transformation(Matrix::new_translation(0.0, 1.0, 0.0))
+        .build()
+        .into();
+    w.add_object(mirror);
+    w.add_object(panel);
+    let r = Ray::new(Point::new(0.0, 0.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+    assert_eq!(w.color_at(&r, 5), Color::new(0.9, 0.9, 0.3));
+}
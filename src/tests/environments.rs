@@ -0,0 +1,227 @@
+use crate::domain::canvas::Canvas;
+use crate::domain::color::Color;
+use crate::domain::environment::{direction_for, luminance, EnvironmentLight};
+use crate::domain::intersection::{Computations, Intersection};
+use crate::domain::light::Light;
+use crate::domain::matrix::Matrix;
+use crate::domain::object::{Object, Plane, Sphere};
+use crate::domain::random::Rng;
+use crate::domain::ray::Ray;
+use crate::domain::uv::equirectangular_map;
+use crate::domain::world::World;
+use crate::domain::{epsilon_eq, Point, Vector};
+use crate::tests::world::build_test_world;
+use crate::utils::image_reader::ImageReader;
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+    assert!(
+        (actual - expected).abs() < tolerance,
+        "{} != {}",
+        actual,
+        expected
+    );
+}
+
+// shades the point of a unit sphere facing the ray
+fn shade_sphere(world: &World) -> Color {
+    let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+    let i = Intersection::new(4.0, &world.objects[0]);
+    let comps = Computations::prepare_computations(&i, &r, Option::None);
+    world.shade_hit(&comps, 5)
+}
+
+#[test]
+fn ch6_test14_luminance_weights() {
+    assert!(epsilon_eq(luminance(&Color::WHITE), 1.0));
+    assert!(epsilon_eq(luminance(&Color::new(0.0, 1.0, 0.0)), 0.7152));
+    assert!(epsilon_eq(luminance(&Color::new(-1.0, 0.0, 0.0)), 0.0));
+}
+
+#[test]
+fn ch6_test15_direction_for_inverts_equirectangular_map() {
+    let cases = [(0.5, 0.5), (0.1, 0.9), (0.75, 0.2), (0.3, 0.6)];
+    for (u, v) in cases.iter() {
+        let direction = direction_for(*u, *v);
+        assert!(epsilon_eq(direction.magnitude(), 1.0));
+        let (actual_u, actual_v) = equirectangular_map(&direction);
+        assert!(epsilon_eq(actual_u, *u));
+        assert!(epsilon_eq(actual_v, *v));
+    }
+    assert_eq!(direction_for(0.5, 1.0), Vector::new(0.0, 1.0, 0.0));
+}
+
+#[test]
+#[should_panic(expected = "non-empty map")]
+fn ch6_test16_environment_light_refuses_empty_map() {
+    EnvironmentLight::new(Arc::new(Canvas::new(0, 4, Color::WHITE)));
+}
+
+#[test]
+fn ch6_test17_uniform_environment_has_uniform_pdf() {
+    let environment = EnvironmentLight::new(Arc::new(Canvas::new(64, 32, Color::WHITE))).build();
+    let directions = [
+        Vector::new(0.0, 0.0, 1.0),
+        Vector::new(1.0, 1.0, 0.0).normalize(),
+        Vector::new(-0.3, -0.8, 0.2).normalize(),
+    ];
+    for direction in directions.iter() {
+        assert_close(environment.pdf(direction), 1.0 / (4.0 * PI), 0.001);
+    }
+}
+
+#[test]
+fn ch6_test18_environment_samples_favour_bright_pixels() {
+    let mut canvas = Canvas::new(8, 4, Color::BLACK);
+    canvas[1][6] = Color::new(10.0, 10.0, 10.0);
+    let environment = EnvironmentLight::new(Arc::new(canvas)).build();
+
+    let mut rng = Rng::new(7);
+    for _ in 0..100 {
+        let (direction, pdf) = environment.sample(&mut rng).unwrap();
+        assert!(pdf > 0.0);
+        assert_eq!(
+            environment.radiance(&direction),
+            Color::new(10.0, 10.0, 10.0)
+        );
+    }
+
+    // black maps can't be sampled
+    let dark = EnvironmentLight::new(Arc::new(Canvas::new(8, 4, Color::BLACK))).build();
+    assert_eq!(dark.sample(&mut rng), Option::None);
+    assert!(epsilon_eq(dark.pdf(&Vector::new(0.0, 1.0, 0.0)), 0.0));
+}
+
+#[test]
+fn ch6_test19_uniform_environment_lights_diffuse_surface() {
+    let mut w = World::new();
+    w.add_object(Sphere::new().build().into());
+    w.environment_light = Option::Some(
+        EnvironmentLight::new(Arc::new(Canvas::new(32, 16, Color::WHITE)))
+            .samples(4000)
+            .build(),
+    );
+
+    // irradiance from a uniform sky is pi, leaving just the diffuse albedo
+    let color = shade_sphere(&w);
+    assert_close(color.red as f64, 0.9, 0.05);
+    assert_close(color.green as f64, 0.9, 0.05);
+    assert_close(color.blue as f64, 0.9, 0.05);
+
+    // intensity scales the result linearly
+    w.environment_light = Option::Some(
+        EnvironmentLight::new(Arc::new(Canvas::new(32, 16, Color::WHITE)))
+            .samples(4000)
+            .intensity(0.5)
+            .build(),
+    );
+    assert_close(shade_sphere(&w).red as f64, 0.45, 0.03);
+}
+
+#[test]
+fn ch6_test20_environment_lighting_is_repeatable() {
+    let mut w = World::new();
+    w.add_object(Sphere::new().build().into());
+    w.environment_light = Option::Some(
+        EnvironmentLight::new(Arc::new(Canvas::new(16, 8, Color::new(0.2, 0.5, 1.0))))
+            .samples(16)
+            .build(),
+    );
+    assert_eq!(shade_sphere(&w), shade_sphere(&w));
+}
+
+#[test]
+fn ch6_test21_occluded_environment_does_not_light() {
+    // only the upper hemisphere is bright, and a ceiling blocks it
+    let mut canvas = Canvas::new(16, 8, Color::BLACK);
+    for row in 0..4 {
+        for column in 0..16 {
+            canvas[row][column] = Color::WHITE;
+        }
+    }
+
+    let mut w = World::new();
+    let floor: Object = Plane::new().build().into();
+    let ceiling: Object = Plane::new()
+        .transformation(Matrix::new_translation(0.0, 1.0, 0.0))
+        .build()
+        .into();
+    w.add_object(floor.clone());
+    w.environment_light = Option::Some(EnvironmentLight::new(Arc::new(canvas)).build());
+
+    let r = Ray::new(
+        Point::new(0.0, 0.5, -0.5),
+        Vector::new(0.0, -1.0, 1.0).normalize(),
+    );
+    let i = Intersection::new(2_f64.sqrt() / 2.0, &floor);
+    let comps = Computations::prepare_computations(&i, &r, Option::None);
+    assert!(w.shade_hit(&comps, 5).red > 0.5);
+
+    w.add_object(ceiling);
+    assert_eq!(w.shade_hit(&comps, 5), Color::BLACK);
+}
+
+#[test]
+fn ch6_test22_environment_adds_to_point_light() {
+    let mut w = build_test_world();
+    let without = shade_sphere(&w);
+    w.environment_light = Option::Some(
+        EnvironmentLight::new(Arc::new(Canvas::new(16, 8, Color::WHITE)))
+            .samples(32)
+            .build(),
+    );
+    let with = shade_sphere(&w);
+    assert!(with.red > without.red);
+    assert!(with.green > without.green);
+
+    w.light_source = Option::None::<Light>;
+    assert!(shade_sphere(&w).green > 0.0);
+}
+
+#[test]
+fn ch6_test23_reading_flat_hdr() {
+    let mut hdr = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 1 +X 2\n".to_vec();
+    // 128 * 2^(129 - 136) = 1.0, 128 * 2^(131 - 136) = 4.0
+    hdr.extend_from_slice(&[128, 64, 0, 129, 128, 128, 128, 131]);
+    let canvas = ImageReader::new(&hdr).to_canvas().unwrap();
+    assert_eq!(canvas.width, 2);
+    assert_eq!(canvas.height, 1);
+    assert_eq!(canvas.pixel_at(0, 0), Color::new(1.0, 0.5, 0.0));
+    assert_eq!(canvas.pixel_at(1, 0), Color::new(4.0, 4.0, 4.0));
+}
+
+#[test]
+fn ch6_test24_reading_run_length_encoded_hdr() {
+    let mut hdr = b"#?RADIANCE\n# made by hand\n\n-Y 2 +X 8\n".to_vec();
+    for row in 0..2 {
+        hdr.extend_from_slice(&[2, 2, 0, 8]);
+        // red: a run of 8; green: 8 literals; blue: two runs; exponent: a run
+        hdr.extend_from_slice(&[128 + 8, 128]);
+        hdr.extend_from_slice(&[8, 0, 16, 32, 48, 64, 80, 96, 112]);
+        hdr.extend_from_slice(&[128 + 4, 0, 128 + 4, 64]);
+        hdr.extend_from_slice(&[128 + 8, 129 + row]);
+    }
+
+    let canvas = ImageReader::new(&hdr).to_canvas().unwrap();
+    assert_eq!(canvas.pixel_at(0, 0), Color::new(1.0, 0.0, 0.0));
+    assert_eq!(canvas.pixel_at(7, 0), Color::new(1.0, 0.875, 0.5));
+    assert_eq!(canvas.pixel_at(7, 1), Color::new(2.0, 1.75, 1.0));
+
+    // truncated data and unsupported orientations are rejected
+    assert!(ImageReader::new(&hdr[..hdr.len() - 1]).to_canvas().is_err());
+    assert!(ImageReader::new(b"#?RADIANCE\n\n+Y 1 +X 1\n\0\0\0\0")
+        .to_canvas()
+        .is_err());
+
+    // as are empty and oversized images
+    assert!(ImageReader::new(b"#?RADIANCE\n\n-Y 1 +X 0\n")
+        .to_canvas()
+        .is_err());
+    assert!(ImageReader::new(b"#?RADIANCE\n\n-Y 0 +X 1\n")
+        .to_canvas()
+        .is_err());
+    assert!(ImageReader::new(b"#?RADIANCE\n\n-Y 100000 +X 100000\n")
+        .to_canvas()
+        .is_err());
+}
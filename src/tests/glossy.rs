@@ -0,0 +1,126 @@
+use crate::domain::background::Background;
+use crate::domain::color::Color;
+use crate::domain::material::{Material, ShadingModel};
+use crate::domain::matrix::Matrix;
+use crate::domain::object::{Object, Plane};
+use crate::domain::random::{sample_cone, Rng};
+use crate::domain::ray::Ray;
+use crate::domain::world::World;
+use crate::domain::{Point, Vector};
+use std::f64::consts::PI;
+
+// a plane below the eye that only reflects or transmits
+fn surface_world(material: Material, background: Background) -> World {
+    let floor: Object = Plane::new()
+        .material(material)
+        .transformation(Matrix::new_translation(0.0, -1.0, 0.0))
+        .build()
+        .into();
+    let mut w = World::new();
+    w.add_object(floor);
+    w.background = background;
+    w
+}
+
+fn mirror(roughness: f64) -> Material {
+    Material::new()
+        .ambient(0.0)
+        .diffuse(0.0)
+        .specular(0.0)
+        .reflective(1.0)
+        .roughness(roughness)
+        .build()
+}
+
+fn frosted(roughness: f64) -> Material {
+    Material::new()
+        .ambient(0.0)
+        .diffuse(0.0)
+        .specular(0.0)
+        .transparency(1.0)
+        .roughness(roughness)
+        .build()
+}
+
+fn look_down(w: &World) -> Color {
+    let r = Ray::new(Point::new(0.0, 0.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+    w.color_at(&r, 5)
+}
+
+#[test]
+fn ch11_test23_default_material_is_smooth() {
+    let m = Material::default();
+    assert_eq!(m.roughness, 0.0);
+    assert_eq!(m.glossy_samples, Material::DEFAULT_GLOSSY_SAMPLES);
+}
+
+#[test]
+fn ch11_test24_cone_samples_stay_within_half_angle() {
+    let mut rng = Rng::new(11);
+    let axis = Vector::new(1.0, 2.0, -0.5).normalize();
+    for _ in 0..200 {
+        let sample = sample_cone(&mut rng, &axis, PI / 8.0);
+        assert!((sample.magnitude() - 1.0).abs() < 0.0001);
+        assert!(sample.dot_product(&axis) >= (PI / 8.0).cos() - 0.0001);
+    }
+    assert_eq!(sample_cone(&mut rng, &axis, 0.0), axis);
+}
+
+#[test]
+fn ch11_test25_rough_reflection_blurs_the_mirror_image() {
+    // bright zenith fading to a black horizon
+    let sky = Background::new_sky(Color::BLACK, Color::BLACK, Color::WHITE);
+
+    let smooth = look_down(&surface_world(mirror(0.0), sky.clone()));
+    assert_eq!(smooth, Color::WHITE);
+
+    let glossy = look_down(&surface_world(mirror(0.5), sky.clone()));
+    assert!(glossy.red < 0.99 && glossy.red > 0.5);
+    assert_eq!(glossy, look_down(&surface_world(mirror(0.5), sky)));
+}
+
+#[test]
+fn ch11_test26_rough_reflection_stays_above_the_surface() {
+    // anything below the horizon would show up red
+    let sky = Background::new_sky(
+        Color::new(1.0, 0.0, 0.0),
+        Color::new(0.0, 0.0, 1.0),
+        Color::new(0.0, 0.0, 1.0),
+    );
+    let color = look_down(&surface_world(mirror(1.0), sky));
+    assert_eq!(color, Color::new(0.0, 0.0, 1.0));
+}
+
+#[test]
+fn ch11_test27_rough_refraction_frosts_the_view_through() {
+    // bright nadir fading to a black horizon
+    let sky = Background::new_sky(Color::WHITE, Color::BLACK, Color::BLACK);
+
+    let clear = look_down(&surface_world(frosted(0.0), sky.clone()));
+    assert_eq!(clear, Color::WHITE);
+
+    let frosted_color = look_down(&surface_world(frosted(0.5), sky));
+    assert!(frosted_color.red < 0.99 && frosted_color.red > 0.5);
+}
+
+#[test]
+fn ch11_test28_rough_refraction_stays_below_the_surface() {
+    let sky = Background::new_sky(
+        Color::new(1.0, 0.0, 0.0),
+        Color::new(1.0, 0.0, 0.0),
+        Color::new(0.0, 0.0, 1.0),
+    );
+    let color = look_down(&surface_world(frosted(1.0), sky));
+    assert_eq!(color, Color::new(1.0, 0.0, 0.0));
+}
+
+#[test]
+fn ch11_test29_microfacet_roughness_also_blurs_reflections() {
+    let sky = Background::new_sky(Color::BLACK, Color::BLACK, Color::WHITE);
+    let mut metal = mirror(0.5);
+    metal.shading_model = ShadingModel::Microfacet { metallic: 1.0 };
+
+    let glossy = look_down(&surface_world(metal, sky.clone()));
+    assert_eq!(glossy, look_down(&surface_world(mirror(0.5), sky)));
+    assert!(glossy.red < 0.99);
+}
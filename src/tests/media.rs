@@ -0,0 +1,222 @@
+use crate::domain::background::Background;
+use crate::domain::color::Color;
+use crate::domain::light::Light;
+use crate::domain::material::Material;
+use crate::domain::matrix::Matrix;
+use crate::domain::medium::{Density, Medium};
+use crate::domain::noise::Noise;
+use crate::domain::object::{Cube, Object, Sphere};
+use crate::domain::ray::Ray;
+use crate::domain::world::World;
+use crate::domain::{Point, Vector};
+
+fn lit_world() -> World {
+    let mut w = World::new();
+    w.background = Background::Solid(Color::new(0.2, 0.4, 0.6));
+    w.light_source = Option::Some(Light::new(Point::new(0.0, 10.0, 0.0), Color::WHITE));
+    w
+}
+
+fn fog(density: f64) -> Medium {
+    Medium::new(Density::Uniform(density))
+        .max_distance(20.0)
+        .build()
+}
+
+// a clear ball holding the given medium
+fn volume(medium: Medium, transformation: Matrix) -> Object {
+    Sphere::new()
+        .material(
+            Material::new()
+                .ambient(0.0)
+                .diffuse(0.0)
+                .specular(0.0)
+                .transparency(1.0)
+                .medium(medium)
+                .build(),
+        )
+        .transformation(transformation)
+        .build()
+        .into()
+}
+
+fn looking_along_z(x: f64) -> Ray {
+    Ray::new(Point::new(x, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0))
+}
+
+#[test]
+fn ch8_test24_there_is_no_fog_by_default() {
+    assert!(World::new().fog.is_none());
+    assert!(Material::default().medium.is_none());
+
+    let m = Medium::new(Density::Uniform(0.1)).build();
+    assert_eq!(m.color, Color::WHITE);
+    assert_eq!(m.anisotropy, Medium::DEFAULT_ANISOTROPY);
+    assert_eq!(m.steps, Medium::DEFAULT_STEPS);
+    assert_eq!(m.max_distance, Medium::DEFAULT_MAX_DISTANCE);
+}
+
+#[test]
+fn ch8_test25_phase_function_averages_to_one() {
+    for g in [0.0, 0.5, -0.3].iter() {
+        let m = Medium::new(Density::Uniform(0.1)).anisotropy(*g).build();
+        // integrated over the sphere by cos theta
+        let steps = 10_000;
+        let mean: f64 = (0..steps)
+            .map(|i| m.phase(-1.0 + (i as f64 + 0.5) * 2.0 / steps as f64))
+            .sum::<f64>()
+            / steps as f64;
+        assert!((mean - 1.0).abs() < 0.001, "{} {}", g, mean);
+    }
+
+    let isotropic = Medium::new(Density::Uniform(0.1)).build();
+    assert_eq!(isotropic.phase(0.3), 1.0);
+    let forward = Medium::new(Density::Uniform(0.1)).anisotropy(0.6).build();
+    assert!(forward.phase(1.0) > forward.phase(-1.0));
+}
+
+#[test]
+fn ch8_test26_uniform_media_dim_light_exponentially() {
+    let m = fog(0.5);
+    let r = looking_along_z(0.0);
+    assert_eq!(m.transmittance(&r, 1.0, 3.0), (-1.0_f64).exp());
+    assert_eq!(m.transmittance(&r, 3.0, 1.0), 1.0);
+}
+
+#[test]
+fn ch8_test27_noise_density_stays_in_range() {
+    let density = Density::Noise {
+        noise: Noise::default(),
+        density: 0.8,
+        frequency: 2.0,
+    };
+    let values: Vec<f64> = (0..200)
+        .map(|i| {
+            density.at(&Point::new(
+                i as f64 * 0.37,
+                i as f64 * 0.11,
+                -(i as f64) * 0.23,
+            ))
+        })
+        .collect();
+    assert!(values.iter().all(|d| (0.0..=0.8).contains(d)));
+    // and it does vary
+    let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = values.iter().cloned().fold(0.0, f64::max);
+    assert!(max - min > 0.1);
+
+    let m = Medium::new(density).build();
+    let t = m.transmittance(&looking_along_z(0.0), 0.0, 10.0);
+    assert!(t > (-8.0_f64).exp() && t < 1.0);
+}
+
+#[test]
+fn ch8_test28_fog_dims_the_background_and_scatters_light() {
+    let mut w = lit_world();
+    let r = looking_along_z(0.0);
+    let clear = w.color_at(&r, 5);
+    assert_eq!(clear, Color::new(0.2, 0.4, 0.6));
+
+    // without light the fog only hides what's behind it
+    w.light_source = Option::None;
+    w.fog = Option::Some(fog(0.1));
+    let dark = w.color_at(&r, 5);
+    let hidden = (-2.0_f64).exp() as f32;
+    assert_eq!(dark, Color::new(0.2 * hidden, 0.4 * hidden, 0.6 * hidden));
+
+    // lit, it glows
+    w.light_source = Option::Some(Light::new(Point::new(0.0, 10.0, 0.0), Color::WHITE));
+    let lit = w.color_at(&r, 5);
+    assert!(lit.red > dark.red && lit.green > dark.green && lit.blue > dark.blue);
+}
+
+#[test]
+fn ch8_test29_fog_attenuates_light_reaching_surfaces() {
+    let mut w = lit_world();
+    w.fog = Option::Some(fog(0.1));
+    let filter = w.shadow_filter(&Point::ORIGIN);
+    let through = (-1.0_f64).exp() as f32;
+    assert_eq!(filter, Color::new(through, through, through));
+}
+
+#[test]
+fn ch8_test30_shadowed_fog_scatters_less_light() {
+    // a wall blocking the light above the left half of the scene
+    let mut w = lit_world();
+    let wall: Object = Cube::new()
+        .transformation(
+            &Matrix::new_translation(-10.0, 5.0, 0.0) * &Matrix::new_scaling(9.0, 0.1, 20.0),
+        )
+        .build()
+        .into();
+    w.add_object(wall);
+    w.fog = Option::Some(fog(0.1));
+
+    let lit = w.color_at(&looking_along_z(5.0), 5);
+    let shadowed = w.color_at(&looking_along_z(-5.0), 5);
+    assert!(lit.red > shadowed.red, "{:?} {:?}", lit, shadowed);
+}
+
+#[test]
+fn ch8_test31_volumes_fill_objects_with_a_medium() {
+    let mut w = lit_world();
+    w.light_source = Option::None;
+    w.add_object(volume(fog(0.5), Matrix::new_translation(0.0, 0.0, 0.0)));
+
+    // the ball holds two units of medium along the middle
+    let through = w.color_at(&looking_along_z(0.0), 5);
+    let hidden = (-1.0_f64).exp() as f32;
+    assert_eq!(
+        through,
+        Color::new(0.2 * hidden, 0.4 * hidden, 0.6 * hidden)
+    );
+    // and none beside it
+    assert_eq!(
+        w.color_at(&looking_along_z(2.0), 5),
+        Color::new(0.2, 0.4, 0.6)
+    );
+
+    // lit, the medium glows
+    w.light_source = Option::Some(Light::new(Point::new(0.0, 10.0, 0.0), Color::WHITE));
+    let lit = w.color_at(&looking_along_z(0.0), 5);
+    assert!(lit.red > through.red);
+}
+
+#[test]
+fn ch8_test32_volumes_cast_soft_shadows() {
+    let mut w = lit_world();
+    w.add_object(volume(fog(0.5), Matrix::new_translation(0.0, 2.0, 0.0)));
+
+    let filter = w.shadow_filter(&Point::ORIGIN);
+    let through = (-1.0_f64).exp() as f32;
+    assert_eq!(filter, Color::new(through, through, through));
+}
+
+#[test]
+fn ch8_test33_opaque_surfaces_hide_their_medium() {
+    // binding a medium makes the surface clear unless told otherwise
+    let clear = Material::new().medium(fog(0.5)).build();
+    assert_eq!(clear.transparency, 1.0);
+
+    let mut w = lit_world();
+    let opaque: Object = Sphere::new()
+        .material(
+            Material::new()
+                .color(Color::new(1.0, 0.0, 0.0))
+                .ambient(1.0)
+                .diffuse(0.0)
+                .specular(0.0)
+                .transparency(0.0)
+                .medium(fog(0.5))
+                .build(),
+        )
+        .build()
+        .into();
+    w.add_object(opaque);
+
+    // no ray gets inside, only the surface shows
+    assert_eq!(
+        w.color_at(&looking_along_z(0.0), 5),
+        Color::new(1.0, 0.0, 0.0)
+    );
+}
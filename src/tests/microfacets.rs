@@ -0,0 +1,146 @@
+use crate::domain::color::Color;
+use crate::domain::light::Light;
+use crate::domain::material::{Material, ShadingModel};
+use crate::domain::microfacet::{
+    base_reflectance, ggx_distribution, schlick_fresnel, smith_geometry,
+};
+use crate::domain::object::{Object, Sphere};
+use crate::domain::{epsilon_eq, Point, Vector};
+use std::f64::consts::PI;
+
+fn microfacet_lighting(
+    material: Material,
+    eye_v: Vector,
+    light_position: Point,
+    in_shadow: bool,
+) -> Color {
+    let object: Object = Sphere::new().build().into();
+    let normal_v = Vector::new(0.0, 0.0, -1.0);
+    let light = Light::new(light_position, Color::WHITE);
+    Light::lighting(
+        &material,
+        &object,
+        &light,
+        &Point::ORIGIN,
+        &eye_v,
+        &normal_v,
+        in_shadow,
+    )
+}
+
+fn microfacet(color: Color, metallic: f64, roughness: f64) -> Material {
+    Material::new()
+        .color(color)
+        .ambient(0.0)
+        .shading_model(ShadingModel::Microfacet { metallic })
+        .roughness(roughness)
+        .build()
+}
+
+#[test]
+fn ch6_test33_default_shading_model_is_phong() {
+    assert_eq!(Material::default().shading_model, ShadingModel::Phong);
+}
+
+#[test]
+fn ch6_test34_ggx_distribution() {
+    // peak density at the normal is 1 / (pi * alpha^2)
+    assert!(epsilon_eq(ggx_distribution(1.0, 1.0), 1.0 / PI));
+    assert!(epsilon_eq(ggx_distribution(1.0, 0.5), 1.0 / (PI * 0.0625)));
+    // fully rough surfaces scatter uniformly
+    assert!(epsilon_eq(ggx_distribution(0.3, 1.0), 1.0 / PI));
+    // smooth surfaces concentrate around the normal
+    assert!(ggx_distribution(0.9, 0.2) < ggx_distribution(1.0, 0.2));
+    assert!(ggx_distribution(0.0, 0.0).is_finite());
+}
+
+#[test]
+fn ch6_test35_smith_geometry() {
+    assert!(epsilon_eq(smith_geometry(1.0, 1.0, 0.5), 1.0));
+    assert!(epsilon_eq(smith_geometry(0.0, 1.0, 0.5), 0.0));
+    // rough surfaces shadow more at grazing angles
+    assert!(smith_geometry(0.2, 0.2, 1.0) < smith_geometry(0.2, 0.2, 0.1));
+}
+
+#[test]
+fn ch6_test36_schlick_fresnel() {
+    let f0 = Color::new(0.04, 0.04, 0.04);
+    assert_eq!(schlick_fresnel(&f0, 1.0), f0);
+    assert_eq!(schlick_fresnel(&f0, 0.0), Color::WHITE);
+    assert_eq!(schlick_fresnel(&f0, 0.5), Color::new(0.07, 0.07, 0.07));
+
+    let gold = Color::new(1.0, 0.8, 0.3);
+    assert_eq!(base_reflectance(&gold, 0.0), Color::new(0.04, 0.04, 0.04));
+    assert_eq!(base_reflectance(&gold, 1.0), gold);
+}
+
+#[test]
+fn ch6_test37_microfacet_head_on_rough_dielectric() {
+    let result = microfacet_lighting(
+        microfacet(Color::WHITE, 0.0, 1.0),
+        Vector::new(0.0, 0.0, -1.0),
+        Point::new(0.0, 0.0, -10.0),
+        false,
+    );
+    // 0.96 diffuse after fresnel, plus 0.04 / 4 specular
+    assert_eq!(result, Color::new(0.97, 0.97, 0.97));
+}
+
+#[test]
+fn ch6_test38_microfacet_head_on_rough_metal() {
+    let result = microfacet_lighting(
+        microfacet(Color::new(1.0, 0.5, 0.2), 1.0, 1.0),
+        Vector::new(0.0, 0.0, -1.0),
+        Point::new(0.0, 0.0, -10.0),
+        false,
+    );
+    // no diffuse, tinted specular
+    assert_eq!(result, Color::new(0.25, 0.125, 0.05));
+}
+
+#[test]
+fn ch6_test39_microfacet_shadow_and_back_lighting_leave_ambient() {
+    let material = Material::new()
+        .color(Color::new(0.5, 0.5, 0.5))
+        .ambient(0.2)
+        .shading_model(ShadingModel::Microfacet { metallic: 0.0 })
+        .roughness(0.5)
+        .build();
+    let eye_v = Vector::new(0.0, 0.0, -1.0);
+
+    let shadowed = microfacet_lighting(material.clone(), eye_v, Point::new(0.0, 0.0, -10.0), true);
+    assert_eq!(shadowed, Color::new(0.1, 0.1, 0.1));
+
+    let behind = microfacet_lighting(material, eye_v, Point::new(0.0, 0.0, 10.0), false);
+    assert_eq!(behind, Color::new(0.1, 0.1, 0.1));
+}
+
+#[test]
+fn ch6_test40_microfacet_roughness_spreads_highlight() {
+    let r = 2_f64.sqrt() / 2.0;
+    let light = Point::new(0.0, 10.0, -10.0);
+
+    // eye in the mirror direction of the light
+    let mirror = Vector::new(0.0, -r, -r);
+    let smooth = microfacet_lighting(microfacet(Color::BLACK, 0.0, 0.1), mirror, light, false);
+    let rough = microfacet_lighting(microfacet(Color::BLACK, 0.0, 0.8), mirror, light, false);
+    assert!(smooth.red > rough.red);
+
+    // eye away from the mirror direction
+    let off = Vector::new(0.0, r, -r);
+    let smooth = microfacet_lighting(microfacet(Color::BLACK, 0.0, 0.1), off, light, false);
+    let rough = microfacet_lighting(microfacet(Color::BLACK, 0.0, 0.8), off, light, false);
+    assert!(smooth.red < rough.red);
+}
+
+#[test]
+fn ch6_test41_phong_materials_render_unchanged() {
+    let material = Material::new().shading_model(ShadingModel::Phong).build();
+    let result = microfacet_lighting(
+        material,
+        Vector::new(0.0, 0.0, -1.0),
+        Point::new(0.0, 0.0, -10.0),
+        false,
+    );
+    assert_eq!(result, Color::new(1.9, 1.9, 1.9));
+}
@@ -1,16 +1,38 @@
 #[cfg(test)]
+mod absorption;
+mod animation;
+mod backgrounds;
+mod bumps;
 mod camera;
 mod canvas;
 mod cubes;
 mod cylinders;
+mod dispersion;
+mod emission;
+mod environments;
+mod glossy;
 mod intersections;
 mod lights;
 mod materials;
 mod matrices;
+mod media;
+mod microfacets;
+mod motion;
+mod noise;
+mod occlusion;
+mod path_tracing;
 mod patterns;
+mod photons;
+mod ramps;
 mod rays;
+mod settings;
+mod shadows;
 mod shapes;
 mod spheres;
+mod subsurface;
+mod textures;
 mod transformations;
 mod tuples;
+mod uv;
+mod visibility;
 mod world;
@@ -0,0 +1,241 @@
+use crate::domain::bump::NormalPerturbation;
+use crate::domain::camera::Camera;
+use crate::domain::color::Color;
+use crate::domain::intersection::{Computations, Intersection};
+use crate::domain::light::Light;
+use crate::domain::material::Material;
+use crate::domain::matrix::{Matrix, IDENTITY};
+use crate::domain::object::{GeometryBuilder, Object, Plane, Sphere};
+use crate::domain::pattern::Pattern;
+use crate::domain::ray::Ray;
+use crate::domain::settings::RenderSettings;
+use crate::domain::world::World;
+use crate::domain::{Point, Vector};
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+// a unit sphere moving two units along x during the frame
+fn moving_sphere() -> Object {
+    Sphere::new()
+        .end_transformation(Matrix::new_translation(2.0, 0.0, 0.0))
+        .build()
+        .into()
+}
+
+fn along_z(x: f64, time: f64) -> Ray {
+    Ray::new(Point::new(x, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0)).with_time(time)
+}
+
+// camera looking down -z at the origin from z = 5 through a single narrow pixel
+fn single_pixel_camera() -> Camera {
+    let mut c = Camera::new(1, 1, PI / 64.0);
+    c.transform = Matrix::new_view_transformation(
+        &Point::new(0.0, 0.0, 5.0),
+        &Point::ORIGIN,
+        &Vector::new(0.0, 1.0, 0.0),
+    );
+    c
+}
+
+#[test]
+fn ch4_test13_nothing_moves_by_default() {
+    let r = Ray::new(Point::ORIGIN, Vector::new(0.0, 0.0, 1.0));
+    assert_eq!(r.time, 0.0);
+    assert_eq!(r.transform(&Matrix::new_scaling(2.0, 2.0, 2.0)).time, 0.0);
+    assert_eq!(
+        r.with_time(0.5)
+            .transform(&Matrix::new_translation(1.0, 0.0, 0.0))
+            .time,
+        0.5
+    );
+
+    let s: Object = Sphere::new().build().into();
+    assert!(s.shape().end_transformation.is_none());
+    assert_eq!(s.transformation_at(0.7), s.shape().transformation);
+
+    let c = Camera::new(10, 10, PI / 2.0);
+    assert_eq!((c.shutter_open, c.shutter_close), (0.0, 0.0));
+    assert_eq!(c.ray_for_pixel(5, 5).time, 0.0);
+}
+
+#[test]
+fn ch4_test14_translations_interpolate_linearly() {
+    let start = Matrix::new_translation(0.0, 0.0, 0.0);
+    let end = Matrix::new_translation(2.0, -4.0, 6.0);
+    assert_eq!(
+        start.interpolate(&end, 0.5),
+        Matrix::new_translation(1.0, -2.0, 3.0)
+    );
+    assert_eq!(start.interpolate(&end, 0.0), start);
+    assert_eq!(start.interpolate(&end, 1.0), end);
+}
+
+#[test]
+fn ch4_test15_rotations_interpolate_along_the_arc() {
+    let s: Object = Sphere::new()
+        .transformation(Matrix::new_scaling(2.0, 2.0, 2.0))
+        .end_transformation(
+            &Matrix::new_translation(4.0, 0.0, 0.0)
+                * &(&Matrix::new_rotation_y(PI / 2.0) * &Matrix::new_scaling(2.0, 2.0, 2.0)),
+        )
+        .build()
+        .into();
+
+    // halfway the object is turned by 45 degrees, neither sheared nor shrunk
+    let halfway = s.transformation_at(0.5);
+    assert_eq!(
+        halfway,
+        &Matrix::new_translation(2.0, 0.0, 0.0)
+            * &(&Matrix::new_rotation_y(PI / 4.0) * &Matrix::new_scaling(2.0, 2.0, 2.0))
+    );
+    // its axes stay orthogonal and keep their length
+    let axes: Vec<Vector> = [
+        Vector::new(1.0, 0.0, 0.0),
+        Vector::new(0.0, 1.0, 0.0),
+        Vector::new(0.0, 0.0, 1.0),
+    ]
+    .iter()
+    .map(|axis| &(&halfway * axis) * 0.5)
+    .collect();
+    for (i, a) in axes.iter().enumerate() {
+        assert!(crate::domain::epsilon_eq(a.magnitude(), 1.0));
+        for b in axes.iter().skip(i + 1) {
+            assert!(crate::domain::epsilon_eq(a.dot_product(b), 0.0));
+        }
+    }
+}
+
+#[test]
+fn ch4_test16_moving_objects_are_intersected_where_they_are_at_the_ray_time() {
+    let s = moving_sphere();
+    assert_eq!(
+        s.transformation_at(0.25),
+        Matrix::new_translation(0.5, 0.0, 0.0)
+    );
+    // the pose stays within the frame
+    assert_eq!(
+        s.transformation_at(3.0),
+        Matrix::new_translation(2.0, 0.0, 0.0)
+    );
+
+    assert!(s.intersect(&along_z(2.0, 0.0)).hit().is_none());
+    assert_eq!(s.intersect(&along_z(2.0, 1.0)).hit().unwrap().distance, 4.0);
+    assert_eq!(s.intersect(&along_z(1.0, 0.5)).hit().unwrap().distance, 4.0);
+    assert!(s.intersect(&along_z(0.0, 1.0)).hit().is_none());
+}
+
+#[test]
+fn ch4_test17_moving_objects_have_normals_for_their_pose() {
+    let s = moving_sphere();
+    assert_eq!(
+        s.normal_at_time(&Point::new(3.0, 0.0, 0.0), 1.0),
+        Vector::new(1.0, 0.0, 0.0)
+    );
+    assert_eq!(
+        s.normal_at(&Point::new(1.0, 0.0, 0.0)),
+        Vector::new(1.0, 0.0, 0.0)
+    );
+}
+
+#[test]
+fn ch4_test18_moving_objects_cast_shadows_where_they_are() {
+    let mut w = World::new();
+    w.light_source = Option::Some(Light::new(Point::new(0.0, 10.0, 0.0), Color::WHITE));
+    // a ball passing over the origin in the first half of the frame
+    let ball: Object = Sphere::new()
+        .transformation(Matrix::new_translation(-1.0, 5.0, 0.0))
+        .end_transformation(Matrix::new_translation(3.0, 5.0, 0.0))
+        .build()
+        .into();
+    w.add_object(ball);
+
+    assert!(w.shadow_filter_at(&Point::ORIGIN, 0.25) == Color::BLACK);
+    assert!(w.shadow_filter_at(&Point::ORIGIN, 1.0) == Color::WHITE);
+}
+
+#[test]
+fn ch4_test19_open_shutters_blur_moving_objects() {
+    let mut w = World::new();
+    w.light_source = Option::Some(Light::new(Point::new(0.0, 0.0, 10.0), Color::WHITE));
+    let ball: Object = Sphere::new()
+        .material(
+            Material::new()
+                .color(Color::WHITE)
+                .ambient(1.0)
+                .diffuse(0.0)
+                .specular(0.0)
+                .build(),
+        )
+        // the ball only covers the pixel for about the first eighth of the frame
+        .end_transformation(Matrix::new_translation(8.0, 0.0, 0.0))
+        .build()
+        .into();
+    w.add_object(ball);
+
+    let mut c = single_pixel_camera();
+    let settings = RenderSettings::new().samples(256).build();
+    let render = |c: &Camera| {
+        w.render(c, &settings, Arc::new(|_, _| {}))
+            .unwrap()
+            .pixel_at(0, 0)
+    };
+    assert_eq!(render(&c), Color::WHITE);
+
+    c.shutter_close = 1.0;
+    let blurred = render(&c);
+    assert!(blurred.red > 0.08 && blurred.red < 0.17, "{:?}", blurred);
+
+    // the shutter closing early keeps the ball sharp
+    c.shutter_close = 0.1;
+    assert_eq!(render(&c), Color::WHITE);
+}
+
+#[test]
+fn ch4_test20_shutter_time_spans_the_exposure() {
+    let mut c = Camera::new(10, 10, PI / 2.0);
+    c.shutter_open = 0.2;
+    c.shutter_close = 0.6;
+    assert_eq!(c.shutter_time(0.0), 0.2);
+    assert_eq!(c.shutter_time(0.5), 0.4);
+    assert_eq!(c.shutter_time(1.0), 0.6);
+    assert_eq!(c.ray_for_pixel(5, 5).time, 0.2);
+}
+
+#[test]
+fn ch4_test21_patterns_move_along_with_their_objects() {
+    let stripes = Pattern::new_striped(Color::WHITE, Color::BLACK, IDENTITY.clone());
+    let ball: Object = Sphere::new()
+        .material(Material::new().pattern(stripes.clone()).build())
+        .end_transformation(Matrix::new_translation(1.0, 0.0, 0.0))
+        .build()
+        .into();
+    let p = Point::new(1.5, 0.0, 0.0);
+    assert_eq!(stripes.color_at(&ball, &p), Color::BLACK);
+    assert_eq!(stripes.color_at_time(&ball, &p, 1.0), Color::WHITE);
+    let material = &ball.shape().material;
+    assert_eq!(material.color_at_time(&ball, &p, 1.0), Color::WHITE);
+}
+
+#[test]
+fn ch4_test22_bumps_turn_along_with_their_objects() {
+    // luminance rises along the plane's own x axis, which turns to -z during the frame
+    let ramp = Pattern::new_gradient(Color::BLACK, Color::WHITE, IDENTITY.clone());
+    let plane: Object = Plane::new()
+        .material(
+            Material::new()
+                .normal_perturbation(NormalPerturbation::new_pattern_bump(ramp, 1.0))
+                .build(),
+        )
+        .end_transformation(Matrix::new_rotation_y(PI / 2.0))
+        .build()
+        .into();
+    let normal_at = |time: f64| {
+        let r = Ray::new(Point::new(0.5, 1.0, 0.5), Vector::new(0.0, -1.0, 0.0)).with_time(time);
+        let i = Intersection::new(1.0, &plane);
+        Computations::prepare_computations(&i, &r, Option::None).normal_v
+    };
+
+    let r = 2_f64.sqrt() / 2.0;
+    assert_eq!(normal_at(0.0), Vector::new(-r, r, 0.0));
+    assert_eq!(normal_at(1.0), Vector::new(0.0, r, r));
+}
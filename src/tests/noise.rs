@@ -0,0 +1,122 @@
+use crate::domain::color::Color;
+use crate::domain::noise::Noise;
+use crate::domain::object::Sphere;
+use crate::domain::pattern::Pattern;
+use crate::domain::Point;
+
+#[test]
+fn ch10_test14_noise_defaults() {
+    let n = Noise::default();
+    assert_eq!(n.seed, Noise::DEFAULT_SEED);
+    assert_eq!(n.octaves, 4);
+    assert_eq!(n.lacunarity, 2.0);
+    assert_eq!(n.gain, 0.5);
+}
+
+#[test]
+fn ch10_test15_noise_is_deterministic_and_seeded() {
+    let p = Point::new(1.3, -2.7, 0.42);
+    let a = Noise::new().seed(7).build();
+    let b = Noise::new().seed(7).build();
+    let c = Noise::new().seed(8).build();
+
+    assert_eq!(a.noise(&p), b.noise(&p));
+    assert_eq!(a.fbm(&p), b.fbm(&p));
+    assert_ne!(a.noise(&p), c.noise(&p));
+}
+
+#[test]
+fn ch10_test16_noise_is_zero_on_lattice_and_bounded() {
+    let n = Noise::default();
+    assert_eq!(n.noise(&Point::new(0.0, 0.0, 0.0)), 0.0);
+    assert_eq!(n.noise(&Point::new(3.0, -5.0, 12.0)), 0.0);
+
+    for i in 0..1000 {
+        let t = i as f64 * 0.137;
+        let p = Point::new(t, t * 0.7 - 3.0, -t * 1.3);
+        let v = n.noise(&p);
+        assert!((-1.0..=1.0).contains(&v));
+        assert!(n.turbulence(&p) >= 0.0);
+    }
+}
+
+#[test]
+fn ch10_test17_noise_is_continuous() {
+    let n = Noise::default();
+    let p = Point::new(0.31, 0.77, 1.59);
+    let q = Point::new(0.3101, 0.77, 1.59);
+    assert!((n.noise(&p) - n.noise(&q)).abs() < 0.001);
+}
+
+#[test]
+fn ch10_test18_fbm_octaves_and_gain() {
+    let p = Point::new(0.31, 0.77, 1.59);
+
+    // a single octave is plain noise
+    let one = Noise::new().octaves(1).build();
+    assert_eq!(one.fbm(&p), one.noise(&p));
+
+    // zero gain drops every octave after the first
+    let flat = Noise::new().octaves(6).gain(0.0).build();
+    assert_eq!(flat.fbm(&p), one.noise(&p));
+
+    // second octave sampled at lacunarity-scaled frequency
+    let two = Noise::new().octaves(2).lacunarity(3.0).gain(0.25).build();
+    let p3 = Point::new(p.x() * 3.0, p.y() * 3.0, p.z() * 3.0);
+    let expected = one.noise(&p) + 0.25 * one.noise(&p3);
+    assert!((two.fbm(&p) - expected).abs() < 1e-12);
+    let expected = one.noise(&p).abs() + 0.25 * one.noise(&p3).abs();
+    assert!((two.turbulence(&p) - expected).abs() < 1e-12);
+}
+
+#[test]
+fn ch10_test19_perturbed_pattern_jitters_points() {
+    let stripes = Pattern::new_striped(
+        Color::WHITE,
+        Color::BLACK,
+        crate::domain::matrix::IDENTITY.clone(),
+    );
+    let obj = Sphere::new().build().into();
+
+    // no jitter when scale is zero
+    let calm = Pattern::new_perturbed(
+        stripes.clone(),
+        Noise::default(),
+        0.0,
+        crate::domain::matrix::IDENTITY.clone(),
+    );
+    for i in 0..20 {
+        let p = Point::new(i as f64 * 0.13 - 1.0, 0.3, 0.2);
+        assert_eq!(calm.color_at(&obj, &p), stripes.color_at(&obj, &p));
+    }
+
+    // stripe boundaries move once perturbed
+    let wavy = Pattern::new_perturbed(
+        stripes.clone(),
+        Noise::default(),
+        0.5,
+        crate::domain::matrix::IDENTITY.clone(),
+    );
+    let differs = (0..100)
+        .map(|i| Point::new(i as f64 * 0.0731 - 3.0, 0.3, i as f64 * 0.05))
+        .any(|p| wavy.color_at(&obj, &p) != stripes.color_at(&obj, &p));
+    assert!(differs);
+}
+
+#[test]
+fn ch10_test20_cellular_noise() {
+    let n = Noise::new().seed(3).build();
+    for i in 0..200 {
+        let t = i as f64 * 0.173;
+        let p = Point::new(t, -t * 0.5, t * 0.25 + 1.0);
+        let (f1, f2) = n.cellular(&p);
+        assert!(f1 >= 0.0);
+        assert!(f1 <= f2);
+        assert!(f1 < 3.0_f64.sqrt());
+    }
+
+    // deterministic per seed
+    let p = Point::new(0.4, 1.7, -2.2);
+    assert_eq!(n.cellular(&p), Noise::new().seed(3).build().cellular(&p));
+    assert_ne!(n.cellular(&p), Noise::new().seed(4).build().cellular(&p));
+}
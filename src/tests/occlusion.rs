@@ -0,0 +1,118 @@
+use crate::domain::camera::Camera;
+use crate::domain::color::Color;
+use crate::domain::integrator::Integrator;
+use crate::domain::intersection::{Computations, Intersection};
+use crate::domain::light::Light;
+use crate::domain::matrix::Matrix;
+use crate::domain::object::{Object, Plane, Sphere};
+use crate::domain::occlusion::AmbientOcclusion;
+use crate::domain::ray::Ray;
+use crate::domain::settings::RenderSettings;
+use crate::domain::world::World;
+use crate::domain::{Point, Vector};
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+// a floor with a low ceiling over the half of it where z > 0
+fn overhang_world() -> World {
+    let mut w = World::new();
+    w.add_object(Plane::new().build().into());
+    let ceiling: Object = Sphere::new()
+        .transformation(
+            &Matrix::new_translation(0.0, 0.6, 10.0) * &Matrix::new_scaling(10.0, 0.1, 10.0),
+        )
+        .build()
+        .into();
+    w.add_object(ceiling);
+    w.light_source = Option::Some(Light::new(Point::new(0.0, 10.0, -20.0), Color::WHITE));
+    w
+}
+
+fn floor_color(w: &World, z: f64) -> Color {
+    let r = Ray::new(Point::new(0.0, 0.3, z), Vector::new(0.0, -1.0, 0.0));
+    w.color_at(&r, 5)
+}
+
+fn floor_visibility(w: &World, z: f64, settings: &AmbientOcclusion) -> f64 {
+    let r = Ray::new(Point::new(0.0, 0.3, z), Vector::new(0.0, -1.0, 0.0));
+    let i = Intersection::new(0.3, &w.objects[0]);
+    let comps = Computations::prepare_computations(&i, &r, Option::None);
+    w.ambient_visibility(&comps, settings)
+}
+
+#[test]
+fn ch8_test8_ambient_occlusion_is_off_by_default() {
+    assert_eq!(World::new().ambient_occlusion, Option::None);
+    let settings = AmbientOcclusion::default();
+    assert_eq!(settings.samples, AmbientOcclusion::DEFAULT_SAMPLES);
+    assert_eq!(settings.distance, AmbientOcclusion::DEFAULT_DISTANCE);
+}
+
+#[test]
+fn ch8_test9_open_surfaces_are_fully_visible() {
+    let w = overhang_world();
+    assert_eq!(
+        floor_visibility(&w, -5.0, &AmbientOcclusion::new(32, 1.0)),
+        1.0
+    );
+}
+
+#[test]
+fn ch8_test10_covered_surfaces_are_occluded_within_distance() {
+    let w = overhang_world();
+    let near = floor_visibility(&w, 5.0, &AmbientOcclusion::new(32, 1.0));
+    assert!(near < 0.5);
+
+    // the ceiling is out of reach for short rays
+    assert_eq!(
+        floor_visibility(&w, 5.0, &AmbientOcclusion::new(32, 0.2)),
+        1.0
+    );
+}
+
+#[test]
+fn ch8_test11_occlusion_darkens_only_the_ambient_term() {
+    let mut w = overhang_world();
+    let open = floor_color(&w, -5.0);
+    let covered = floor_color(&w, 5.0);
+
+    w.ambient_occlusion = Option::Some(AmbientOcclusion::new(32, 1.0));
+    assert_eq!(floor_color(&w, -5.0), open);
+    let occluded = floor_color(&w, 5.0);
+    assert!(occluded.red < covered.red);
+    // at most the ambient share can disappear
+    assert!(occluded.red >= covered.red - 0.1);
+}
+
+#[test]
+fn ch8_test12_occlusion_only_render_mode() {
+    let w = overhang_world();
+    let settings = AmbientOcclusion::new(32, 1.0);
+
+    let sky = Ray::new(Point::new(0.0, 0.3, -5.0), Vector::new(0.0, 1.0, 0.0));
+    assert_eq!(w.occlusion_color_at(&sky, &settings), Color::WHITE);
+    let open = Ray::new(Point::new(0.0, 0.3, -5.0), Vector::new(0.0, -1.0, 0.0));
+    assert_eq!(w.occlusion_color_at(&open, &settings), Color::WHITE);
+    let covered = Ray::new(Point::new(0.0, 0.3, 5.0), Vector::new(0.0, -1.0, 0.0));
+    let clay = w.occlusion_color_at(&covered, &settings);
+    assert!(clay.red < 0.5);
+    assert_eq!(clay.red, clay.blue);
+
+    // rendering uses the same estimate for every pixel
+    let mut c = Camera::new(3, 3, PI / 2.0);
+    c.transform = Matrix::new_view_transformation(
+        &Point::new(0.0, 0.3, 5.0),
+        &Point::new(0.0, 0.0, 5.0),
+        &Vector::new(0.0, 0.0, 1.0),
+    );
+    let render_settings = RenderSettings::new()
+        .integrator(Integrator::AmbientOcclusion(settings))
+        .build();
+    let image = w
+        .render(&c, &render_settings, Arc::new(move |_itr, _total_size| {}))
+        .unwrap();
+    assert_eq!(
+        image.pixel_at(1, 1),
+        w.occlusion_color_at(&c.ray_for_pixel(1, 1), &settings)
+    );
+}
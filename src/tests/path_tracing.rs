@@ -0,0 +1,235 @@
+use crate::domain::background::Background;
+use crate::domain::camera::Camera;
+use crate::domain::color::Color;
+use crate::domain::integrator::Integrator;
+use crate::domain::light::Light;
+use crate::domain::material::{Material, ShadingModel};
+use crate::domain::matrix::Matrix;
+use crate::domain::medium::{Density, Medium};
+use crate::domain::microfacet::sample_ggx_half_vector;
+use crate::domain::object::{Object, Plane, Sphere};
+use crate::domain::random::{sample_cosine_hemisphere, Rng};
+use crate::domain::ray::Ray;
+use crate::domain::settings::RenderSettings;
+use crate::domain::world::World;
+use crate::domain::{Point, Vector};
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+fn matte(color: Color, diffuse: f64) -> Material {
+    Material::new()
+        .color(color)
+        .ambient(0.0)
+        .diffuse(diffuse)
+        .specular(0.0)
+        .build()
+}
+
+fn average_path(w: &World, r: &Ray, paths: usize) -> Color {
+    let mut rng = Rng::new(7);
+    let mut total = Color::BLACK;
+    for _ in 0..paths {
+        total = &total + &w.trace_path(r, Integrator::DEFAULT_PATH_BOUNCES, &mut rng);
+    }
+    &total * (1.0 / paths as f32)
+}
+
+#[test]
+fn ch7_test27_default_integrator_is_whitted() {
+    assert_eq!(RenderSettings::default().integrator, Integrator::Whitted);
+    assert_eq!(
+        Integrator::new_path_tracing(),
+        Integrator::PathTracing {
+            max_bounces: Integrator::DEFAULT_PATH_BOUNCES,
+        }
+    );
+
+    // path tracing needs more samples, unless told otherwise
+    let settings = RenderSettings::new()
+        .integrator(Integrator::new_path_tracing())
+        .build();
+    assert_eq!(settings.samples, Integrator::DEFAULT_PATH_SAMPLES);
+    let settings = RenderSettings::new()
+        .samples(3)
+        .integrator(Integrator::new_path_tracing())
+        .build();
+    assert_eq!(settings.samples, 3);
+}
+
+#[test]
+fn ch7_test28_cosine_samples_stay_in_hemisphere() {
+    let mut rng = Rng::new(3);
+    let normal = Vector::new(0.0, 1.0, 1.0).normalize();
+    let mut mean_cos = 0.0;
+    for _ in 0..2000 {
+        let sample = sample_cosine_hemisphere(&mut rng, &normal);
+        assert!((sample.magnitude() - 1.0).abs() < 0.0001);
+        assert!(sample.dot_product(&normal) >= 0.0);
+        mean_cos += sample.dot_product(&normal) / 2000.0;
+    }
+    // the mean cosine of a cosine-weighted hemisphere is 2/3
+    assert!((mean_cos - 2.0 / 3.0).abs() < 0.02);
+}
+
+#[test]
+fn ch7_test29_pixel_center_sample_matches_pixel_ray() {
+    let c = Camera::new(201, 101, PI / 2.0);
+    assert_eq!(
+        c.ray_for_sample(100, 50, 0.5, 0.5),
+        c.ray_for_pixel(100, 50)
+    );
+    // the top left corner lies up and left of the pixel center
+    let center = c.ray_for_pixel(0, 0);
+    let corner = c.ray_for_sample(0, 0, 0.0, 0.0);
+    assert!(corner.direction.x() > center.direction.x());
+    assert!(corner.direction.y() > center.direction.y());
+}
+
+#[test]
+fn ch7_test30_white_furnace() {
+    // a lossless diffuse ball in a uniform environment looks exactly like the environment
+    let mut w = World::new();
+    w.add_object(
+        Sphere::new()
+            .material(matte(Color::WHITE, 1.0))
+            .build()
+            .into(),
+    );
+    w.background = Background::Solid(Color::new(0.5, 0.5, 0.5));
+    let r = Ray::new(Point::new(0.2, 0.3, -5.0), Vector::new(0.0, 0.0, 1.0));
+    assert_eq!(average_path(&w, &r, 16), Color::new(0.5, 0.5, 0.5));
+}
+
+#[test]
+fn ch7_test31_next_event_estimation_lights_diffuse_surfaces() {
+    let mut w = World::new();
+    w.add_object(
+        Plane::new()
+            .material(matte(Color::WHITE, 0.5))
+            .build()
+            .into(),
+    );
+    w.light_source = Option::Some(Light::new(Point::new(0.0, 10.0, 0.0), Color::WHITE));
+    let r = Ray::new(Point::new(0.0, 5.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+    assert_eq!(average_path(&w, &r, 8), Color::new(0.5, 0.5, 0.5));
+}
+
+#[test]
+fn ch7_test32_diffuse_surfaces_bleed_color() {
+    let mut w = World::new();
+    w.add_object(
+        Plane::new()
+            .material(matte(Color::WHITE, 0.8))
+            .build()
+            .into(),
+    );
+    let wall: Object = Plane::new()
+        .material(matte(Color::new(1.0, 0.0, 0.0), 0.8))
+        .transformation(&Matrix::new_translation(1.0, 0.0, 0.0) * &Matrix::new_rotation_z(PI / 2.0))
+        .build()
+        .into();
+    w.add_object(wall);
+    w.light_source = Option::Some(Light::new(Point::new(-5.0, 10.0, 0.0), Color::WHITE));
+    let r = Ray::new(Point::new(0.8, 0.5, 0.0), Vector::new(0.0, -1.0, 0.0));
+
+    // direct lighting alone leaves the floor neutral
+    let whitted = w.color_at(&r, 5);
+    assert_eq!(whitted.red, whitted.blue);
+
+    // light bouncing off the red wall tints it
+    let traced = average_path(&w, &r, 256);
+    assert!(traced.red > traced.blue + 0.05);
+    assert!(traced.blue > 0.0);
+}
+
+#[test]
+fn ch7_test33_ggx_half_vectors_stay_in_hemisphere() {
+    let mut rng = Rng::new(5);
+    let normal = Vector::new(0.0, 1.0, 0.0);
+    let mut smooth_cos = 0.0;
+    let mut rough_cos = 0.0;
+    for _ in 0..1000 {
+        let smooth = sample_ggx_half_vector(&mut rng, &normal, 0.1);
+        let rough = sample_ggx_half_vector(&mut rng, &normal, 0.9);
+        assert!((smooth.magnitude() - 1.0).abs() < 0.0001);
+        assert!(rough.dot_product(&normal) >= 0.0);
+        smooth_cos += smooth.dot_product(&normal) / 1000.0;
+        rough_cos += rough.dot_product(&normal) / 1000.0;
+    }
+    // smooth surfaces keep their microfacets close to the normal
+    assert!(smooth_cos > 0.99);
+    assert!(rough_cos < smooth_cos - 0.1);
+}
+
+#[test]
+fn ch7_test34_path_tracing_reflects_off_metals() {
+    let mut w = World::new();
+    let gold = Material::new()
+        .color(Color::new(1.0, 0.8, 0.3))
+        .ambient(0.0)
+        .diffuse(0.0)
+        .shading_model(ShadingModel::Microfacet { metallic: 1.0 })
+        .roughness(0.3)
+        .build();
+    w.add_object(Sphere::new().material(gold).build().into());
+    w.background = Background::Solid(Color::new(0.5, 0.5, 0.5));
+    w.light_source = Option::Some(Light::new(Point::new(-10.0, 10.0, -10.0), Color::WHITE));
+    let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+
+    // no diffuse lobe, yet the environment and the highlight are reflected in the metal's color
+    let traced = average_path(&w, &r, 64);
+    assert!(traced.blue > 0.05);
+    assert!(traced.red > traced.green && traced.green > traced.blue);
+}
+
+#[test]
+fn ch7_test35_path_tracing_passes_through_fog() {
+    let mut w = World::new();
+    w.background = Background::Solid(Color::new(0.2, 0.4, 0.6));
+    w.light_source = Option::Some(Light::new(Point::new(0.0, 10.0, 0.0), Color::WHITE));
+    w.fog = Option::Some(
+        Medium::new(Density::Uniform(0.1))
+            .max_distance(20.0)
+            .build(),
+    );
+    let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+
+    // the fog hides the background and scatters the light, as in the Whitted tracer
+    let traced = average_path(&w, &r, 1);
+    assert_eq!(traced, w.color_at(&r, 5));
+    assert_ne!(traced, Color::new(0.2, 0.4, 0.6));
+}
+
+#[test]
+fn ch7_test36_path_traced_renders_are_repeatable() {
+    let mut w = World::new();
+    w.add_object(
+        Sphere::new()
+            .material(matte(Color::WHITE, 0.9))
+            .build()
+            .into(),
+    );
+    w.light_source = Option::Some(Light::new(Point::new(-10.0, 10.0, -10.0), Color::WHITE));
+    let settings = RenderSettings::new()
+        .integrator(Integrator::PathTracing { max_bounces: 4 })
+        .samples(4)
+        .build();
+    let mut c = Camera::new(6, 6, PI / 3.0);
+    c.transform = Matrix::new_view_transformation(
+        &Point::new(0.0, 0.0, -5.0),
+        &Point::ORIGIN,
+        &Vector::new(0.0, 1.0, 0.0),
+    );
+    let first = w
+        .render(&c, &settings, Arc::new(move |_itr, _total_size| {}))
+        .unwrap();
+    let second = w
+        .render(&c, &settings, Arc::new(move |_itr, _total_size| {}))
+        .unwrap();
+    for y in 0..6 {
+        for x in 0..6 {
+            assert_eq!(first.pixel_at(x, y), second.pixel_at(x, y));
+        }
+    }
+    assert!(first.pixel_at(2, 2).red > 0.0);
+}
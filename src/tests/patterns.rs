@@ -2,19 +2,22 @@ use crate::domain::color::Color;
 use crate::domain::light::Light;
 use crate::domain::material::Material;
 use crate::domain::matrix::Matrix;
+use crate::domain::noise::Noise;
 use crate::domain::object::Sphere;
-use crate::domain::pattern::Pattern;
+use crate::domain::pattern::{Pattern, Striped, Texture};
+use crate::domain::ramp::ColorRamp;
 use crate::domain::{Point, Vector};
 
 #[test]
 fn ch10_test1_creating_stripe_pattern() {
-    if let Pattern::STRIPED { a, b, .. } = Pattern::new_striped(
+    let pattern = Pattern::new_striped(
         Color::WHITE,
         Color::BLACK,
         crate::domain::matrix::IDENTITY.clone(),
-    ) {
-        assert_eq!(a, Color::WHITE);
-        assert_eq!(b, Color::BLACK);
+    );
+    if let Some(Striped { a, b }) = pattern.texture().as_any().downcast_ref::<Striped>() {
+        assert_eq!(*a, Pattern::new_solid(Color::WHITE));
+        assert_eq!(*b, Pattern::new_solid(Color::BLACK));
     } else {
         panic!("Unexpected result!");
     }
@@ -240,3 +243,182 @@ fn ch10_test8_checkered_pattern() {
     assert_eq!(c2, Color::WHITE);
     assert_eq!(c3, Color::BLACK);
 }
+
+#[test]
+fn ch10_test9_nested_patterns_honor_their_own_transformations() {
+    let red = Color::new(1.0, 0.0, 0.0);
+    let blue = Color::new(0.0, 0.0, 1.0);
+
+    // stripes inside the first checker square, scaled down to a quarter unit
+    let stripes = Pattern::new_striped(red, blue, Matrix::new_scaling(0.25, 0.25, 0.25));
+    let pattern = Pattern::new_checkered(
+        stripes,
+        Color::BLACK,
+        crate::domain::matrix::IDENTITY.clone(),
+    );
+    let obj = Sphere::new().build().into();
+
+    assert_eq!(pattern.color_at(&obj, &Point::new(0.1, 0.1, 0.1)), red);
+    assert_eq!(pattern.color_at(&obj, &Point::new(0.3, 0.1, 0.1)), blue);
+    assert_eq!(pattern.color_at(&obj, &Point::new(0.6, 0.1, 0.1)), red);
+    assert_eq!(
+        pattern.color_at(&obj, &Point::new(1.1, 0.1, 0.1)),
+        Color::BLACK
+    );
+
+    // child transforms apply on top of the parent's
+    let stripes = Pattern::new_striped(red, blue, Matrix::new_translation(0.5, 0.0, 0.0));
+    let pattern = Pattern::new_checkered(stripes, Color::BLACK, Matrix::new_scaling(2.0, 2.0, 2.0));
+    assert_eq!(pattern.color_at(&obj, &Point::new(0.5, 0.1, 0.1)), blue);
+    assert_eq!(pattern.color_at(&obj, &Point::new(1.5, 0.1, 0.1)), red);
+}
+
+#[test]
+fn ch10_test10_blended_pattern_averages_sub_patterns() {
+    let horizontal = Pattern::new_striped(
+        Color::WHITE,
+        Color::BLACK,
+        crate::domain::matrix::IDENTITY.clone(),
+    );
+    let vertical = Pattern::new_striped(
+        Color::WHITE,
+        Color::BLACK,
+        Matrix::new_rotation_y(std::f64::consts::PI / 2.0),
+    );
+    let pattern = Pattern::new_blended(
+        horizontal,
+        vertical,
+        crate::domain::matrix::IDENTITY.clone(),
+    );
+    let obj = Sphere::new().build().into();
+
+    assert_eq!(
+        pattern.color_at(&obj, &Point::new(0.5, 0.0, -0.5)),
+        Color::WHITE
+    );
+    assert_eq!(
+        pattern.color_at(&obj, &Point::new(1.5, 0.0, -0.5)),
+        Color::new(0.5, 0.5, 0.5)
+    );
+    assert_eq!(
+        pattern.color_at(&obj, &Point::new(1.5, 0.0, 0.5)),
+        Color::BLACK
+    );
+}
+
+// User-defined pattern: red above the xz plane, green below.
+#[derive(PartialEq, Debug, Clone)]
+struct Horizon;
+
+impl Texture for Horizon {
+    fn color_at(&self, pattern_point: &Point) -> Color {
+        if pattern_point.y() >= 0.0 {
+            Color::new(1.0, 0.0, 0.0)
+        } else {
+            Color::new(0.0, 1.0, 0.0)
+        }
+    }
+}
+
+#[test]
+fn ch10_test11_user_defined_pattern() {
+    let pattern = Pattern::new(Horizon, Matrix::new_translation(0.0, 1.0, 0.0));
+    let obj = Sphere::new().build().into();
+
+    assert_eq!(
+        pattern.color_at(&obj, &Point::new(0.0, 1.5, 0.0)),
+        Color::new(1.0, 0.0, 0.0)
+    );
+    assert_eq!(
+        pattern.color_at(&obj, &Point::new(0.0, 0.5, 0.0)),
+        Color::new(0.0, 1.0, 0.0)
+    );
+
+    // usable as a sub-pattern, and comparable
+    let stripes = Pattern::new_striped(
+        pattern.clone(),
+        Color::BLACK,
+        crate::domain::matrix::IDENTITY.clone(),
+    );
+    assert_eq!(
+        stripes.color_at(&obj, &Point::new(0.0, 0.5, 0.0)),
+        Color::new(0.0, 1.0, 0.0)
+    );
+    assert_eq!(stripes.clone(), stripes);
+    assert_ne!(
+        Pattern::new(Horizon, Matrix::new_scaling(2.0, 2.0, 2.0)),
+        pattern
+    );
+}
+
+#[test]
+fn ch10_test12_wood_rings_follow_ramp() {
+    let ramp = ColorRamp::new(vec![(0.0, Color::BLACK), (1.0, Color::WHITE)]);
+    let obj = Sphere::new().build().into();
+
+    // without distortion rings are concentric around y
+    let wood = Pattern::new_wood(
+        ramp.clone(),
+        Noise::default(),
+        1.0,
+        0.0,
+        crate::domain::matrix::IDENTITY.clone(),
+    );
+    assert_eq!(
+        wood.color_at(&obj, &Point::new(0.25, 0.0, 0.0)),
+        Color::new(0.25, 0.25, 0.25)
+    );
+    assert_eq!(
+        wood.color_at(&obj, &Point::new(0.0, 3.0, 1.5)),
+        Color::new(0.5, 0.5, 0.5)
+    );
+
+    // distortion bends the rings
+    let wavy = Pattern::new_wood(
+        ramp,
+        Noise::default(),
+        1.0,
+        0.2,
+        crate::domain::matrix::IDENTITY.clone(),
+    );
+    let p = Point::new(0.35, 0.42, 0.1);
+    assert_ne!(wavy.color_at(&obj, &p), wood.color_at(&obj, &p));
+}
+
+#[test]
+fn ch10_test13_marble_and_granite_stay_within_ramp() {
+    let ramp = ColorRamp::new(vec![(0.0, Color::BLACK), (1.0, Color::WHITE)]);
+    let obj = Sphere::new().build().into();
+    let marble = Pattern::new_marble(
+        ramp.clone(),
+        Noise::default(),
+        2.0,
+        3.0,
+        crate::domain::matrix::IDENTITY.clone(),
+    );
+    let granite = Pattern::new_granite(
+        ColorRamp::new_granite(),
+        Noise::default(),
+        Matrix::new_scaling(0.1, 0.1, 0.1),
+    );
+    let straight = Pattern::new_marble(
+        ramp,
+        Noise::default(),
+        2.0,
+        0.0,
+        crate::domain::matrix::IDENTITY.clone(),
+    );
+
+    // undistorted marble is a plain sine band
+    let c = straight.color_at(&obj, &Point::new(std::f64::consts::PI / 4.0, 0.3, 0.2));
+    assert_eq!(c, Color::WHITE);
+
+    let mut granite_colors = Vec::new();
+    for i in 0..50 {
+        let p = Point::new(i as f64 * 0.037, 0.5 - i as f64 * 0.02, 0.1);
+        let c = marble.color_at(&obj, &p);
+        assert!((0.0..=1.0).contains(&c.red));
+        granite_colors.push(granite.color_at(&obj, &p));
+    }
+    assert!(granite_colors.iter().any(|c| *c != granite_colors[0]));
+}
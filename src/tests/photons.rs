@@ -0,0 +1,136 @@
+use crate::domain::color::Color;
+use crate::domain::kdtree::KdTree;
+use crate::domain::light::Light;
+use crate::domain::material::{Material, Substance};
+use crate::domain::matrix::Matrix;
+use crate::domain::object::{Object, Plane, Sphere};
+use crate::domain::photon::PhotonMapping;
+use crate::domain::random::Rng;
+use crate::domain::ray::Ray;
+use crate::domain::world::World;
+use crate::domain::{Point, Vector};
+
+fn random_points(count: usize) -> Vec<(Point, usize)> {
+    let mut rng = Rng::new(5);
+    (0..count)
+        .map(|i| {
+            let p = Point::new(rng.next_f64(), rng.next_f64(), rng.next_f64());
+            (p, i)
+        })
+        .collect()
+}
+
+// a matte floor below a clear ball, lit from straight above
+fn caustic_world(substance: Substance) -> World {
+    let mut w = World::new();
+    let floor: Object = Plane::new()
+        .material(Material::new().ambient(0.0).specular(0.0).build())
+        .build()
+        .into();
+    let ball: Object = Sphere::new()
+        .material(
+            Material::new()
+                .ambient(0.0)
+                .diffuse(0.0)
+                .specular(0.0)
+                .transparency(1.0)
+                .substance(substance)
+                .build(),
+        )
+        .transformation(Matrix::new_translation(0.0, 2.0, 0.0))
+        .build()
+        .into();
+    w.add_object(floor);
+    w.add_object(ball);
+    w.light_source = Option::Some(Light::new(Point::new(0.0, 10.0, 0.0), Color::WHITE));
+    w
+}
+
+fn settings() -> PhotonMapping {
+    PhotonMapping {
+        photons: 20_000,
+        neighbours: 50,
+        radius: 0.5,
+        max_bounces: 8,
+    }
+}
+
+fn floor_at(w: &World, x: f64) -> Color {
+    let r = Ray::new(Point::new(x, 0.5, 0.0), Vector::new(0.0, -1.0, 0.0));
+    w.color_at(&r, 5)
+}
+
+#[test]
+fn ch11_test40_kd_tree_finds_nearest_neighbours() {
+    let points = random_points(500);
+    let tree = KdTree::new(points.clone());
+    assert_eq!(tree.len(), 500);
+
+    let query = Point::new(0.4, 0.6, 0.5);
+    let distance2 = |p: &Point| {
+        let v = p - &query;
+        v.dot_product(&v)
+    };
+    let mut expected: Vec<(usize, f64)> = points.iter().map(|(p, i)| (*i, distance2(p))).collect();
+    expected.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+
+    let nearest = tree.nearest(&query, 10, f64::INFINITY);
+    assert_eq!(nearest.len(), 10);
+    for (found, wanted) in nearest.iter().zip(expected.iter()) {
+        assert_eq!(*found.0, wanted.0);
+        assert_eq!(found.1, wanted.1);
+    }
+}
+
+#[test]
+fn ch11_test41_kd_tree_respects_max_distance() {
+    let tree = KdTree::new(random_points(500));
+    let query = Point::new(0.5, 0.5, 0.5);
+    let nearest = tree.nearest(&query, 500, 0.1);
+    assert!(!nearest.is_empty() && nearest.len() < 500);
+    assert!(nearest.iter().all(|(_, d2)| *d2 <= 0.01));
+
+    assert!(tree.nearest(&query, 0, 1.0).is_empty());
+    assert!(KdTree::<usize>::new(Vec::new())
+        .nearest(&query, 5, 1.0)
+        .is_empty());
+}
+
+#[test]
+fn ch11_test42_photon_mapping_is_off_by_default() {
+    assert!(World::new().photon_map.is_none());
+
+    // no light, no photons
+    let mut w = caustic_world(Substance::GLASS);
+    w.light_source = Option::None;
+    w.emit_photons(&settings());
+    assert!(w.photon_map.unwrap().is_empty());
+}
+
+#[test]
+fn ch11_test43_glass_focuses_light_into_its_shadow() {
+    let mut w = caustic_world(Substance::GLASS);
+    // without photons light passes the clear ball unbent
+    assert_eq!(floor_at(&w, 0.0), Color::new(0.9, 0.9, 0.9));
+    let open = floor_at(&w, 5.0);
+
+    w.emit_photons(&settings());
+    assert!(!w.photon_map.as_ref().unwrap().is_empty());
+
+    // the ball's focus is brighter than the unobstructed floor
+    let caustic = floor_at(&w, 0.0);
+    assert!(caustic.red > open.red);
+    // away from the ball nothing changes
+    assert_eq!(floor_at(&w, 5.0), open);
+}
+
+#[test]
+fn ch11_test44_unfocused_photons_match_direct_light() {
+    // a ball that doesn't bend light passes on exactly what would have lit the floor
+    let mut w = caustic_world(Substance::VACUUM);
+    w.emit_photons(&settings());
+    // averaged over a few spots, single estimates are noisy
+    let spots = [-0.3, -0.15, 0.0, 0.15, 0.3];
+    let mean = spots.iter().map(|x| floor_at(&w, *x).red).sum::<f32>() / spots.len() as f32;
+    assert!((mean - 0.9).abs() < 0.1, "{}", mean);
+}
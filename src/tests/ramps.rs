@@ -0,0 +1,116 @@
+use crate::domain::color::Color;
+use crate::domain::matrix::Matrix;
+use crate::domain::object::Sphere;
+use crate::domain::pattern::Pattern;
+use crate::domain::ramp::{ColorRamp, Interpolation};
+use crate::domain::Point;
+
+#[test]
+fn ch10_test21_ramp_interpolates_between_stops() {
+    let red = Color::new(1.0, 0.0, 0.0);
+    let green = Color::new(0.0, 1.0, 0.0);
+    let blue = Color::new(0.0, 0.0, 1.0);
+    let ramp = ColorRamp::new(vec![(1.0, blue), (0.0, red), (0.5, green)]);
+
+    assert_eq!(ramp.stops()[0].1, red);
+    assert_eq!(ramp.color_at(0.0), red);
+    assert_eq!(ramp.color_at(0.25), Color::new(0.5, 0.5, 0.0));
+    assert_eq!(ramp.color_at(0.5), green);
+    assert_eq!(ramp.color_at(0.875), Color::new(0.0, 0.25, 0.75));
+    assert_eq!(ramp.color_at(1.0), blue);
+}
+
+#[test]
+fn ch10_test22_ramp_clamps_outside_of_stops() {
+    let ramp = ColorRamp::new(vec![(0.2, Color::WHITE), (0.8, Color::BLACK)]);
+    assert_eq!(ramp.color_at(-3.0), Color::WHITE);
+    assert_eq!(ramp.color_at(0.1), Color::WHITE);
+    assert_eq!(ramp.color_at(0.5), Color::new(0.5, 0.5, 0.5));
+    assert_eq!(ramp.color_at(0.9), Color::BLACK);
+    assert_eq!(ramp.color_at(7.0), Color::BLACK);
+
+    let single = ColorRamp::new(vec![(0.5, Color::WHITE)]);
+    assert_eq!(single.color_at(0.0), Color::WHITE);
+    assert_eq!(single.color_at(1.0), Color::WHITE);
+}
+
+#[test]
+fn ch10_test23_ramp_ignores_nan() {
+    let red = Color::new(1.0, 0.0, 0.0);
+    let ramp = ColorRamp::new(vec![
+        (0.8, Color::BLACK),
+        (f64::NAN, red),
+        (0.2, Color::WHITE),
+    ]);
+    assert_eq!(ramp.stops().len(), 2);
+    assert_eq!(ramp.color_at(0.5), Color::new(0.5, 0.5, 0.5));
+    assert_eq!(ramp.color_at(f64::NAN), Color::WHITE);
+}
+
+#[test]
+fn ch10_test24_uniform_ramp() {
+    let ramp = ColorRamp::new_uniform(vec![Color::BLACK, Color::WHITE, Color::BLACK]);
+    assert_eq!(ramp.stops()[1].0, 0.5);
+    assert_eq!(ramp.color_at(0.25), Color::new(0.5, 0.5, 0.5));
+    assert_eq!(ramp.color_at(0.5), Color::WHITE);
+}
+
+#[test]
+fn ch10_test25_ramp_interpolation_modes() {
+    let stops = vec![(0.0, Color::BLACK), (1.0, Color::WHITE)];
+
+    let smooth = ColorRamp::new_interpolated(stops.clone(), Interpolation::Smoothstep);
+    assert_eq!(smooth.color_at(0.5), Color::new(0.5, 0.5, 0.5));
+    assert_eq!(smooth.color_at(0.25), Color::new(0.15625, 0.15625, 0.15625));
+    assert_eq!(smooth.color_at(0.75), Color::new(0.84375, 0.84375, 0.84375));
+
+    let constant = ColorRamp::new_interpolated(
+        vec![
+            (0.0, Color::BLACK),
+            (0.5, Color::WHITE),
+            (1.0, Color::BLACK),
+        ],
+        Interpolation::Constant,
+    );
+    assert_eq!(constant.color_at(0.49), Color::BLACK);
+    assert_eq!(constant.color_at(0.5), Color::WHITE);
+    assert_eq!(constant.color_at(0.99), Color::WHITE);
+    assert_eq!(constant.color_at(1.0), Color::BLACK);
+}
+
+#[test]
+fn ch10_test26_ramp_gradient_patterns() {
+    let ramp = ColorRamp::new(vec![(0.0, Color::WHITE), (2.0, Color::BLACK)]);
+    let obj = Sphere::new().build().into();
+    let grey = Color::new(0.5, 0.5, 0.5);
+
+    // linear - no wrapping past the last stop
+    let linear =
+        Pattern::new_linear_gradient(ramp.clone(), crate::domain::matrix::IDENTITY.clone());
+    assert_eq!(linear.color_at(&obj, &Point::new(1.0, 5.0, 5.0)), grey);
+    assert_eq!(
+        linear.color_at(&obj, &Point::new(3.5, 0.0, 0.0)),
+        Color::BLACK
+    );
+
+    // radial - distance from the y axis
+    let radial =
+        Pattern::new_radial_gradient(ramp.clone(), crate::domain::matrix::IDENTITY.clone());
+    assert_eq!(radial.color_at(&obj, &Point::new(0.6, 7.0, 0.8)), grey);
+    assert_eq!(
+        radial.color_at(&obj, &Point::new(0.0, 7.0, 0.0)),
+        Color::WHITE
+    );
+
+    // spherical - distance from the origin, honoring the pattern transformation
+    let spherical =
+        Pattern::new_spherical_gradient(ramp.clone(), Matrix::new_scaling(2.0, 2.0, 2.0));
+    assert_eq!(spherical.color_at(&obj, &Point::new(0.0, 2.0, 0.0)), grey);
+
+    // usable in place of a color in two-color patterns
+    let stripes = Pattern::new_striped(ramp, Color::BLACK, crate::domain::matrix::IDENTITY.clone());
+    assert_eq!(
+        stripes.color_at(&obj, &Point::new(0.5, 0.0, 0.0)),
+        Color::new(0.75, 0.75, 0.75)
+    );
+}
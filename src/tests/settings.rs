@@ -0,0 +1,166 @@
+use crate::domain::background::Background;
+use crate::domain::camera::Camera;
+use crate::domain::color::Color;
+use crate::domain::integrator::Integrator;
+use crate::domain::intersection::{Computations, Intersection};
+use crate::domain::material::Material;
+use crate::domain::matrix::Matrix;
+use crate::domain::object::{Object, Plane, Sphere};
+use crate::domain::ray::Ray;
+use crate::domain::settings::{Recursion, RenderSettings};
+use crate::domain::world::World;
+use crate::domain::{Point, Vector, EPSILON};
+use crate::tests::world::build_test_world;
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+fn test_camera(size: usize) -> Camera {
+    let mut c = Camera::new(size, size, PI / 2.0);
+    c.transform = Matrix::new_view_transformation(
+        &Point::new(0.0, 0.0, -5.0),
+        &Point::ORIGIN,
+        &Vector::new(0.0, 1.0, 0.0),
+    );
+    c
+}
+
+// an invisible glass ball in front of a blue background, seen through two refractions
+fn glass_ball_world() -> World {
+    let mut w = World::new();
+    w.background = Background::Solid(Color::new(0.0, 0.0, 1.0));
+    let ball: Object = Sphere::new()
+        .material(
+            Material::new()
+                .ambient(0.0)
+                .diffuse(0.0)
+                .specular(0.0)
+                .transparency(1.0)
+                .refractive_index_override(1.0)
+                .build(),
+        )
+        .build()
+        .into();
+    w.add_object(ball);
+    w
+}
+
+#[test]
+fn ch11_test45_default_settings_match_previous_renders() {
+    let settings = RenderSettings::default();
+    assert_eq!(settings, RenderSettings::new().build());
+    assert_eq!(settings.max_depth, 5);
+    assert_eq!(settings.max_reflection_depth, 5);
+    assert_eq!(settings.max_refraction_depth, 5);
+    assert_eq!(settings.samples, 1);
+    assert_eq!(settings.bias, EPSILON);
+    assert_eq!(settings.integrator, Integrator::Whitted);
+    assert_eq!(settings.threads, Option::None);
+    assert_eq!(settings.recursion(), Recursion::from(5));
+
+    let image = build_test_world()
+        .render(&test_camera(11), &settings, Arc::new(|_, _| {}))
+        .unwrap();
+    assert_eq!(image.pixel_at(5, 5), Color::new(0.38066, 0.47583, 0.2855));
+}
+
+#[test]
+fn ch11_test46_recursion_counts_bounces_by_kind() {
+    let recursion = RenderSettings::new()
+        .max_depth(3)
+        .max_reflection_depth(1)
+        .build()
+        .recursion();
+    assert!(recursion.can_reflect() && recursion.can_refract());
+
+    let reflected = recursion.reflected();
+    assert_eq!((reflected.depth, reflected.bounce), (2, 1));
+    assert!(!reflected.can_reflect());
+    assert!(reflected.can_refract());
+
+    let refracted = reflected.refracted().refracted();
+    assert_eq!((refracted.depth, refracted.refractions), (0, 3));
+    assert!(!refracted.can_refract());
+}
+
+#[test]
+fn ch11_test47_reflection_depth_is_limited_separately() {
+    let mut w = build_test_world();
+    let mirror: Object = Plane::new()
+        .material(Material::new().reflective(0.5).build())
+        .transformation(Matrix::new_translation(0.0, -1.0, 0.0))
+        .build()
+        .into();
+    w.add_object(mirror.clone());
+    let r = Ray::new(
+        Point::new(0.0, 0.0, -3.0),
+        Vector::new(0.0, -2_f64.sqrt() / 2.0, 2_f64.sqrt() / 2.0),
+    );
+    let i = Intersection::new(2_f64.sqrt(), &mirror);
+    let comps = Computations::prepare_computations(&i, &r, Option::None);
+
+    let no_reflections = RenderSettings::new().max_reflection_depth(0).build();
+    assert_eq!(
+        w.reflected_color(&comps, no_reflections.recursion()),
+        Color::BLACK
+    );
+    let one_reflection = RenderSettings::new().max_reflection_depth(1).build();
+    assert_eq!(
+        w.reflected_color(&comps, one_reflection.recursion()),
+        Color::new(0.19032, 0.2379, 0.14274)
+    );
+}
+
+#[test]
+fn ch11_test48_refraction_depth_is_limited_separately() {
+    let w = glass_ball_world();
+    let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+    let blue = Color::new(0.0, 0.0, 1.0);
+    assert_eq!(w.color_at(&r, 5), blue);
+
+    let no_reflections = RenderSettings::new().max_reflection_depth(0).build();
+    assert_eq!(w.color_at(&r, no_reflections.recursion()), blue);
+
+    // leaving the ball takes a second refraction
+    let one_refraction = RenderSettings::new().max_refraction_depth(1).build();
+    assert_eq!(w.color_at(&r, one_refraction.recursion()), Color::BLACK);
+    let shallow = RenderSettings::new().max_depth(1).build();
+    assert_eq!(w.color_at(&r, shallow.recursion()), Color::BLACK);
+}
+
+#[test]
+fn ch11_test49_bias_offsets_spawned_rays() {
+    let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+    let shape: Object = Sphere::new()
+        .transformation(Matrix::new_translation(0.0, 0.0, 1.0))
+        .build()
+        .into();
+    let i = Intersection::new(5.0, &shape);
+
+    let comps = Computations::prepare_computations(&i, &r, Option::None);
+    assert!(crate::domain::epsilon_eq(comps.over_point.z(), -EPSILON));
+
+    let comps = Computations::prepare_computations_with_bias(&i, &r, Option::None, 0.1);
+    assert!(crate::domain::epsilon_eq(comps.over_point.z(), -0.1));
+    assert!(crate::domain::epsilon_eq(comps.under_point.z(), 0.1));
+    assert_eq!(comps.point, Point::ORIGIN);
+}
+
+#[test]
+fn ch11_test50_renders_are_independent_of_thread_count() {
+    let w = build_test_world();
+    let c = test_camera(8);
+    let render = |settings: &RenderSettings| w.render(&c, settings, Arc::new(|_, _| {})).unwrap();
+
+    let all_cores = render(&RenderSettings::new().samples(4).build());
+    let single = render(&RenderSettings::new().samples(4).threads(1).build());
+    for y in 0..8 {
+        for x in 0..8 {
+            assert_eq!(all_cores.pixel_at(x, y), single.pixel_at(x, y));
+        }
+    }
+
+    // several samples smooth the outline of the ball
+    let sharp = render(&RenderSettings::default());
+    let edge = (0..8).any(|y| (0..8).any(|x| sharp.pixel_at(x, y) != all_cores.pixel_at(x, y)));
+    assert!(edge);
+}
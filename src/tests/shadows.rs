@@ -0,0 +1,106 @@
+use crate::domain::color::Color;
+use crate::domain::light::Light;
+use crate::domain::material::Material;
+use crate::domain::matrix::Matrix;
+use crate::domain::object::{Cube, GeometryBuilder, Object, Plane, Sphere};
+use crate::domain::ray::Ray;
+use crate::domain::world::World;
+use crate::domain::{Point, Vector};
+
+// a thin pane at the given height between the floor and a light above
+fn pane(height: f64, material: Material) -> Object {
+    Cube::new()
+        .material(material)
+        .transformation(
+            &Matrix::new_translation(0.0, height, 0.0) * &Matrix::new_scaling(3.0, 0.05, 3.0),
+        )
+        .build()
+        .into()
+}
+
+fn glass(color: Color, transparency: f64) -> Material {
+    Material::new()
+        .color(color)
+        .transparency(transparency)
+        .build()
+}
+
+fn lit_world() -> World {
+    let mut w = World::new();
+    w.light_source = Option::Some(Light::new(Point::new(0.0, 10.0, 0.0), Color::WHITE));
+    w
+}
+
+#[test]
+fn ch8_test13_shapes_cast_shadows_by_default() {
+    let sphere: Object = Sphere::new().build().into();
+    assert!(sphere.shape().casts_shadow);
+    let plane: Object = Plane::new().casts_shadow(false).build().into();
+    assert!(!plane.shape().casts_shadow);
+}
+
+#[test]
+fn ch8_test14_opaque_objects_block_the_light() {
+    let mut w = lit_world();
+    w.add_object(pane(2.0, Material::default()));
+    assert_eq!(w.shadow_filter(&Point::ORIGIN), Color::BLACK);
+    assert!(w.is_shadowed(&Point::ORIGIN));
+}
+
+#[test]
+fn ch8_test15_windows_let_light_through() {
+    let mut w = lit_world();
+    w.add_object(pane(2.0, glass(Color::WHITE, 0.8)));
+    assert_eq!(w.shadow_filter(&Point::ORIGIN), Color::new(0.8, 0.8, 0.8));
+    assert!(!w.is_shadowed(&Point::ORIGIN));
+
+    // every pane dims the light further
+    w.add_object(pane(4.0, glass(Color::WHITE, 0.5)));
+    assert_eq!(w.shadow_filter(&Point::ORIGIN), Color::new(0.4, 0.4, 0.4));
+}
+
+#[test]
+fn ch8_test16_colored_glass_tints_its_shadow() {
+    let mut w = lit_world();
+    w.add_object(pane(2.0, glass(Color::new(1.0, 0.5, 0.0), 1.0)));
+    assert_eq!(w.shadow_filter(&Point::ORIGIN), Color::new(1.0, 0.5, 0.0));
+
+    // the floor below is lit in the glass's color
+    let floor: Object = Plane::new()
+        .material(Material::new().ambient(0.0).specular(0.0).build())
+        .build()
+        .into();
+    w.add_object(floor);
+    let r = Ray::new(Point::new(0.0, 1.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+    assert_eq!(w.color_at(&r, 5), Color::new(0.9, 0.45, 0.0));
+}
+
+#[test]
+fn ch8_test17_shadow_rays_are_absorbed_inside_objects() {
+    let mut w = lit_world();
+    let ball = Sphere::new()
+        .material(
+            Material::new()
+                .transparency(1.0)
+                .absorption_color(Color::new(0.5, 1.0, 1.0))
+                .absorption_density(1.0)
+                .build(),
+        )
+        .transformation(Matrix::new_translation(0.0, 3.0, 0.0))
+        .build();
+    w.add_object(ball.into());
+    // two units of glass on the way
+    assert_eq!(w.shadow_filter(&Point::ORIGIN), Color::new(0.25, 1.0, 1.0));
+}
+
+#[test]
+fn ch8_test18_objects_can_opt_out_of_casting_shadows() {
+    let mut w = lit_world();
+    let water: Object = Plane::new()
+        .casts_shadow(false)
+        .transformation(Matrix::new_translation(0.0, 2.0, 0.0))
+        .build()
+        .into();
+    w.add_object(water);
+    assert_eq!(w.shadow_filter(&Point::ORIGIN), Color::WHITE);
+}
@@ -1,7 +1,10 @@
 use crate::domain::material::Material;
 use crate::domain::matrix::Matrix;
-use crate::domain::object::{Null, Object, Plane, Shape, Sphere};
+use crate::domain::object::{
+    Bounds, Cone, Cube, Cylinder, Geometry, Null, Object, Plane, Shape, Sphere,
+};
 use crate::domain::ray::Ray;
+use crate::domain::world::World;
 use crate::domain::{Point, Vector};
 
 #[test]
@@ -107,3 +110,125 @@ fn ch9_test7_ray_intersecting_plane_from_above_and_below() {
     assert_eq!(xs.len(), 1);
     assert_eq!(xs[0], 1.0);
 }
+
+// User-defined shape living outside of object.rs: unit disc on the xz plane.
+#[derive(Clone, PartialEq)]
+struct Disc {
+    shape: Shape,
+}
+
+impl Geometry for Disc {
+    fn shape(&self) -> &Shape {
+        &self.shape
+    }
+
+    fn shape_mut(&mut self) -> &mut Shape {
+        &mut self.shape
+    }
+
+    fn local_intersect(&self, ray: &Ray) -> Vec<f64> {
+        if ray.direction.y().abs() < crate::domain::EPSILON {
+            return vec![];
+        }
+        let t = -ray.origin.y() / ray.direction.y();
+        let p = ray.position(t);
+        if p.x().powi(2) + p.z().powi(2) <= 1.0 {
+            vec![t]
+        } else {
+            vec![]
+        }
+    }
+
+    fn local_normal_at(&self, _point: &Point) -> Vector {
+        Vector::new(0.0, 1.0, 0.0)
+    }
+
+    fn bounds(&self) -> Bounds {
+        Bounds::new(Point::new(-1.0, 0.0, -1.0), Point::new(1.0, 0.0, 1.0))
+    }
+}
+
+#[test]
+fn ch9_test8_user_defined_shape_in_world() {
+    let disc = Disc {
+        shape: Shape::new("Disc")
+            .transformation(Matrix::new_translation(0.0, 2.0, 0.0))
+            .build(),
+    };
+    let mut w = World::new();
+    w.add_object(disc.into());
+    w.add_object(Sphere::new().build().into());
+
+    let r = Ray::new(Point::new(0.0, 5.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+    let mut xs = w.intersect(&r);
+    assert_eq!(xs.len(), 3);
+    let hit = xs.hit().unwrap();
+    assert_eq!(hit.distance, 3.0);
+    assert_eq!(hit.object.shape().shape_type_name, "Disc");
+    assert_eq!(
+        hit.object.normal_at(&Point::new(0.0, 2.0, 0.0)),
+        Vector::new(0.0, 1.0, 0.0)
+    );
+
+    // clones keep their identity
+    let copy = w.objects[0].clone();
+    assert_eq!(copy, w.objects[0]);
+    assert_ne!(copy, w.objects[1]);
+
+    // misses outside of the disc radius
+    let r = Ray::new(Point::new(1.5, 5.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+    assert!(w.intersect(&r).is_empty());
+}
+
+#[test]
+fn ch9_test9_objects_compare_their_geometry() {
+    // same shape attributes, different kinds of geometry
+    let sphere = Sphere::new().build();
+    let cube = Cube {
+        shape: sphere.shape.clone(),
+    };
+    assert_ne!(Object::from(sphere.clone()), Object::from(cube));
+    assert_eq!(Object::from(sphere.clone()), Object::from(sphere));
+
+    // geometry fields beyond the shape count too
+    let cylinder = Cylinder::new().minimum(1.0).maximum(2.0).build();
+    let taller = Cylinder {
+        maximum: 3.0,
+        ..cylinder.clone()
+    };
+    let capped = Cylinder {
+        closed: true,
+        ..cylinder.clone()
+    };
+    assert_ne!(Object::from(cylinder.clone()), Object::from(taller));
+    assert_ne!(Object::from(cylinder.clone()), Object::from(capped));
+}
+
+#[test]
+fn ch9_test10_shape_bounds() {
+    let b = Object::from(Sphere::new().build()).bounds();
+    assert_eq!(b.minimum, Point::new(-1.0, -1.0, -1.0));
+    assert_eq!(b.maximum, Point::new(1.0, 1.0, 1.0));
+
+    // bounds follow the sphere's origin, as its intersections do
+    let b = Sphere::new()
+        .origin(Point::new(5.0, 0.0, 0.0))
+        .build()
+        .bounds();
+    assert_eq!(b.minimum, Point::new(4.0, -1.0, -1.0));
+    assert_eq!(b.maximum, Point::new(6.0, 1.0, 1.0));
+
+    let b = Cylinder::new().minimum(-2.0).maximum(3.0).build().bounds();
+    assert_eq!(b.minimum, Point::new(-1.0, -2.0, -1.0));
+    assert_eq!(b.maximum, Point::new(1.0, 3.0, 1.0));
+
+    let b = Cone::new().minimum(-3.0).maximum(2.0).build().bounds();
+    assert_eq!(b.minimum, Point::new(-3.0, -3.0, -3.0));
+    assert_eq!(b.maximum, Point::new(3.0, 2.0, 3.0));
+
+    let b = Plane::new().build().bounds();
+    assert_eq!(b.minimum.x(), -f64::INFINITY);
+    assert_eq!(b.maximum.z(), f64::INFINITY);
+    assert_eq!(b.minimum.y(), 0.0);
+    assert_eq!(b.maximum.y(), 0.0);
+}
@@ -0,0 +1,137 @@
+use crate::domain::color::Color;
+use crate::domain::integrator::Integrator;
+use crate::domain::light::Light;
+use crate::domain::material::Material;
+use crate::domain::matrix::Matrix;
+use crate::domain::object::{Cube, Object};
+use crate::domain::random::Rng;
+use crate::domain::ray::Ray;
+use crate::domain::subsurface::Subsurface;
+use crate::domain::world::World;
+use crate::domain::{Point, Vector};
+
+fn wax(subsurface: Option<Subsurface>) -> Material {
+    let mut material = Material::new();
+    material.specular(0.0);
+    if let Some(subsurface) = subsurface {
+        material.subsurface(subsurface);
+    }
+    material.build()
+}
+
+// a block of the given material, lit from the given height straight above (or below) it
+fn block(material: Material, scaling: Matrix, light_height: f64) -> World {
+    let mut w = World::new();
+    let block: Object = Cube::new()
+        .material(material)
+        .transformation(scaling)
+        .build()
+        .into();
+    w.add_object(block);
+    w.light_source = Option::Some(Light::new(Point::new(0.0, light_height, 0.0), Color::WHITE));
+    w
+}
+
+fn looking_down_at(w: &World, x: f64) -> Color {
+    let r = Ray::new(Point::new(x, 5.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+    w.color_at(&r, 5)
+}
+
+// looks at the side of the block facing +x, just below its top
+fn looking_at_side(w: &World) -> Color {
+    let r = Ray::new(Point::new(5.0, 0.9, 0.0), Vector::new(-1.0, 0.0, 0.0));
+    w.color_at(&r, 5)
+}
+
+#[test]
+fn ch6_test47_materials_have_no_subsurface_scattering_by_default() {
+    assert!(Material::default().subsurface.is_none());
+
+    let s = Subsurface::new(0.5).build();
+    assert_eq!(s.radius, 0.5);
+    assert_eq!(s.color, Subsurface::DEFAULT_COLOR);
+    assert_eq!(s.samples, Subsurface::DEFAULT_SAMPLES);
+
+    // without light nothing gets scattered
+    let mut w = block(wax(Some(s)), Matrix::new_scaling(1.0, 1.0, 1.0), 10.0);
+    w.light_source = Option::None;
+    assert_eq!(looking_down_at(&w, 0.0), Color::BLACK);
+}
+
+#[test]
+fn ch6_test48_subsurface_scattering_replaces_diffuse_light() {
+    let scaling = Matrix::new_scaling(5.0, 1.0, 5.0);
+    let plain = looking_down_at(&block(wax(None), scaling.clone(), 10.0), 0.0);
+    assert_eq!(plain, Color::new(1.0, 1.0, 1.0));
+
+    let subsurface = Subsurface::new(0.05)
+        .color(Color::new(0.99, 0.99, 0.99))
+        .samples(64)
+        .build();
+    let scattered = looking_down_at(&block(wax(Some(subsurface)), scaling, 10.0), 0.0);
+    // some of the light is lost inside, but most of it comes back out
+    assert!(
+        scattered.red < plain.red && scattered.red > 0.5,
+        "{:?}",
+        scattered
+    );
+}
+
+#[test]
+fn ch6_test49_subsurface_scattering_bleeds_light_into_shadows() {
+    let scaling = Matrix::new_scaling(1.0, 1.0, 1.0);
+    // the side is edge-on to the light, only ambient
+    let plain = looking_at_side(&block(wax(None), scaling.clone(), 10.0));
+    assert_eq!(plain, Color::new(0.1, 0.1, 0.1));
+
+    let subsurface = Subsurface::new(0.2).samples(64).build();
+    let scattered = looking_at_side(&block(wax(Some(subsurface)), scaling, 10.0));
+    assert!(scattered.red > plain.red + 0.05, "{:?}", scattered);
+}
+
+#[test]
+fn ch6_test50_subsurface_scattering_shines_through_thin_objects() {
+    // a thin sheet lit from below
+    let scaling = Matrix::new_scaling(5.0, 0.05, 5.0);
+    let plain = looking_down_at(&block(wax(None), scaling.clone(), -10.0), 0.0);
+    assert_eq!(plain, Color::new(0.1, 0.1, 0.1));
+
+    let subsurface = Subsurface::new(0.2).samples(64).build();
+    let scattered = looking_down_at(&block(wax(Some(subsurface)), scaling, -10.0), 0.0);
+    assert!(scattered.red > plain.red + 0.1, "{:?}", scattered);
+}
+
+#[test]
+fn ch6_test51_subsurface_color_tints_the_scattered_light() {
+    let subsurface = Subsurface::new(0.2)
+        .color(Color::new(0.95, 0.6, 0.3))
+        .samples(64)
+        .build();
+    let w = block(
+        wax(Some(subsurface)),
+        Matrix::new_scaling(5.0, 1.0, 5.0),
+        10.0,
+    );
+    let c = looking_down_at(&w, 0.0);
+    assert!(c.red > c.green && c.green > c.blue, "{:?}", c);
+}
+
+#[test]
+fn ch6_test52_path_tracing_scatters_light_beneath_the_surface() {
+    // a thin sheet lit from below, seen from above against a black background
+    let scaling = Matrix::new_scaling(5.0, 0.05, 5.0);
+    let r = Ray::new(Point::new(0.0, 5.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+    let traced = |w: &World| {
+        let mut rng = Rng::new(3);
+        (0..4).fold(Color::BLACK, |total, _| {
+            &total + &w.trace_path(&r, Integrator::DEFAULT_PATH_BOUNCES, &mut rng)
+        })
+    };
+
+    let plain = traced(&block(wax(None), scaling.clone(), -10.0));
+    assert_eq!(plain, Color::BLACK);
+
+    let subsurface = Subsurface::new(0.2).samples(16).build();
+    let scattered = traced(&block(wax(Some(subsurface)), scaling, -10.0));
+    assert!(scattered.red > 0.1, "{:?}", scattered);
+}
@@ -0,0 +1,247 @@
+use crate::domain::canvas::Canvas;
+use crate::domain::color::Color;
+use crate::domain::matrix::IDENTITY;
+use crate::domain::object::{Cube, Cylinder, Object, Plane, Sphere};
+use crate::domain::pattern::{Addressing, Filtering, ImageTexture, Pattern, Texture};
+use crate::domain::uv::UvMapping;
+use crate::domain::Point;
+use crate::utils::image_reader::ImageReader;
+use crate::utils::image_writer::{Format, ImageWriter};
+use indoc::indoc;
+use std::sync::Arc;
+
+// 2x2 image: red/green on top, blue/white at the bottom
+fn quad_canvas() -> Arc<Canvas> {
+    let mut canvas = Canvas::new(2, 2, Color::BLACK);
+    canvas[0][0] = Color::new(1.0, 0.0, 0.0);
+    canvas[0][1] = Color::new(0.0, 1.0, 0.0);
+    canvas[1][0] = Color::new(0.0, 0.0, 1.0);
+    canvas[1][1] = Color::WHITE;
+    Arc::new(canvas)
+}
+
+#[test]
+fn ch10_test34_reading_ascii_ppm() {
+    let ppm = indoc! {"
+        P3
+        # a comment
+        2 1
+        255
+        255 0 0 0 127.5 0
+        "};
+    assert!(ImageReader::new(ppm.as_bytes()).to_canvas().is_err());
+
+    let ppm = indoc! {"
+        P3
+        # a comment
+        2 1
+        255
+        255 0 0
+        0 51 255
+        "};
+    let canvas = ImageReader::new(ppm.as_bytes()).to_canvas().unwrap();
+    assert_eq!(canvas.width, 2);
+    assert_eq!(canvas.height, 1);
+    assert_eq!(canvas.pixel_at(0, 0), Color::new(1.0, 0.0, 0.0));
+    assert_eq!(canvas.pixel_at(1, 0), Color::new(0.0, 0.2, 1.0));
+}
+
+#[test]
+fn ch10_test35_reading_binary_ppm() {
+    let mut ppm = b"P6\n2 1\n255\n".to_vec();
+    ppm.extend_from_slice(&[255, 0, 0, 0, 51, 255]);
+    let canvas = ImageReader::new(&ppm).to_canvas().unwrap();
+    assert_eq!(canvas.pixel_at(0, 0), Color::new(1.0, 0.0, 0.0));
+    assert_eq!(canvas.pixel_at(1, 0), Color::new(0.0, 0.2, 1.0));
+
+    // truncated raster
+    assert!(ImageReader::new(&ppm[..ppm.len() - 1]).to_canvas().is_err());
+    assert!(ImageReader::new(b"P5\n1 1\n255\n0").to_canvas().is_err());
+}
+
+#[test]
+fn ch10_test36_oversized_ppm_is_rejected() {
+    let overflowing = format!("P6\n{} 2\n255\n", usize::MAX);
+    assert!(ImageReader::new(overflowing.as_bytes())
+        .to_canvas()
+        .is_err());
+    assert!(ImageReader::new(b"P6\n100000 100000\n255\n")
+        .to_canvas()
+        .is_err());
+}
+
+#[test]
+fn ch10_test37_written_ppm_round_trips() {
+    let canvas = quad_canvas();
+    let ppm = ImageWriter::new(Format::Ppm3, &canvas).to_string();
+    let read = ImageReader::new(ppm.as_bytes()).to_canvas().unwrap();
+    assert_eq!(read.width, canvas.width);
+    assert_eq!(read.height, canvas.height);
+    for (actual, expected) in read.pixels.iter().zip(canvas.pixels.iter()) {
+        assert_eq!(actual, expected);
+    }
+}
+
+#[test]
+fn ch10_test38_addressing_modes() {
+    assert_eq!(Addressing::Wrap.resolve(-1, 4), 3);
+    assert_eq!(Addressing::Wrap.resolve(5, 4), 1);
+    assert_eq!(Addressing::Clamp.resolve(-1, 4), 0);
+    assert_eq!(Addressing::Clamp.resolve(5, 4), 3);
+    assert_eq!(Addressing::Mirror.resolve(-1, 4), 0);
+    assert_eq!(Addressing::Mirror.resolve(4, 4), 3);
+    assert_eq!(Addressing::Mirror.resolve(5, 4), 2);
+    assert_eq!(Addressing::Mirror.resolve(8, 4), 0);
+}
+
+#[test]
+fn ch10_test39_nearest_image_sampling() {
+    let texture = ImageTexture::new(quad_canvas(), Filtering::Nearest, Addressing::Wrap);
+    // v = 0 is the bottom of the image
+    assert_eq!(
+        texture.color_at(&Point::new(0.25, 0.75, 0.0)),
+        Color::new(1.0, 0.0, 0.0)
+    );
+    assert_eq!(
+        texture.color_at(&Point::new(0.75, 0.75, 0.0)),
+        Color::new(0.0, 1.0, 0.0)
+    );
+    assert_eq!(
+        texture.color_at(&Point::new(0.25, 0.25, 0.0)),
+        Color::new(0.0, 0.0, 1.0)
+    );
+    assert_eq!(texture.color_at(&Point::new(0.75, 0.25, 0.0)), Color::WHITE);
+    // wraps around outside [0, 1)
+    assert_eq!(
+        texture.color_at(&Point::new(1.25, -0.25, 0.0)),
+        Color::new(1.0, 0.0, 0.0)
+    );
+
+    let clamped = ImageTexture::new(quad_canvas(), Filtering::Nearest, Addressing::Clamp);
+    assert_eq!(
+        clamped.color_at(&Point::new(1.25, -0.25, 0.0)),
+        Color::WHITE
+    );
+
+    let mirrored = ImageTexture::new(quad_canvas(), Filtering::Nearest, Addressing::Mirror);
+    assert_eq!(
+        mirrored.color_at(&Point::new(1.25, 0.75, 0.0)),
+        Color::new(0.0, 1.0, 0.0)
+    );
+}
+
+#[test]
+fn ch10_test40_bilinear_image_sampling() {
+    let texture = ImageTexture::new(quad_canvas(), Filtering::Bilinear, Addressing::Clamp);
+    // texel centers return the exact texel
+    assert_eq!(
+        texture.color_at(&Point::new(0.25, 0.75, 0.0)),
+        Color::new(1.0, 0.0, 0.0)
+    );
+    // halfway between red and green
+    assert_eq!(
+        texture.color_at(&Point::new(0.5, 0.75, 0.0)),
+        Color::new(0.5, 0.5, 0.0)
+    );
+    // center of the image averages all four texels
+    assert_eq!(
+        texture.color_at(&Point::new(0.5, 0.5, 0.0)),
+        Color::new(0.5, 0.5, 0.5)
+    );
+
+    // wrapping blends the right edge back into the left column
+    let wrapped = ImageTexture::new(quad_canvas(), Filtering::Bilinear, Addressing::Wrap);
+    assert_eq!(
+        wrapped.color_at(&Point::new(1.0, 0.75, 0.0)),
+        Color::new(0.5, 0.5, 0.0)
+    );
+}
+
+#[test]
+fn ch10_test41_image_texture_equality_is_by_image() {
+    let canvas = quad_canvas();
+    let a = Pattern::new_image(
+        canvas.clone(),
+        Filtering::Nearest,
+        Addressing::Wrap,
+        UvMapping::Native,
+        IDENTITY.clone(),
+    );
+    let b = Pattern::new_image(
+        canvas,
+        Filtering::Nearest,
+        Addressing::Wrap,
+        UvMapping::Native,
+        IDENTITY.clone(),
+    );
+    let c = Pattern::new_image(
+        quad_canvas(),
+        Filtering::Nearest,
+        Addressing::Wrap,
+        UvMapping::Native,
+        IDENTITY.clone(),
+    );
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+}
+
+#[test]
+fn ch10_test42_image_texture_on_shapes() {
+    let image = |mapping| {
+        Pattern::new_image(
+            quad_canvas(),
+            Filtering::Nearest,
+            Addressing::Wrap,
+            mapping,
+            IDENTITY.clone(),
+        )
+    };
+    let red = Color::new(1.0, 0.0, 0.0);
+    let green = Color::new(0.0, 1.0, 0.0);
+    let blue = Color::new(0.0, 0.0, 1.0);
+
+    let plane: Object = Plane::new().build().into();
+    let pattern = image(UvMapping::Planar);
+    assert_eq!(pattern.color_at(&plane, &Point::new(0.25, 0.0, 0.75)), red);
+    assert_eq!(
+        pattern.color_at(&plane, &Point::new(0.75, 0.0, 0.25)),
+        Color::WHITE
+    );
+
+    // top of the sphere maps to the top row, bottom to the bottom row
+    let sphere: Object = Sphere::new().build().into();
+    let pattern = image(UvMapping::Native);
+    assert_eq!(
+        pattern.color_at(&sphere, &Point::new(0.0, 0.5, -0.866)),
+        red
+    );
+    assert_eq!(
+        pattern.color_at(&sphere, &Point::new(0.0, -0.9, -0.1)),
+        blue
+    );
+
+    let cylinder: Object = Cylinder::new().build().into();
+    let pattern = image(UvMapping::Native);
+    assert_eq!(
+        pattern.color_at(&cylinder, &Point::new(0.0, 0.75, -1.0)),
+        red
+    );
+    assert_eq!(
+        pattern.color_at(&cylinder, &Point::new(0.0, 0.75, 1.0)),
+        green
+    );
+
+    // the front face of the cube sits in the left half of the image's middle band
+    let cube: Object = Cube::new().build().into();
+    let pattern = image(UvMapping::Native);
+    assert_eq!(pattern.color_at(&cube, &Point::new(0.0, 0.9, 1.0)), red);
+    assert_eq!(pattern.color_at(&cube, &Point::new(0.0, -0.9, 1.0)), blue);
+}
+
+#[test]
+fn ch10_test43_empty_ppm_is_rejected() {
+    // a texture needs at least one pixel to sample
+    assert!(ImageReader::new(b"P6\n0 2\n255\n").to_canvas().is_err());
+    assert!(ImageReader::new(b"P3\n2 0\n255\n").to_canvas().is_err());
+    assert!(ImageReader::new(b"P3\n0 0\n255\n").to_canvas().is_err());
+}
@@ -0,0 +1,185 @@
+use crate::domain::color::Color;
+use crate::domain::object::{Cube, Cylinder, Object, Plane, Sphere};
+use crate::domain::pattern::Pattern;
+use crate::domain::uv::{
+    cube_face, cube_face_uv, cube_map, cylindrical_map, planar_map, spherical_map, CubeFace,
+    UvMapping,
+};
+use crate::domain::Point;
+
+fn assert_uv(actual: (f64, f64), expected: (f64, f64)) {
+    assert!(
+        crate::domain::epsilon_eq(actual.0, expected.0)
+            && crate::domain::epsilon_eq(actual.1, expected.1),
+        "{:?} != {:?}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn ch10_test27_spherical_mapping() {
+    let cases = [
+        (Point::new(0.0, 0.0, -1.0), (0.0, 0.5)),
+        (Point::new(1.0, 0.0, 0.0), (0.25, 0.5)),
+        (Point::new(0.0, 0.0, 1.0), (0.5, 0.5)),
+        (Point::new(-1.0, 0.0, 0.0), (0.75, 0.5)),
+        (Point::new(0.0, 1.0, 0.0), (0.5, 1.0)),
+        (Point::new(0.0, -1.0, 0.0), (0.5, 0.0)),
+        (
+            Point::new(2_f64.sqrt() / 2.0, 2_f64.sqrt() / 2.0, 0.0),
+            (0.25, 0.75),
+        ),
+    ];
+    for (p, uv) in cases.iter() {
+        assert_uv(spherical_map(p), *uv);
+    }
+}
+
+#[test]
+fn ch10_test28_planar_mapping() {
+    let cases = [
+        (Point::new(0.25, 0.0, 0.5), (0.25, 0.5)),
+        (Point::new(0.25, 0.0, -0.25), (0.25, 0.75)),
+        (Point::new(0.25, 0.5, -0.25), (0.25, 0.75)),
+        (Point::new(1.25, 0.0, 0.5), (0.25, 0.5)),
+        (Point::new(0.25, 0.0, -1.75), (0.25, 0.25)),
+        (Point::new(1.0, 0.0, -1.0), (0.0, 0.0)),
+        (Point::new(0.0, 0.0, 0.0), (0.0, 0.0)),
+    ];
+    for (p, uv) in cases.iter() {
+        assert_uv(planar_map(p), *uv);
+    }
+}
+
+#[test]
+fn ch10_test29_cylindrical_mapping() {
+    let r = 2_f64.sqrt() / 2.0;
+    let cases = [
+        (Point::new(0.0, 0.0, -1.0), (0.0, 0.0)),
+        (Point::new(0.0, 0.5, -1.0), (0.0, 0.5)),
+        (Point::new(0.0, 1.0, -1.0), (0.0, 0.0)),
+        (Point::new(r, 0.5, -r), (0.125, 0.5)),
+        (Point::new(1.0, 0.5, 0.0), (0.25, 0.5)),
+        (Point::new(r, 0.5, r), (0.375, 0.5)),
+        (Point::new(0.0, -0.25, 1.0), (0.5, 0.75)),
+        (Point::new(-r, 0.5, r), (0.625, 0.5)),
+        (Point::new(-1.0, 1.25, 0.0), (0.75, 0.25)),
+        (Point::new(-r, 0.5, -r), (0.875, 0.5)),
+    ];
+    for (p, uv) in cases.iter() {
+        assert_uv(cylindrical_map(p), *uv);
+    }
+}
+
+#[test]
+fn ch10_test30_cube_faces_and_face_mapping() {
+    let faces = [
+        (Point::new(-1.0, 0.5, -0.25), CubeFace::Left),
+        (Point::new(1.1, -0.75, 0.8), CubeFace::Right),
+        (Point::new(0.1, 0.6, 0.9), CubeFace::Front),
+        (Point::new(-0.7, 0.0, -2.0), CubeFace::Back),
+        (Point::new(0.5, 1.0, 0.9), CubeFace::Up),
+        (Point::new(-0.2, -1.3, 1.1), CubeFace::Down),
+    ];
+    for (p, face) in faces.iter() {
+        assert_eq!(cube_face(p), *face);
+    }
+
+    let cases = [
+        (CubeFace::Front, Point::new(-0.5, 0.5, 1.0), (0.25, 0.75)),
+        (CubeFace::Front, Point::new(0.5, -0.5, 1.0), (0.75, 0.25)),
+        (CubeFace::Back, Point::new(0.5, 0.5, -1.0), (0.25, 0.75)),
+        (CubeFace::Back, Point::new(-0.5, -0.5, -1.0), (0.75, 0.25)),
+        (CubeFace::Left, Point::new(-1.0, 0.5, -0.5), (0.25, 0.75)),
+        (CubeFace::Left, Point::new(-1.0, -0.5, 0.5), (0.75, 0.25)),
+        (CubeFace::Right, Point::new(1.0, 0.5, 0.5), (0.25, 0.75)),
+        (CubeFace::Right, Point::new(1.0, -0.5, -0.5), (0.75, 0.25)),
+        (CubeFace::Up, Point::new(-0.5, 1.0, -0.5), (0.25, 0.75)),
+        (CubeFace::Up, Point::new(0.5, 1.0, 0.5), (0.75, 0.25)),
+        (CubeFace::Down, Point::new(-0.5, -1.0, 0.5), (0.25, 0.75)),
+        (CubeFace::Down, Point::new(0.5, -1.0, -0.5), (0.75, 0.25)),
+    ];
+    for (face, p, uv) in cases.iter() {
+        assert_uv(cube_face_uv(*face, p), *uv);
+    }
+
+    // all faces unfold into a single cross
+    assert_uv(cube_map(&Point::new(-0.5, 0.5, 1.0)), (0.3125, 0.5833333));
+    assert_uv(cube_map(&Point::new(0.5, 1.0, 0.5)), (0.4375, 0.75));
+    assert_uv(cube_map(&Point::new(-1.0, 0.5, -0.5)), (0.0625, 0.5833333));
+}
+
+#[test]
+fn ch10_test31_native_mapping_per_shape() {
+    let p = Point::new(0.7, 0.5, -0.7);
+    let sphere: Object = Sphere::new().build().into();
+    let plane: Object = Plane::new().build().into();
+    let cylinder: Object = Cylinder::new().build().into();
+    let cube: Object = Cube::new().build().into();
+
+    assert_uv(UvMapping::Native.map(&sphere, &p), spherical_map(&p));
+    assert_uv(UvMapping::Native.map(&plane, &p), planar_map(&p));
+    assert_uv(UvMapping::Native.map(&cylinder, &p), cylindrical_map(&p));
+    assert_uv(UvMapping::Native.map(&cube, &p), cube_map(&p));
+    assert_uv(UvMapping::Planar.map(&sphere, &p), planar_map(&p));
+}
+
+#[test]
+fn ch10_test32_uv_checkers_pattern() {
+    let pattern =
+        Pattern::new_uv_checkered(2.0, 2.0, Color::BLACK, Color::WHITE, UvMapping::Planar);
+    let plane: Object = Plane::new().build().into();
+    assert_eq!(
+        pattern.color_at(&plane, &Point::new(0.0, 0.0, 0.0)),
+        Color::BLACK
+    );
+    assert_eq!(
+        pattern.color_at(&plane, &Point::new(0.5, 0.0, 0.0)),
+        Color::WHITE
+    );
+    assert_eq!(
+        pattern.color_at(&plane, &Point::new(0.0, 0.0, 0.5)),
+        Color::WHITE
+    );
+    assert_eq!(
+        pattern.color_at(&plane, &Point::new(0.5, 0.0, 0.5)),
+        Color::BLACK
+    );
+    assert_eq!(
+        pattern.color_at(&plane, &Point::new(1.0, 0.0, 1.0)),
+        Color::BLACK
+    );
+}
+
+#[test]
+fn ch10_test33_uv_checkers_wrap_a_sphere() {
+    let pattern =
+        Pattern::new_uv_checkered(16.0, 8.0, Color::BLACK, Color::WHITE, UvMapping::Native);
+    let sphere: Object = Sphere::new().build().into();
+    let cases = [
+        (Point::new(0.4315, 0.4670, 0.7719), Color::WHITE),
+        (Point::new(-0.9654, 0.2552, -0.0534), Color::BLACK),
+        (Point::new(0.1039, 0.7090, 0.6975), Color::WHITE),
+        (Point::new(-0.4986, -0.7856, -0.3663), Color::BLACK),
+        (Point::new(-0.0317, -0.9395, 0.3411), Color::BLACK),
+        (Point::new(0.4809, -0.7721, 0.4154), Color::BLACK),
+        (Point::new(0.0285, -0.9612, -0.2745), Color::BLACK),
+        (Point::new(-0.5734, -0.2162, -0.7903), Color::WHITE),
+        (Point::new(0.7688, -0.1470, 0.6223), Color::BLACK),
+        (Point::new(-0.7652, 0.2175, 0.6060), Color::BLACK),
+    ];
+    for (p, c) in cases.iter() {
+        assert_eq!(pattern.color_at(&sphere, p), *c);
+    }
+
+    // object transformations are honored before mapping
+    let moved: Object = Sphere::new()
+        .transformation(crate::domain::matrix::Matrix::new_translation(
+            5.0, 0.0, 0.0,
+        ))
+        .build()
+        .into();
+    let p = Point::new(5.4315, 0.4670, 0.7719);
+    assert_eq!(pattern.color_at(&moved, &p), Color::WHITE);
+}
@@ -0,0 +1,122 @@
+use crate::domain::background::Background;
+use crate::domain::color::Color;
+use crate::domain::light::Light;
+use crate::domain::material::Material;
+use crate::domain::matrix::Matrix;
+use crate::domain::object::{GeometryBuilder, Object, Plane, Sphere};
+use crate::domain::ray::Ray;
+use crate::domain::world::World;
+use crate::domain::{Point, Vector};
+
+fn glowing(color: Color) -> Material {
+    Material::new()
+        .color(Color::BLACK)
+        .ambient(0.0)
+        .diffuse(0.0)
+        .specular(0.0)
+        .emission(color)
+        .build()
+}
+
+fn mirror() -> Material {
+    Material::new()
+        .color(Color::BLACK)
+        .ambient(0.0)
+        .diffuse(0.0)
+        .specular(0.0)
+        .reflective(1.0)
+        .build()
+}
+
+// a matte floor lit from above, with a ball hanging between the two
+fn shadow_world(floor_receives_shadow: bool) -> World {
+    let mut w = World::new();
+    w.light_source = Option::Some(Light::new(Point::new(0.0, 10.0, 0.0), Color::WHITE));
+    let floor: Object = Plane::new()
+        .material(Material::new().ambient(0.1).specular(0.0).build())
+        .receives_shadow(floor_receives_shadow)
+        .build()
+        .into();
+    let ball: Object = Sphere::new()
+        .visible_to_camera(false)
+        .transformation(Matrix::new_translation(0.0, 3.0, 0.0))
+        .build()
+        .into();
+    w.add_object(floor);
+    w.add_object(ball);
+    w
+}
+
+fn look_down(w: &World) -> Color {
+    let r = Ray::new(Point::new(0.0, 5.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+    w.color_at(&r, 5)
+}
+
+#[test]
+fn ch8_test19_shapes_are_visible_and_receive_shadows_by_default() {
+    let sphere: Object = Sphere::new().build().into();
+    assert!(sphere.shape().visible_to_camera);
+    assert!(sphere.shape().receives_shadow);
+    assert!(sphere.shape().casts_shadow);
+
+    let hidden: Object = Sphere::new()
+        .visible_to_camera(false)
+        .receives_shadow(false)
+        .build()
+        .into();
+    assert!(!hidden.shape().visible_to_camera);
+    assert!(!hidden.shape().receives_shadow);
+}
+
+#[test]
+fn ch8_test20_camera_rays_pass_hidden_objects() {
+    let mut w = World::new();
+    w.background = Background::Solid(Color::new(0.0, 0.0, 1.0));
+    w.add_object(
+        Sphere::new()
+            .material(glowing(Color::new(1.0, 0.0, 0.0)))
+            .visible_to_camera(false)
+            .build()
+            .into(),
+    );
+    let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+    assert_eq!(w.intersect(&r).len(), 2);
+    assert!(w.intersect_from_camera(&r).is_empty());
+    assert_eq!(w.color_at(&r, 5), Color::new(0.0, 0.0, 1.0));
+}
+
+#[test]
+fn ch8_test21_hidden_objects_show_in_reflections() {
+    let mut w = World::new();
+    let floor: Object = Plane::new()
+        .material(mirror())
+        .transformation(Matrix::new_translation(0.0, -1.0, 0.0))
+        .build()
+        .into();
+    let ball: Object = Sphere::new()
+        .material(glowing(Color::new(1.0, 0.0, 0.0)))
+        .visible_to_camera(false)
+        .transformation(Matrix::new_translation(0.0, 2.0, 0.0))
+        .build()
+        .into();
+    w.add_object(floor);
+    w.add_object(ball);
+
+    // looking down past the ball at its reflection
+    let r = Ray::new(Point::new(0.0, 0.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+    assert_eq!(w.color_at(&r, 5), Color::new(1.0, 0.0, 0.0));
+}
+
+#[test]
+fn ch8_test22_hidden_objects_still_cast_shadows() {
+    let w = shadow_world(true);
+    assert!(w.is_shadowed(&Point::ORIGIN));
+    assert_eq!(look_down(&w), Color::new(0.1, 0.1, 0.1));
+}
+
+#[test]
+fn ch8_test23_objects_can_ignore_shadows() {
+    let w = shadow_world(false);
+    // the floor is lit as if the ball weren't there
+    assert_eq!(look_down(&w), Color::new(1.0, 1.0, 1.0));
+}
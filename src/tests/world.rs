@@ -408,6 +408,7 @@ fn ch11_test16_shade_hit_with_transparent_material() {
         .into();
     let w = {
         let mut w = build_test_world();
+        w.transparent_shadows = false;
         w.add_object(floor.clone());
 
         let ball = Sphere::new()
@@ -444,6 +445,7 @@ fn ch11_test16_shade_hit_with_transparent_material() {
 #[test]
 fn ch11_test20_shade_hit_with_reflective_transparent_material() {
     let mut w = build_test_world();
+    w.transparent_shadows = false;
     let r = Ray::new(
         Point::new(0.0, 0.0, -3.0),
         Vector::new(0.0, -(2_f64.sqrt() / 2.0), 2_f64.sqrt() / 2.0),
@@ -481,3 +483,93 @@ fn ch11_test20_shade_hit_with_reflective_transparent_material() {
     let color_exp = Color::new(0.93391, 0.69643, 0.69243);
     assert_eq!(color, color_exp);
 }
+
+#[test]
+fn ch11_test21_shade_hit_with_transparent_material_lights_what_lies_beyond() {
+    let floor: Object = Plane::new()
+        .transformation(Matrix::new_translation(0.0, -1.0, 0.0))
+        .material(
+            Material::new()
+                .transparency(0.5)
+                .refractive_index_override(1.5)
+                .build(),
+        )
+        .build()
+        .into();
+    let w = {
+        let mut w = build_test_world();
+        w.add_object(floor.clone());
+
+        let ball = Sphere::new()
+            .material(
+                Material::new()
+                    .color(Color::new(1.0, 0.0, 0.0))
+                    .ambient(0.5)
+                    .build(),
+            )
+            .transformation(Matrix::new_translation(0.0, -3.5, -0.5))
+            .build()
+            .into();
+        w.add_object(ball);
+
+        w
+    };
+
+    let r = Ray::new(
+        Point::new(0.0, 0.0, -3.0),
+        Vector::new(0.0, -(2_f64.sqrt() / 2.0), 2_f64.sqrt() / 2.0),
+    );
+    let int = Intersection::new(2_f64.sqrt(), &floor);
+    let xs = {
+        let mut xs = Intersections::new();
+        xs.push(int);
+        xs
+    };
+
+    let comps = Computations::prepare_computations(&int, &r, Option::Some(&xs));
+    let color = w.shade_hit(&comps, 5);
+    // the half transparent floor lets half of the light through onto the ball
+    assert_eq!(color, Color::new(1.12546, 0.68642, 0.68642));
+}
+
+#[test]
+fn ch11_test22_shade_hit_with_reflective_transparent_material_lights_what_lies_beyond() {
+    let mut w = build_test_world();
+    let r = Ray::new(
+        Point::new(0.0, 0.0, -3.0),
+        Vector::new(0.0, -(2_f64.sqrt() / 2.0), 2_f64.sqrt() / 2.0),
+    );
+    let floor: Object = Plane::new()
+        .transformation(Matrix::new_translation(0.0, -1.0, 0.0))
+        .material(
+            Material::new()
+                .reflective(0.5)
+                .transparency(0.5)
+                .refractive_index_override(1.5)
+                .build(),
+        )
+        .build()
+        .into();
+    w.add_object(floor.clone());
+    let ball = Sphere::new()
+        .material(
+            Material::new()
+                .color(Color::new(1.0, 0.0, 0.0))
+                .ambient(0.5)
+                .build(),
+        )
+        .transformation(Matrix::new_translation(0.0, -3.5, -0.5))
+        .build();
+    w.add_object(ball.into());
+    let int = Intersection::new(2_f64.sqrt(), &floor);
+    let xs = {
+        let mut xs = Intersections::new();
+        xs.push(int);
+        xs
+    };
+    let comps = Computations::prepare_computations(&int, &r, Option::Some(&xs));
+    let color = w.shade_hit(&comps, 5);
+    // the half transparent floor lets half of the light through onto the ball
+    let color_exp = Color::new(1.115, 0.69643, 0.69243);
+    assert_eq!(color, color_exp);
+}
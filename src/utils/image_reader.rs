@@ -0,0 +1,268 @@
+use crate::domain::canvas::Canvas;
+use crate::domain::color::Color;
+use std::io::{Error, ErrorKind};
+
+const PPM3_MAGIC_NUMBER: &str = "P3";
+const PPM6_MAGIC_NUMBER: &str = "P6";
+const RADIANCE_MAGIC_NUMBER: &[u8] = b"#?";
+// largest image accepted, keeps a corrupt header from requesting an enormous canvas
+const MAX_PIXELS: usize = 1 << 28;
+
+pub struct ImageReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+fn invalid_data<T: Into<String>>(message: T) -> Error {
+    Error::new(ErrorKind::InvalidData, message.into())
+}
+
+// black canvas of the size read from an image header
+fn blank_canvas(width: usize, height: usize) -> Result<Canvas, Error> {
+    match width.checked_mul(height) {
+        Some(0) => Err(invalid_data(format!(
+            "Image size {}x{} is empty.",
+            width, height
+        ))),
+        Some(pixels) if pixels <= MAX_PIXELS => Ok(Canvas::new(width, height, Color::BLACK)),
+        _ => Err(invalid_data(format!(
+            "Image size {}x{} is too large.",
+            width, height
+        ))),
+    }
+}
+
+impl<'a> ImageReader<'a> {
+    // constructor
+    pub fn new(data: &'a [u8]) -> ImageReader<'a> {
+        ImageReader { data, position: 0 }
+    }
+
+    // parses ascii (P3) or binary (P6) ppm, or radiance hdr contents into a canvas
+    pub fn to_canvas(&mut self) -> Result<Canvas, Error> {
+        if self.data.starts_with(RADIANCE_MAGIC_NUMBER) {
+            return self.read_radiance();
+        }
+
+        let magic = self.next_token()?;
+        if magic != PPM3_MAGIC_NUMBER && magic != PPM6_MAGIC_NUMBER {
+            return Err(invalid_data(format!("Unsupported image format {}.", magic)));
+        }
+
+        let width = self.next_number()?;
+        let height = self.next_number()?;
+        let max_value = self.next_number()?;
+        if max_value == 0 || max_value > 65535 {
+            return Err(invalid_data(format!("Invalid max color {}.", max_value)));
+        }
+
+        let mut canvas = blank_canvas(width, height)?;
+        let scale = max_value as f32;
+        if magic == PPM3_MAGIC_NUMBER {
+            for pixel in canvas.pixels.iter_mut() {
+                let r = self.next_number()? as f32 / scale;
+                let g = self.next_number()? as f32 / scale;
+                let b = self.next_number()? as f32 / scale;
+                *pixel = Color::new(r, g, b);
+            }
+        } else {
+            // single whitespace separates header from raster
+            self.position += 1;
+            let sample_size = if max_value < 256 { 1 } else { 2 };
+            for pixel in canvas.pixels.iter_mut() {
+                let r = self.next_sample(sample_size)? as f32 / scale;
+                let g = self.next_sample(sample_size)? as f32 / scale;
+                let b = self.next_sample(sample_size)? as f32 / scale;
+                *pixel = Color::new(r, g, b);
+            }
+        }
+
+        Ok(canvas)
+    }
+
+    // radiance rgbe image - colors are kept unclamped, so values above 1.0 survive
+    fn read_radiance(&mut self) -> Result<Canvas, Error> {
+        // header lines run up to the first empty line
+        loop {
+            let line = self.next_line()?;
+            if line.is_empty() {
+                break;
+            }
+            if line.starts_with("FORMAT=") && line != "FORMAT=32-bit_rle_rgbe" {
+                return Err(invalid_data(format!("Unsupported hdr {}.", line)));
+            }
+        }
+
+        let resolution = self.next_line()?;
+        let parts: Vec<&str> = resolution.split_whitespace().collect();
+        if parts.len() != 4 || parts[0] != "-Y" || parts[2] != "+X" {
+            return Err(invalid_data(format!(
+                "Unsupported hdr resolution {}.",
+                resolution
+            )));
+        }
+        let parse = |token: &str| {
+            token
+                .parse::<usize>()
+                .map_err(|_| invalid_data(format!("Invalid number {}.", token)))
+        };
+        let height = parse(parts[1])?;
+        let width = parse(parts[3])?;
+        if width == 0 || height == 0 {
+            return Err(invalid_data(format!(
+                "Unsupported hdr resolution {}.",
+                resolution
+            )));
+        }
+
+        let mut canvas = blank_canvas(width, height)?;
+        let mut scanline = vec![[0_u8; 4]; width];
+        for row in 0..height {
+            self.read_scanline(&mut scanline)?;
+            for (column, rgbe) in scanline.iter().enumerate() {
+                canvas[row][column] = rgbe_to_color(rgbe);
+            }
+        }
+
+        Ok(canvas)
+    }
+
+    // reads either a flat or a run-length encoded ("new" rle) scanline
+    fn read_scanline(&mut self, scanline: &mut [[u8; 4]]) -> Result<(), Error> {
+        let width = scanline.len();
+        let header = self.next_bytes(4)?;
+        let encoded = (8..32768).contains(&width)
+            && header[0] == 2
+            && header[1] == 2
+            && header[2] & 0x80 == 0;
+        if !encoded {
+            scanline[0].copy_from_slice(header);
+            for pixel in scanline.iter_mut().skip(1) {
+                pixel.copy_from_slice(self.next_bytes(4)?);
+            }
+            return Ok(());
+        }
+
+        if ((header[2] as usize) << 8 | header[3] as usize) != width {
+            return Err(invalid_data("Invalid hdr scanline width."));
+        }
+
+        // each channel is encoded separately
+        for channel in 0..4 {
+            let mut column = 0;
+            while column < width {
+                let count = self.next_bytes(1)?[0] as usize;
+                let (length, run) = if count > 128 {
+                    (count - 128, true)
+                } else {
+                    (count, false)
+                };
+                if length == 0 || column + length > width {
+                    return Err(invalid_data("Invalid hdr run length."));
+                }
+
+                if run {
+                    let value = self.next_bytes(1)?[0];
+                    for pixel in scanline[column..column + length].iter_mut() {
+                        pixel[channel] = value;
+                    }
+                } else {
+                    let values = self.next_bytes(length)?;
+                    for (pixel, value) in scanline[column..column + length].iter_mut().zip(values) {
+                        pixel[channel] = *value;
+                    }
+                }
+                column += length;
+            }
+        }
+        Ok(())
+    }
+
+    // next newline-terminated line, without the terminator
+    fn next_line(&mut self) -> Result<String, Error> {
+        let start = self.position;
+        while self.position < self.data.len() && self.data[self.position] != b'\n' {
+            self.position += 1;
+        }
+        if self.position == self.data.len() {
+            return Err(invalid_data("Unexpected end of image data."));
+        }
+
+        let line = String::from_utf8_lossy(&self.data[start..self.position]).to_string();
+        self.position += 1;
+        Ok(line)
+    }
+
+    fn next_bytes(&mut self, count: usize) -> Result<&'a [u8], Error> {
+        if self.position + count > self.data.len() {
+            return Err(invalid_data("Unexpected end of image data."));
+        }
+
+        let bytes = &self.data[self.position..self.position + count];
+        self.position += count;
+        Ok(bytes)
+    }
+
+    // next whitespace-delimited token, skipping '#' comments
+    fn next_token(&mut self) -> Result<String, Error> {
+        loop {
+            while self.position < self.data.len() && self.data[self.position].is_ascii_whitespace()
+            {
+                self.position += 1;
+            }
+
+            if self.position < self.data.len() && self.data[self.position] == b'#' {
+                while self.position < self.data.len() && self.data[self.position] != b'\n' {
+                    self.position += 1;
+                }
+            } else {
+                break;
+            }
+        }
+
+        let start = self.position;
+        while self.position < self.data.len() && !self.data[self.position].is_ascii_whitespace() {
+            self.position += 1;
+        }
+
+        if start == self.position {
+            return Err(invalid_data("Unexpected end of image data."));
+        }
+        Ok(String::from_utf8_lossy(&self.data[start..self.position]).to_string())
+    }
+
+    fn next_number(&mut self) -> Result<usize, Error> {
+        let token = self.next_token()?;
+        token
+            .parse()
+            .map_err(|_| invalid_data(format!("Invalid number {}.", token)))
+    }
+
+    // big-endian binary sample of 1 or 2 bytes
+    fn next_sample(&mut self, size: usize) -> Result<usize, Error> {
+        if self.position + size > self.data.len() {
+            return Err(invalid_data("Unexpected end of image data."));
+        }
+
+        let mut value = 0;
+        for _ in 0..size {
+            value = (value << 8) | self.data[self.position] as usize;
+            self.position += 1;
+        }
+        Ok(value)
+    }
+}
+
+// shared-exponent color: each mantissa is scaled by 2^(exponent - 128 - 8)
+fn rgbe_to_color(rgbe: &[u8; 4]) -> Color {
+    if rgbe[3] == 0 {
+        return Color::BLACK;
+    }
+
+    let scale = 2_f32.powi(rgbe[3] as i32 - 136);
+    Color::new(
+        rgbe[0] as f32 * scale,
+        rgbe[1] as f32 * scale,
+        rgbe[2] as f32 * scale,
+    )
+}
@@ -1,10 +1,19 @@
+pub mod image_reader;
 pub mod image_writer;
 
+use crate::domain::animation::Animation;
+use crate::domain::camera::Camera;
 use crate::domain::canvas::Canvas;
+use crate::domain::settings::RenderSettings;
+use crate::domain::world::World;
+use crate::utils::image_reader::ImageReader;
 use crate::utils::image_writer::{Format, ImageWriter};
 use std::fmt::Display;
 use std::fs;
 use std::io::Error;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::SystemTime;
 
 // Utility method to write to file given canvas
@@ -25,3 +34,34 @@ pub fn write_imagefile<T: Into<String> + Display>(
     fs::write(filename, ppm)?;
     Ok(())
 }
+
+// Utility method to load a canvas from a ppm (P3 or P6) or radiance hdr file
+pub fn read_imagefile<T: AsRef<std::path::Path>>(source_filename: T) -> Result<Canvas, Error> {
+    let data = fs::read(source_filename)?;
+    ImageReader::new(&data).to_canvas()
+}
+
+// Renders the given range of frames of the animation into numbered files (`frame_0001.ppm`,
+// ...) in the target directory, and returns their paths. The world and camera are posed for
+// every frame in turn and left in the last frame's pose; a photon map isn't updated along.
+pub fn render_frames<P: AsRef<Path>>(
+    world: &mut World,
+    camera: &mut Camera,
+    animation: &Animation,
+    settings: &RenderSettings,
+    frames: RangeInclusive<usize>,
+    target_dir: P,
+    logger: Arc<dyn Fn(usize, usize) + Send + Sync>,
+) -> Result<Vec<PathBuf>, Error> {
+    fs::create_dir_all(&target_dir)?;
+    let mut written = Vec::new();
+    for frame in frames {
+        animation.apply(world, camera, frame as f64);
+        let canvas = world.render(camera, settings, Arc::clone(&logger))?;
+        let ppm = ImageWriter::new(Format::Ppm3, &canvas).to_string();
+        let filename = target_dir.as_ref().join(format!("frame_{:04}.ppm", frame));
+        fs::write(&filename, ppm)?;
+        written.push(filename);
+    }
+    Ok(written)
+}